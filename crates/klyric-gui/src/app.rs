@@ -86,10 +86,14 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
                     let file = rfd::AsyncFileDialog::new()
                         .add_filter(
                             "All Supported",
-                            &["klyric", "json", "ass", "ssa", "srt", "lrc"],
+                            &[
+                                "klyric", "json", "ass", "ssa", "srt", "lrc", "ttml", "txt", "kar",
+                                "mid",
+                            ],
                         )
                         .add_filter("KLyric Project", &["klyric", "json"])
-                        .add_filter("Subtitle", &["ass", "ssa", "srt", "lrc"])
+                        .add_filter("Subtitle", &["ass", "ssa", "srt", "lrc", "ttml"])
+                        .add_filter("Karaoke", &["txt", "kar", "mid"])
                         .pick_file()
                         .await;
                     file.map(|f| f.path().to_path_buf())
@@ -117,10 +121,15 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
 
             return Task::perform(
                 async move {
-                    match std::fs::read_to_string(&path_clone) {
+                    // Project files resolve their style imports relative to themselves
+                    if is_project_file {
+                        return klyric_renderer::load_document(&path_clone)
+                            .map_err(|e| e.to_string());
+                    }
+                    match std::fs::read(&path_clone) {
                         Ok(content) => {
                             let filename = path_clone.file_name().and_then(|s| s.to_str());
-                            klyric_renderer::importer::import_subtitle(&content, filename)
+                            klyric_renderer::importer::import_subtitle_bytes(&content, filename)
                                 .map_err(|e| e.to_string())
                         }
 
@@ -140,7 +149,7 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
             // Load audio if present
             if let Some(audio_path) = &doc.project.audio {
                 if let Some(am) = &mut state.audio_manager {
-                    if let Err(e) = am.load(audio_path) {
+                    if let Err(e) = am.load(doc.asset_path(audio_path)) {
                         log::error!("Failed to load audio: {}", e);
                     }
                 }
@@ -164,7 +173,7 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
 
         Message::SaveFile => {
             if let Some(ref doc) = state.document {
-                let json = serde_json::to_string_pretty(doc.as_ref()).unwrap_or_default();
+                let json = doc.to_json(true).unwrap_or_default();
                 let existing_path = state.file_path.clone();
 
                 return Task::perform(
@@ -231,6 +240,26 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
             }
         }
 
+        Message::ToggleTrackMute(id) => {
+            if let Some(doc) = &mut state.document {
+                let doc = Arc::make_mut(doc);
+                if let Some(track) = doc.tracks.get_mut(&id) {
+                    track.muted = !track.muted;
+                    state.preview_stale = true;
+                }
+            }
+        }
+
+        Message::ToggleTrackSolo(id) => {
+            if let Some(doc) = &mut state.document {
+                let doc = Arc::make_mut(doc);
+                if let Some(track) = doc.tracks.get_mut(&id) {
+                    track.solo = !track.solo;
+                    state.preview_stale = true;
+                }
+            }
+        }
+
         Message::Play => {
             log::info!("Starting playback. Duration: {}", state.playback.duration);
             state.playback.is_playing = true;
@@ -302,12 +331,13 @@ pub fn update(state: &mut AppState, message: Message) -> Task<Message> {
                     state.playback.current_time = 0.0;
                 }
                 update_preview(state);
-            } else if state.is_dirty && state.show_preview {
+            } else if (state.is_dirty || state.preview_stale) && state.show_preview {
                 // Real-time update for property changes when paused
                 // Only reset dirty flag if we actually sent a request (or worker is free)
                 if !state.pending_frame {
                     update_preview(state);
                     state.is_dirty = false;
+                    state.preview_stale = false;
                 }
             }
         }
@@ -1,4 +1,4 @@
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
@@ -85,6 +85,18 @@ impl AudioManager {
     }
 }
 
+/// Decode an audio file into interleaved samples for offline analysis.
+///
+/// Returns `(samples, channels, sample_rate)`.
+pub fn decode_samples<P: AsRef<Path>>(path: P) -> anyhow::Result<(Vec<f32>, u16, u32)> {
+    let file = File::open(path.as_ref())?;
+    let source = Decoder::new(BufReader::new(file))?;
+    let channels = u16::from(source.channels());
+    let sample_rate = u32::from(source.sample_rate());
+    let samples: Vec<f32> = source.collect();
+    Ok((samples, channels, sample_rate))
+}
+
 impl std::fmt::Debug for AudioManager {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AudioManager")
@@ -46,6 +46,11 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Directory holding user particle preset packs (`*.json`)
+    pub fn preset_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("klyric").join("presets"))
+    }
+
     fn config_path() -> Option<PathBuf> {
         dirs::config_dir().map(|d| d.join("klyric").join("config.json"))
     }
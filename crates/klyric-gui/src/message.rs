@@ -29,6 +29,10 @@ pub enum Message {
     Seek(f64),
     Tick,
 
+    // Track preview
+    ToggleTrackMute(String),
+    ToggleTrackSolo(String),
+
     // Timing edits
     SetCharStart(String),
     SetCharEnd(String),
@@ -46,6 +46,10 @@ pub struct AppState {
     /// Whether a frame render is currently pending (render throttling)
     pub pending_frame: bool,
 
+    /// Preview needs a new frame after a change that isn't saved (track
+    /// mute/solo)
+    pub preview_stale: bool,
+
     /// Currently selected effect name
     pub selected_effect: Option<String>,
 
@@ -112,37 +116,35 @@ impl AppState {
 
         for path in candidates {
             if path.exists() {
-                if let Ok(content) = std::fs::read_to_string(&path) {
-                    if let Ok(mut doc) = serde_json::from_str::<KLyricDocumentV2>(&content) {
-                        log::info!("Loaded sample project from {:?}", path);
-
-                        // Check for sample.wav in the same directory
-                        if let Some(parent) = path.parent() {
-                            let wav_path = parent.join("sample.wav");
-                            if wav_path.exists() {
-                                if let Ok(abs_wav) = std::fs::canonicalize(&wav_path) {
-                                    doc.project.audio = Some(abs_wav.to_string_lossy().to_string());
-                                }
-                            }
-                        }
-
-                        // Set initial selection
-                        if !doc.lines.is_empty() {
-                            selected_line = Some(0);
-                            if !doc.lines[0].chars.is_empty() {
-                                selected_char = Some(0);
+                if let Ok(mut doc) = klyric_renderer::load_document(&path) {
+                    log::info!("Loaded sample project from {:?}", path);
+
+                    // Check for sample.wav in the same directory
+                    if let Some(parent) = path.parent() {
+                        let wav_path = parent.join("sample.wav");
+                        if wav_path.exists() {
+                            if let Ok(abs_wav) = std::fs::canonicalize(&wav_path) {
+                                doc.project.audio = Some(abs_wav.to_string_lossy().to_string());
                             }
                         }
+                    }
 
-                        if let Ok(abs_path) = std::fs::canonicalize(&path) {
-                            file_path = Some(abs_path);
-                        } else {
-                            file_path = Some(path);
+                    // Set initial selection
+                    if !doc.lines.is_empty() {
+                        selected_line = Some(0);
+                        if !doc.lines[0].chars.is_empty() {
+                            selected_char = Some(0);
                         }
+                    }
 
-                        document = Some(Arc::new(doc));
-                        break;
+                    if let Ok(abs_path) = std::fs::canonicalize(&path) {
+                        file_path = Some(abs_path);
+                    } else {
+                        file_path = Some(path);
                     }
+
+                    document = Some(Arc::new(doc));
+                    break;
                 }
             }
         }
@@ -154,7 +156,7 @@ impl AppState {
         if let Some(doc) = &document {
             if let Some(audio_path) = &doc.project.audio {
                 if let Some(am) = &mut audio_manager {
-                    if let Err(e) = am.load(audio_path) {
+                    if let Err(e) = am.load(doc.asset_path(audio_path)) {
                         log::error!("Failed to load sample audio: {}", e);
                     }
                 }
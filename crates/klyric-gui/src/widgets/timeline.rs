@@ -2,7 +2,7 @@
 //! Redesigned with custom dark theme styling
 
 use iced::{
-    widget::{button, container, row, slider, text, Space},
+    widget::{button, container, row, slider, text, Row, Space},
     Alignment, Element, Length,
 };
 
@@ -69,9 +69,49 @@ pub fn view(state: &AppState) -> Element<'_, Message> {
         Space::new().width(Length::Fixed(16.0)),
         seek_slider,
         Space::new().width(Length::Fixed(16.0)),
+        track_toggles(state),
     ]
     .spacing(8)
     .padding([12, 16])
     .align_y(Alignment::Center)
     .into()
 }
+
+/// Mute/solo buttons for each track of the document, topmost first
+fn track_toggles(state: &AppState) -> Element<'_, Message> {
+    let Some(doc) = &state.document else {
+        return Space::new().into();
+    };
+    let mut tracks: Vec<_> = doc.tracks.iter().collect();
+    tracks.sort_by(|(a_id, a), (b_id, b)| b.z_index.cmp(&a.z_index).then(a_id.cmp(b_id)));
+
+    let toggle = |label: &'static str, on: bool, message: Message| {
+        button(text(label).size(11))
+            .style(if on {
+                theme::primary_button_style
+            } else {
+                theme::toolbar_button_style
+            })
+            .padding([4, 8])
+            .on_press(message)
+    };
+
+    Row::with_children(tracks.into_iter().map(|(id, track)| {
+        container(
+            row![
+                text(id.as_str())
+                    .size(12)
+                    .color(theme::colors::TEXT_SECONDARY),
+                toggle("M", track.muted, Message::ToggleTrackMute(id.clone())),
+                toggle("S", track.solo, Message::ToggleTrackSolo(id.clone())),
+            ]
+            .spacing(4)
+            .align_y(Alignment::Center),
+        )
+        .style(theme::card_style)
+        .padding([4, 8])
+        .into()
+    }))
+    .spacing(8)
+    .into()
+}
@@ -1,9 +1,12 @@
+use crate::config::AppConfig;
 use iced::widget::image;
 
 // use iced::futures::SinkExt; // Removed
+use klyric_renderer::audio::AudioAnalysis;
 use klyric_renderer::model::KLyricDocumentV2;
-use klyric_renderer::renderer::Renderer;
-use std::sync::{Arc, Mutex};
+use klyric_renderer::renderer::{RenderMode, Renderer};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use tokio::sync::mpsc as tokio_mpsc;
 
@@ -50,6 +53,11 @@ pub fn spawn() -> WorkerConnection {
     thread::spawn(move || {
         let mut renderer: Option<Renderer> = None;
         let mut last_size = (0, 0);
+        // Analysis of the current document's audio, keyed by resolved path.
+        // Decoding runs on its own thread so preview frames keep coming.
+        let mut audio_path: Option<PathBuf> = None;
+        let mut audio: Option<Arc<AudioAnalysis>> = None;
+        let (audio_tx, audio_rx) = mpsc::channel::<(PathBuf, Option<Arc<AudioAnalysis>>)>();
 
         while let Some(msg) = req_rx.blocking_recv() {
             match msg {
@@ -59,8 +67,42 @@ pub fn spawn() -> WorkerConnection {
                     width,
                     height,
                 } => {
+                    let path = doc.project.audio.as_deref().map(|p| doc.asset_path(p));
+                    if path != audio_path {
+                        audio_path = path.clone();
+                        audio = None;
+                        if let Some(r) = renderer.as_mut() {
+                            r.set_audio_analysis(None);
+                        }
+                        if let Some(path) = path {
+                            let tx = audio_tx.clone();
+                            thread::spawn(move || {
+                                let analysis = analyze_audio(&path);
+                                let _ = tx.send((path, analysis));
+                            });
+                        }
+                    }
+
+                    // Pick up finished analyses, unless the audio changed since
+                    while let Ok((path, analysis)) = audio_rx.try_recv() {
+                        if audio_path.as_ref() == Some(&path) {
+                            audio = analysis;
+                            if let Some(r) = renderer.as_mut() {
+                                r.set_audio_analysis(audio.clone());
+                            }
+                        }
+                    }
+
                     if renderer.is_none() || last_size != (width, height) {
-                        renderer = Some(Renderer::new(width, height));
+                        let mut r = Renderer::new(width, height);
+                        r.set_render_mode(RenderMode::Preview);
+                        if let Some(dir) = AppConfig::preset_dir() {
+                            if let Err(e) = r.load_preset_directory(&dir) {
+                                log::error!("Failed to load particle presets: {}", e);
+                            }
+                        }
+                        r.set_audio_analysis(audio.clone());
+                        renderer = Some(r);
                         last_size = (width, height);
                     }
 
@@ -86,6 +128,21 @@ pub fn spawn() -> WorkerConnection {
     }
 }
 
+/// Precompute the audio analysis used by audio-reactive effects
+fn analyze_audio(path: &Path) -> Option<Arc<AudioAnalysis>> {
+    match crate::audio::decode_samples(path) {
+        Ok((samples, channels, sample_rate)) => Some(Arc::new(AudioAnalysis::analyze(
+            &samples,
+            channels,
+            sample_rate,
+        ))),
+        Err(e) => {
+            log::error!("Failed to analyze audio {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
 // Subscription removed in favor of polling in app.rs
 // pub fn subscription(...) ...
 
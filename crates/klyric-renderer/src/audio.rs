@@ -0,0 +1,470 @@
+//! Offline audio analysis for audio-reactive effects
+//!
+//! The soundtrack (`project.audio`) is analyzed once into per-frame
+//! amplitude and frequency band levels. Rendering only looks levels up by
+//! time, so exports stay deterministic regardless of playback or frame order.
+//!
+//! Levels are exposed to expressions (`amplitude`, `bass`, `low_mid`, `mid`,
+//! `treble`) and to `ValueDriver::Audio`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Analysis frames per second used by [`AudioAnalysis::analyze`]
+pub const DEFAULT_FRAME_RATE: f32 = 60.0;
+
+/// FFT window size in samples
+const WINDOW_SIZE: usize = 2048;
+
+/// Frequency range (Hz) of each band, in `AudioBand` order after `Amplitude`
+const BAND_RANGES: [(f32, f32); 4] = [
+    (20.0, 150.0),
+    (150.0, 500.0),
+    (500.0, 2000.0),
+    (2000.0, 16000.0),
+];
+
+/// A single analyzed audio signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioBand {
+    /// Overall loudness (RMS)
+    Amplitude,
+    /// 20 - 150 Hz (kick drum, bass)
+    Bass,
+    /// 150 - 500 Hz
+    LowMid,
+    /// 500 - 2000 Hz (vocals)
+    Mid,
+    /// 2 - 16 kHz (hi-hats, cymbals)
+    Treble,
+}
+
+/// Normalized (0.0 - 1.0) audio levels at a point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevels {
+    pub amplitude: f32,
+    pub bass: f32,
+    pub low_mid: f32,
+    pub mid: f32,
+    pub treble: f32,
+}
+
+impl AudioLevels {
+    /// Level of a single band
+    pub fn get(&self, band: AudioBand) -> f32 {
+        match band {
+            AudioBand::Amplitude => self.amplitude,
+            AudioBand::Bass => self.bass,
+            AudioBand::LowMid => self.low_mid,
+            AudioBand::Mid => self.mid,
+            AudioBand::Treble => self.treble,
+        }
+    }
+
+    fn lerp(&self, other: &AudioLevels, t: f32) -> AudioLevels {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        AudioLevels {
+            amplitude: mix(self.amplitude, other.amplitude),
+            bass: mix(self.bass, other.bass),
+            low_mid: mix(self.low_mid, other.low_mid),
+            mid: mix(self.mid, other.mid),
+            treble: mix(self.treble, other.treble),
+        }
+    }
+}
+
+/// Precomputed audio levels sampled at a fixed frame rate.
+///
+/// Each band is normalized so its loudest frame is 1.0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioAnalysis {
+    /// Analysis frames per second
+    pub frame_rate: f32,
+    /// Levels per analysis frame
+    pub frames: Vec<AudioLevels>,
+}
+
+impl AudioAnalysis {
+    /// Analyze interleaved samples at [`DEFAULT_FRAME_RATE`]
+    pub fn analyze(samples: &[f32], channels: u16, sample_rate: u32) -> Self {
+        Self::analyze_with_rate(samples, channels, sample_rate, DEFAULT_FRAME_RATE)
+    }
+
+    /// Analyze interleaved samples at a custom analysis frame rate
+    pub fn analyze_with_rate(
+        samples: &[f32],
+        channels: u16,
+        sample_rate: u32,
+        frame_rate: f32,
+    ) -> Self {
+        let channels = channels.max(1) as usize;
+        let mono: Vec<f32> = samples
+            .chunks(channels)
+            .map(|c| c.iter().sum::<f32>() / c.len() as f32)
+            .collect();
+
+        if mono.is_empty() || sample_rate == 0 || frame_rate <= 0.0 {
+            return Self {
+                frame_rate,
+                frames: Vec::new(),
+            };
+        }
+
+        let hop = sample_rate as f32 / frame_rate;
+        let frame_count = (mono.len() as f32 / hop).ceil() as usize;
+
+        let window: Vec<f32> = (0..WINDOW_SIZE)
+            .map(|i| {
+                let x = i as f32 / (WINDOW_SIZE - 1) as f32;
+                0.5 - 0.5 * (2.0 * std::f32::consts::PI * x).cos()
+            })
+            .collect();
+        let twiddles: Vec<(f32, f32)> = (0..WINDOW_SIZE / 2)
+            .map(|k| {
+                let angle = -2.0 * std::f64::consts::PI * k as f64 / WINDOW_SIZE as f64;
+                (angle.cos() as f32, angle.sin() as f32)
+            })
+            .collect();
+
+        let bin_hz = sample_rate as f32 / WINDOW_SIZE as f32;
+        let nyquist_bin = WINDOW_SIZE / 2;
+        let band_bins: Vec<(usize, usize)> = BAND_RANGES
+            .iter()
+            .map(|(lo, hi)| {
+                let start = ((lo / bin_hz).floor() as usize).clamp(1, nyquist_bin);
+                let end = ((hi / bin_hz).ceil() as usize).clamp(start + 1, nyquist_bin + 1);
+                (start, end)
+            })
+            .collect();
+
+        let mut re = vec![0.0f32; WINDOW_SIZE];
+        let mut im = vec![0.0f32; WINDOW_SIZE];
+        let mut frames = Vec::with_capacity(frame_count);
+
+        for f in 0..frame_count {
+            let center = (f as f32 * hop) as isize;
+            let start = center - (WINDOW_SIZE / 2) as isize;
+
+            let mut sum_sq = 0.0f32;
+            for i in 0..WINDOW_SIZE {
+                let idx = start + i as isize;
+                let s = if idx >= 0 {
+                    mono.get(idx as usize).copied().unwrap_or(0.0)
+                } else {
+                    0.0
+                };
+                sum_sq += s * s;
+                re[i] = s * window[i];
+                im[i] = 0.0;
+            }
+
+            fft(&mut re, &mut im, &twiddles);
+
+            let band = |(start, end): (usize, usize)| {
+                let energy: f32 = (start..end).map(|k| re[k] * re[k] + im[k] * im[k]).sum();
+                (energy / (end - start) as f32).sqrt()
+            };
+
+            frames.push(AudioLevels {
+                amplitude: (sum_sq / WINDOW_SIZE as f32).sqrt(),
+                bass: band(band_bins[0]),
+                low_mid: band(band_bins[1]),
+                mid: band(band_bins[2]),
+                treble: band(band_bins[3]),
+            });
+        }
+
+        normalize(&mut frames);
+
+        Self { frame_rate, frames }
+    }
+
+    /// Decode and analyze a PCM or float WAV file from memory
+    pub fn from_wav(bytes: &[u8]) -> Result<Self> {
+        let (samples, channels, sample_rate) = decode_wav(bytes)?;
+        Ok(Self::analyze(&samples, channels, sample_rate))
+    }
+
+    /// Load an analysis from disk.
+    ///
+    /// `.json` files are read as a previously saved analysis; anything else
+    /// is decoded as WAV.
+    pub fn load(path: &Path) -> Result<Self> {
+        let is_json = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+
+        if is_json {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read audio analysis {}", path.display()))?;
+            Ok(Self::from_json(&content)?)
+        } else {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("Failed to read audio file {}", path.display()))?;
+            Self::from_wav(&bytes)
+                .with_context(|| format!("Failed to analyze audio file {}", path.display()))
+        }
+    }
+
+    /// Parse a saved analysis from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize to JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Analyzed duration in seconds
+    pub fn duration(&self) -> f64 {
+        if self.frame_rate <= 0.0 {
+            return 0.0;
+        }
+        self.frames.len() as f64 / self.frame_rate as f64
+    }
+
+    /// Levels at `time` seconds, interpolated between analysis frames.
+    /// Silence outside the analyzed range.
+    pub fn levels_at(&self, time: f64) -> AudioLevels {
+        if time < 0.0 || self.frames.is_empty() || self.frame_rate <= 0.0 {
+            return AudioLevels::default();
+        }
+        let pos = time * self.frame_rate as f64;
+        let idx = pos.floor() as usize;
+        if idx >= self.frames.len() {
+            return AudioLevels::default();
+        }
+        let next = (idx + 1).min(self.frames.len() - 1);
+        self.frames[idx].lerp(&self.frames[next], (pos - idx as f64) as f32)
+    }
+}
+
+/// Scale every band so its peak is 1.0
+fn normalize(frames: &mut [AudioLevels]) {
+    let mut peak = AudioLevels::default();
+    for f in frames.iter() {
+        peak.amplitude = peak.amplitude.max(f.amplitude);
+        peak.bass = peak.bass.max(f.bass);
+        peak.low_mid = peak.low_mid.max(f.low_mid);
+        peak.mid = peak.mid.max(f.mid);
+        peak.treble = peak.treble.max(f.treble);
+    }
+    let scale = |v: f32, p: f32| if p > 0.0 { v / p } else { 0.0 };
+    for f in frames.iter_mut() {
+        f.amplitude = scale(f.amplitude, peak.amplitude);
+        f.bass = scale(f.bass, peak.bass);
+        f.low_mid = scale(f.low_mid, peak.low_mid);
+        f.mid = scale(f.mid, peak.mid);
+        f.treble = scale(f.treble, peak.treble);
+    }
+}
+
+/// In-place iterative radix-2 FFT. `twiddles` holds `len / 2` roots of unity.
+fn fft(re: &mut [f32], im: &mut [f32], twiddles: &[(f32, f32)]) {
+    let n = re.len();
+
+    // Bit-reversal permutation
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let step = n / len;
+        for start in (0..n).step_by(len) {
+            for k in 0..half {
+                let (wr, wi) = twiddles[k * step];
+                let a = start + k;
+                let b = a + half;
+                let tr = re[b] * wr - im[b] * wi;
+                let ti = re[b] * wi + im[b] * wr;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Decode a RIFF/WAVE file into interleaved f32 samples.
+///
+/// Supports 8/16/24/32-bit integer PCM and 32/64-bit float.
+fn decode_wav(bytes: &[u8]) -> Result<(Vec<f32>, u16, u32)> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        bail!("Not a WAV file");
+    }
+
+    let u16_at = |b: &[u8], i: usize| u16::from_le_bytes([b[i], b[i + 1]]);
+    let u32_at = |b: &[u8], i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    let mut data: Option<&[u8]> = None;
+
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32_at(bytes, pos + 4) as usize;
+        let body_start = pos + 8;
+        let body_end = (body_start + size).min(bytes.len());
+        let body = &bytes[body_start..body_end];
+
+        match id {
+            b"fmt " => {
+                if body.len() < 16 {
+                    bail!("Truncated WAV format chunk");
+                }
+                let mut tag = u16_at(body, 0);
+                // WAVE_FORMAT_EXTENSIBLE: the real format is the sub-format GUID prefix
+                if tag == 0xFFFE && body.len() >= 26 {
+                    tag = u16_at(body, 24);
+                }
+                format = Some((tag, u16_at(body, 2), u32_at(body, 4), u16_at(body, 14)));
+            }
+            b"data" => data = Some(body),
+            _ => {}
+        }
+
+        // Chunks are word aligned
+        pos = body_start + size + (size & 1);
+    }
+
+    let (tag, channels, sample_rate, bits) =
+        format.ok_or_else(|| anyhow!("WAV file has no format chunk"))?;
+    let data = data.ok_or_else(|| anyhow!("WAV file has no data chunk"))?;
+
+    let samples: Vec<f32> = match (tag, bits) {
+        (1, 8) => data.iter().map(|&b| (b as f32 - 128.0) / 128.0).collect(),
+        (1, 16) => data
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]) as f32 / 32768.0)
+            .collect(),
+        (1, 24) => data
+            .chunks_exact(3)
+            .map(|c| (i32::from_le_bytes([0, c[0], c[1], c[2]]) >> 8) as f32 / 8_388_608.0)
+            .collect(),
+        (1, 32) => data
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f32 / 2_147_483_648.0)
+            .collect(),
+        (3, 32) => data
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect(),
+        (3, 64) => data
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]) as f32)
+            .collect(),
+        _ => bail!("Unsupported WAV encoding (format {}, {} bits)", tag, bits),
+    };
+
+    Ok((samples, channels, sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 44_100;
+
+    fn sine(freq: f32, seconds: f32) -> Vec<f32> {
+        let n = (RATE as f32 * seconds) as usize;
+        (0..n)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / RATE as f32).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_band_separation() {
+        // Low tone for the first half second, high tone for the second
+        let mut samples = sine(80.0, 0.5);
+        samples.extend(sine(6000.0, 0.5));
+        let analysis = AudioAnalysis::analyze(&samples, 1, RATE);
+
+        assert_eq!(analysis.frames.len(), 60);
+        let low = analysis.levels_at(0.2);
+        let high = analysis.levels_at(0.8);
+        assert!(low.bass > 0.9 && low.treble < 0.1, "{:?}", low);
+        assert!(high.treble > 0.9 && high.bass < 0.1, "{:?}", high);
+        assert!(low.amplitude > 0.9 && high.amplitude > 0.9);
+    }
+
+    #[test]
+    fn test_levels_interpolate_and_clamp() {
+        let analysis = AudioAnalysis {
+            frame_rate: 10.0,
+            frames: vec![
+                AudioLevels::default(),
+                AudioLevels {
+                    bass: 1.0,
+                    ..Default::default()
+                },
+            ],
+        };
+        assert!((analysis.levels_at(0.05).bass - 0.5).abs() < 1e-5);
+        assert_eq!(analysis.levels_at(-1.0), AudioLevels::default());
+        assert_eq!(analysis.levels_at(5.0), AudioLevels::default());
+        assert!((analysis.duration() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_wav_decode() {
+        let samples: Vec<i16> = vec![0, 16384, -16384, 32767];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&2u16.to_le_bytes()); // stereo
+        wav.extend_from_slice(&RATE.to_le_bytes());
+        wav.extend_from_slice(&(RATE * 4).to_le_bytes());
+        wav.extend_from_slice(&4u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+
+        let (decoded, channels, rate) = decode_wav(&wav).unwrap();
+        assert_eq!(channels, 2);
+        assert_eq!(rate, RATE);
+        assert_eq!(decoded.len(), 4);
+        assert!((decoded[1] - 0.5).abs() < 1e-4);
+        assert!((decoded[2] + 0.5).abs() < 1e-4);
+
+        assert!(decode_wav(b"not a wav file").is_err());
+    }
+
+    #[test]
+    fn test_json_roundtrip_is_deterministic() {
+        let samples = sine(440.0, 0.25);
+        let a = AudioAnalysis::analyze(&samples, 1, RATE);
+        let b = AudioAnalysis::analyze(&samples, 1, RATE);
+        assert_eq!(a, b);
+
+        let restored = AudioAnalysis::from_json(&a.to_json().unwrap()).unwrap();
+        assert_eq!(restored.frames.len(), a.frames.len());
+        assert!((restored.levels_at(0.1).mid - a.levels_at(0.1).mid).abs() < 1e-6);
+    }
+}
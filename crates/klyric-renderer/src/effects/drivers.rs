@@ -1,3 +1,4 @@
+use crate::audio::AudioLevels;
 use crate::model::modifiers::{DriverEasing, ValueDriver};
 
 // We might need to map DriverEasing to effect::Easing if possible,
@@ -7,6 +8,11 @@ pub struct DriverManager;
 
 impl DriverManager {
     pub fn evaluate(driver: &ValueDriver, time: f64) -> f32 {
+        Self::evaluate_with_audio(driver, time, &AudioLevels::default())
+    }
+
+    /// Evaluate a driver with the audio levels at `time`
+    pub fn evaluate_with_audio(driver: &ValueDriver, time: f64, audio: &AudioLevels) -> f32 {
         match driver {
             ValueDriver::Fixed { val } => *val,
             ValueDriver::Linear { start, end, ease } => {
@@ -46,10 +52,30 @@ impl DriverManager {
                 let idx = (time as f32 / interval).floor() as usize;
                 values[idx % values.len()]
             }
+            ValueDriver::Audio { band, base, amp } => base + amp * audio.get(*band),
             ValueDriver::Default => 0.0,
         }
     }
 
+    /// Evaluate a driver over a timed span: `Linear` runs across it from
+    /// `start` to `end`, the others take the seconds since `start`
+    pub fn evaluate_span(
+        driver: &ValueDriver,
+        time: f64,
+        start: f64,
+        end: f64,
+        audio: &AudioLevels,
+    ) -> f32 {
+        let t = match driver {
+            ValueDriver::Linear { .. } if end > start => {
+                ((time - start) / (end - start)).clamp(0.0, 1.0)
+            }
+            ValueDriver::Linear { .. } => 1.0,
+            _ => time - start,
+        };
+        Self::evaluate_with_audio(driver, t, audio)
+    }
+
     fn lerp(start: f32, end: f32, t: f32) -> f32 {
         start + (end - start) * t
     }
@@ -75,3 +101,32 @@ impl DriverManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_span() {
+        let audio = AudioLevels::default();
+        let at = |driver: &ValueDriver, time: f64| {
+            DriverManager::evaluate_span(driver, time, 10.0, 12.0, &audio)
+        };
+        let linear = ValueDriver::Linear {
+            start: -1.0,
+            end: 0.0,
+            ease: DriverEasing::Linear,
+        };
+        assert_eq!(at(&linear, 10.0), -1.0);
+        assert_eq!(at(&linear, 11.0), -0.5);
+        assert_eq!(at(&linear, 20.0), 0.0);
+
+        // Other drivers see the seconds since the span began
+        let step = ValueDriver::Step {
+            values: vec![1.0, 2.0],
+            interval: 1.0,
+        };
+        assert_eq!(at(&step, 10.5), 1.0);
+        assert_eq!(at(&step, 11.5), 2.0);
+    }
+}
@@ -1,4 +1,7 @@
-use super::model::{AnimatedValue, Easing, Effect, EffectType, RenderTransform, Transform};
+use super::model::{
+    AnimatedValue, Easing, Effect, EffectTrigger, EffectType, Keyframe, RenderTransform, Transform,
+};
+use crate::audio::AudioLevels;
 use crate::expressions::{EvaluationContext, ExpressionEvaluator};
 use evalexpr::Node;
 use std::collections::HashMap;
@@ -32,6 +35,10 @@ pub enum RenderProperty {
     AnchorX,
     AnchorY,
     HueShift,
+    Brightness,
+    Contrast,
+    Saturation,
+    Invert,
 }
 
 impl RenderProperty {
@@ -49,6 +56,10 @@ impl RenderProperty {
             Self::AnchorX => 1 << 9,
             Self::AnchorY => 1 << 10,
             Self::HueShift => 1 << 11,
+            Self::Brightness => 1 << 12,
+            Self::Contrast => 1 << 13,
+            Self::Saturation => 1 << 14,
+            Self::Invert => 1 << 15,
         }
     }
 
@@ -66,6 +77,10 @@ impl RenderProperty {
             "anchor_x" => Some(Self::AnchorX),
             "anchor_y" => Some(Self::AnchorY),
             "hue_shift" => Some(Self::HueShift),
+            "brightness" => Some(Self::Brightness),
+            "contrast" => Some(Self::Contrast),
+            "saturation" => Some(Self::Saturation),
+            "invert" => Some(Self::Invert),
             _ => None,
         }
     }
@@ -84,7 +99,9 @@ pub struct CompiledRenderOp {
 }
 
 use crate::effects::drivers::DriverManager;
-use crate::model::modifiers::{AppearMode, EffectLayer, Modifier, ScopeType, Selector};
+use crate::model::modifiers::{
+    AppearMode, EffectLayer, Modifier, ScopeType, Selector, ValueDriver,
+};
 
 pub mod drivers; // Added drivers module
 
@@ -403,6 +420,18 @@ impl EffectEngine {
                             });
                         }
                     }
+                    for (prop, s, e) in keyframe_color_adjusts(start_kf, end_kf) {
+                        let val = Self::lerp(s as f64, e as f64, segment_eased) as f32;
+                        apply_property_enum(hoisted_transform, prop, val);
+                        *hoisted_mask |= prop.mask_bit();
+
+                        if (dynamic_seen_mask & prop.mask_bit()) != 0 {
+                            ops.push(CompiledRenderOp {
+                                prop,
+                                value: RenderValueOp::Constant(val),
+                            });
+                        }
+                    }
                 }
                 _ => {}
             }
@@ -478,6 +507,7 @@ impl EffectEngine {
                         progress: eased_progress,
                         index: trigger_context.char_index,
                         count: trigger_context.char_count,
+                        audio: trigger_context.audio,
                         ..Default::default()
                     };
 
@@ -611,6 +641,13 @@ impl EffectEngine {
                             Self::lerp(s as f64, e as f64, segment_eased),
                         );
                     }
+                    for (prop, s, e) in keyframe_color_adjusts(start_kf, end_kf) {
+                        apply_property_enum_to_transform(
+                            &mut final_transform,
+                            prop,
+                            Self::lerp(s as f64, e as f64, segment_eased) as f32,
+                        );
+                    }
                 }
                 _ => {}
             }
@@ -646,6 +683,7 @@ impl EffectEngine {
                         progress: eased_progress,
                         index: trigger_context.char_index,
                         count: trigger_context.char_count,
+                        audio: trigger_context.audio,
                         ..Default::default()
                     };
 
@@ -775,6 +813,13 @@ impl EffectEngine {
                             Self::lerp(s as f64, e as f64, segment_eased),
                         );
                     }
+                    for (prop, s, e) in keyframe_color_adjusts(start_kf, end_kf) {
+                        apply_property_enum(
+                            &mut transform,
+                            prop,
+                            Self::lerp(s as f64, e as f64, segment_eased) as f32,
+                        );
+                    }
                 }
                 _ => {}
             }
@@ -788,10 +833,14 @@ impl EffectEngine {
         true // simplified for now
     }
 
-    /// Calculate progress of an effect (0.0 to 1.0)
+    /// Calculate progress of an effect (0.0 to 1.0).
+    /// Exit effects are timed to finish when the line ends.
     pub fn calculate_progress(current_time: f64, effect: &Effect, ctx: &TriggerContext) -> f64 {
         let duration = effect.duration.unwrap_or(ctx.end_time - ctx.start_time);
-        let start = ctx.start_time + effect.delay;
+        let start = match effect.trigger {
+            EffectTrigger::Exit => ctx.end_time - duration + effect.delay,
+            _ => ctx.start_time + effect.delay,
+        };
 
         if current_time < start {
             return -1.0;
@@ -857,6 +906,7 @@ impl EffectEngine {
             Selector::Scope(ScopeType::Line) => {}
             Selector::Scope(ScopeType::Char) => {}
             Selector::TimeRange { .. } => {}
+            Selector::Section(_) => {}
             // Pattern, Word, Syllable, Text, Tag are potentially index-dependent or context-sensitive
             _ => return false,
         }
@@ -872,6 +922,7 @@ impl EffectEngine {
                 Modifier::Jitter(_) => {}
                 Modifier::Perspect(_) => {}
                 Modifier::Color(_) => {} // Safe (no-op or global)
+                Modifier::Adjust(_) => {}
                 // Wave, Appear, Spacing, Emit are potentially index-dependent
                 _ => return false,
             }
@@ -957,6 +1008,7 @@ impl EffectEngine {
             }
             Selector::Text { .. } => true, // Not implemented fully without text access
             Selector::Tag(_) => true,      // Tagging system not passed in context
+            Selector::Section(name) => ctx.section == Some(name.as_str()),
         }
     }
 
@@ -964,7 +1016,7 @@ impl EffectEngine {
         transform: &mut Transform,
         modifier: &Modifier,
         time: f64,
-        _ctx: &TriggerContext,
+        ctx: &TriggerContext,
     ) {
         // Time usage: ValueDrivers usually take absolute time or relative?
         // Let's use `time` (which is usually `current_time` from render loop).
@@ -973,14 +1025,14 @@ impl EffectEngine {
 
         match modifier {
             Modifier::Move(p) => {
-                let x = DriverManager::evaluate(&p.x, time);
-                let y = DriverManager::evaluate(&p.y, time);
+                let x = DriverManager::evaluate_with_audio(&p.x, time, &ctx.audio);
+                let y = DriverManager::evaluate_with_audio(&p.y, time, &ctx.audio);
                 transform.x = Some(transform.x.unwrap_or(0.0) + x);
                 transform.y = Some(transform.y.unwrap_or(0.0) + y);
             }
             Modifier::Scale(p) => {
-                let sx = DriverManager::evaluate(&p.x, time);
-                let sy = DriverManager::evaluate(&p.y, time);
+                let sx = DriverManager::evaluate_with_audio(&p.x, time, &ctx.audio);
+                let sy = DriverManager::evaluate_with_audio(&p.y, time, &ctx.audio);
                 // Multiplicative or additive? Usually multiplicative for scale.
                 // But Driver returns a value.
                 // E.g. Sine(1.0, 0.1) -> varies 0.9 to 1.1.
@@ -989,7 +1041,7 @@ impl EffectEngine {
                 transform.scale_y = Some(transform.scale_y.unwrap_or(1.0) * sy);
             }
             Modifier::Rotate(p) => {
-                let angle = DriverManager::evaluate(&p.angle, time);
+                let angle = DriverManager::evaluate_with_audio(&p.angle, time, &ctx.audio);
                 transform.rotation = Some(transform.rotation.unwrap_or(0.0) + angle);
                 // pivot not handled in Transform struct yet
             }
@@ -1004,15 +1056,37 @@ impl EffectEngine {
                 }
             }
             Modifier::Fade(p) => {
-                let alpha = DriverManager::evaluate(&p.value, time);
+                let alpha = DriverManager::evaluate_with_audio(&p.value, time, &ctx.audio);
                 transform.opacity = Some(transform.opacity.unwrap_or(1.0) * alpha);
             }
             Modifier::Blur(sigma) => {
                 transform.blur = Some(*sigma);
             }
+            Modifier::Adjust(p) => {
+                let value = |driver: &Option<ValueDriver>| {
+                    driver
+                        .as_ref()
+                        .map(|d| DriverManager::evaluate_with_audio(d, time, &ctx.audio))
+                };
+                if let Some(v) = value(&p.hue_shift) {
+                    transform.hue_shift = Some(transform.hue_shift_val() + v);
+                }
+                if let Some(v) = value(&p.brightness) {
+                    transform.brightness = Some(transform.brightness_val() * v);
+                }
+                if let Some(v) = value(&p.contrast) {
+                    transform.contrast = Some(transform.contrast_val() * v);
+                }
+                if let Some(v) = value(&p.saturation) {
+                    transform.saturation = Some(transform.saturation_val() * v);
+                }
+                if let Some(v) = value(&p.invert) {
+                    transform.invert = Some(transform.invert_val() + v);
+                }
+            }
             Modifier::Jitter(p) => {
-                let amt = DriverManager::evaluate(&p.amount, time);
-                let speed = DriverManager::evaluate(&p.speed, time);
+                let amt = DriverManager::evaluate_with_audio(&p.amount, time, &ctx.audio);
+                let speed = DriverManager::evaluate_with_audio(&p.speed, time, &ctx.audio);
                 // Pseudo random based on time * speed
                 let off_x = (time as f32 * speed).sin() * amt;
                 let off_y = (time as f32 * speed * 1.5).cos() * amt;
@@ -1021,8 +1095,8 @@ impl EffectEngine {
                 transform.y = Some(transform.y.unwrap_or(0.0) + off_y);
             }
             Modifier::Wave(p) => {
-                let freq = DriverManager::evaluate(&p.freq, time);
-                let amp = DriverManager::evaluate(&p.amp, time);
+                let freq = DriverManager::evaluate_with_audio(&p.freq, time, &ctx.audio);
+                let amp = DriverManager::evaluate_with_audio(&p.amp, time, &ctx.audio);
                 // Wave usually needs position index (spatial).
                 // But Modifier receives Context?
                 // Wait, Modifier applies to Transform. Transform is for a single char?
@@ -1035,9 +1109,9 @@ impl EffectEngine {
                 // `Modifier::Wave` is explicitly explicit for this.
 
                 // If `ctx` has index, we use it.
-                let idx = _ctx.char_index.unwrap_or(0) as f32;
+                let idx = ctx.char_index.unwrap_or(0) as f32;
                 // speed?
-                let speed = DriverManager::evaluate(&p.speed, time);
+                let speed = DriverManager::evaluate_with_audio(&p.speed, time, &ctx.audio);
 
                 let phase = idx * 0.5; // arbitary spatial freq
                 let y = (time as f32 * speed + phase * freq).sin() * amp;
@@ -1046,7 +1120,7 @@ impl EffectEngine {
             }
             Modifier::Appear(p) => {
                 // Logic likely similar to Typewriter but driven by `progress`.
-                let progress = DriverManager::evaluate(&p.progress, time);
+                let progress = DriverManager::evaluate_with_audio(&p.progress, time, &ctx.audio);
                 match p.mode {
                     AppearMode::Fade => {
                         transform.opacity = Some(progress.clamp(0.0, 1.0));
@@ -1062,54 +1136,67 @@ impl EffectEngine {
         transform: &mut RenderTransform,
         modifier: &Modifier,
         time: f64,
-        _ctx: &TriggerContext,
+        ctx: &TriggerContext,
     ) {
         match modifier {
             Modifier::Move(p) => {
-                let x = DriverManager::evaluate(&p.x, time);
-                let y = DriverManager::evaluate(&p.y, time);
+                let x = DriverManager::evaluate_with_audio(&p.x, time, &ctx.audio);
+                let y = DriverManager::evaluate_with_audio(&p.y, time, &ctx.audio);
                 transform.x += x;
                 transform.y += y;
             }
             Modifier::Scale(p) => {
-                let sx = DriverManager::evaluate(&p.x, time);
-                let sy = DriverManager::evaluate(&p.y, time);
+                let sx = DriverManager::evaluate_with_audio(&p.x, time, &ctx.audio);
+                let sy = DriverManager::evaluate_with_audio(&p.y, time, &ctx.audio);
                 transform.scale_x *= sx;
                 transform.scale_y *= sy;
             }
             Modifier::Rotate(p) => {
-                let angle = DriverManager::evaluate(&p.angle, time);
+                let angle = DriverManager::evaluate_with_audio(&p.angle, time, &ctx.audio);
                 transform.rotation += angle;
             }
             Modifier::Color(_p) => {
                 // Not supported in Transform/RenderTransform for now
             }
             Modifier::Fade(p) => {
-                let alpha = DriverManager::evaluate(&p.value, time);
+                let alpha = DriverManager::evaluate_with_audio(&p.value, time, &ctx.audio);
                 transform.opacity *= alpha;
             }
             Modifier::Blur(sigma) => {
                 transform.blur = *sigma;
             }
+            Modifier::Adjust(p) => {
+                let value = |driver: &Option<ValueDriver>| {
+                    driver
+                        .as_ref()
+                        .map(|d| DriverManager::evaluate_with_audio(d, time, &ctx.audio))
+                };
+                // Hue and inversion add up, the factors multiply
+                transform.hue_shift += value(&p.hue_shift).unwrap_or(0.0);
+                transform.brightness *= value(&p.brightness).unwrap_or(1.0);
+                transform.contrast *= value(&p.contrast).unwrap_or(1.0);
+                transform.saturation *= value(&p.saturation).unwrap_or(1.0);
+                transform.invert += value(&p.invert).unwrap_or(0.0);
+            }
             Modifier::Jitter(p) => {
-                let amt = DriverManager::evaluate(&p.amount, time);
-                let speed = DriverManager::evaluate(&p.speed, time);
+                let amt = DriverManager::evaluate_with_audio(&p.amount, time, &ctx.audio);
+                let speed = DriverManager::evaluate_with_audio(&p.speed, time, &ctx.audio);
                 let off_x = (time as f32 * speed).sin() * amt;
                 let off_y = (time as f32 * speed * 1.5).cos() * amt;
                 transform.x += off_x;
                 transform.y += off_y;
             }
             Modifier::Wave(p) => {
-                let freq = DriverManager::evaluate(&p.freq, time);
-                let amp = DriverManager::evaluate(&p.amp, time);
-                let idx = _ctx.char_index.unwrap_or(0) as f32;
-                let speed = DriverManager::evaluate(&p.speed, time);
+                let freq = DriverManager::evaluate_with_audio(&p.freq, time, &ctx.audio);
+                let amp = DriverManager::evaluate_with_audio(&p.amp, time, &ctx.audio);
+                let idx = ctx.char_index.unwrap_or(0) as f32;
+                let speed = DriverManager::evaluate_with_audio(&p.speed, time, &ctx.audio);
                 let phase = idx * 0.5;
                 let y = (time as f32 * speed + phase * freq).sin() * amp;
                 transform.y += y;
             }
             Modifier::Appear(p) => {
-                let progress = DriverManager::evaluate(&p.progress, time);
+                let progress = DriverManager::evaluate_with_audio(&p.progress, time, &ctx.audio);
                 match p.mode {
                     AppearMode::Fade => {
                         transform.opacity = progress.clamp(0.0, 1.0);
@@ -1127,16 +1214,20 @@ fn text_cos(val: f64) -> f64 {
 }
 
 #[derive(Clone)]
-pub struct TriggerContext {
+pub struct TriggerContext<'a> {
     pub start_time: f64,
     pub end_time: f64,
     pub current_time: f64,
     pub active: bool,
     pub char_index: Option<usize>,
     pub char_count: Option<usize>,
+    /// Audio levels at `current_time`
+    pub audio: AudioLevels,
+    /// Name of the section the line belongs to
+    pub section: Option<&'a str>,
 }
 
-impl Default for TriggerContext {
+impl Default for TriggerContext<'_> {
     fn default() -> Self {
         Self {
             start_time: 0.0,
@@ -1145,6 +1236,8 @@ impl Default for TriggerContext {
             active: false,
             char_index: None,
             char_count: None,
+            audio: AudioLevels::default(),
+            section: None,
         }
     }
 }
@@ -1162,6 +1255,11 @@ fn apply_property(transform: &mut Transform, prop: &str, value: f64) {
         "rotation" => transform.rotation = Some(value as f32),
         "blur" => transform.blur = Some(value as f32),
         "glitch_offset" | "glitch" => transform.glitch_offset = Some(value as f32),
+        "hue_shift" => transform.hue_shift = Some(value as f32),
+        "brightness" => transform.brightness = Some(value as f32),
+        "contrast" => transform.contrast = Some(value as f32),
+        "saturation" => transform.saturation = Some(value as f32),
+        "invert" => transform.invert = Some(value as f32),
         _ => {}
     }
 }
@@ -1180,6 +1278,10 @@ fn apply_property_to_render(transform: &mut RenderTransform, prop: &str, value:
         "anchor_x" => transform.anchor_x = value as f32,
         "anchor_y" => transform.anchor_y = value as f32,
         "hue_shift" => transform.hue_shift = value as f32,
+        "brightness" => transform.brightness = value as f32,
+        "contrast" => transform.contrast = value as f32,
+        "saturation" => transform.saturation = value as f32,
+        "invert" => transform.invert = value as f32,
         _ => {}
     }
 }
@@ -1198,12 +1300,60 @@ fn apply_property_enum(transform: &mut RenderTransform, prop: RenderProperty, va
         RenderProperty::AnchorX => transform.anchor_x = value,
         RenderProperty::AnchorY => transform.anchor_y = value,
         RenderProperty::HueShift => transform.hue_shift = value,
+        RenderProperty::Brightness => transform.brightness = value,
+        RenderProperty::Contrast => transform.contrast = value,
+        RenderProperty::Saturation => transform.saturation = value,
+        RenderProperty::Invert => transform.invert = value,
     }
 }
 
+/// `apply_property_enum` for a sparse `Transform`; `Scale` sets both axes,
+/// like `apply_property`
+fn apply_property_enum_to_transform(transform: &mut Transform, prop: RenderProperty, value: f32) {
+    match prop {
+        RenderProperty::Opacity => transform.opacity = Some(value),
+        RenderProperty::Scale => {
+            transform.scale = Some(value);
+            transform.scale_x = Some(value);
+            transform.scale_y = Some(value);
+        }
+        RenderProperty::ScaleX => transform.scale_x = Some(value),
+        RenderProperty::ScaleY => transform.scale_y = Some(value),
+        RenderProperty::X => transform.x = Some(value),
+        RenderProperty::Y => transform.y = Some(value),
+        RenderProperty::Rotation => transform.rotation = Some(value),
+        RenderProperty::Blur => transform.blur = Some(value),
+        RenderProperty::GlitchOffset => transform.glitch_offset = Some(value),
+        RenderProperty::AnchorX => transform.anchor_x = Some(value),
+        RenderProperty::AnchorY => transform.anchor_y = Some(value),
+        RenderProperty::HueShift => transform.hue_shift = Some(value),
+        RenderProperty::Brightness => transform.brightness = Some(value),
+        RenderProperty::Contrast => transform.contrast = Some(value),
+        RenderProperty::Saturation => transform.saturation = Some(value),
+        RenderProperty::Invert => transform.invert = Some(value),
+    }
+}
+
+/// Color adjustments set on both ends of a keyframe segment
+fn keyframe_color_adjusts(
+    start: &Keyframe,
+    end: &Keyframe,
+) -> impl Iterator<Item = (RenderProperty, f32, f32)> {
+    [
+        (RenderProperty::HueShift, start.hue_shift, end.hue_shift),
+        (RenderProperty::Brightness, start.brightness, end.brightness),
+        (RenderProperty::Contrast, start.contrast, end.contrast),
+        (RenderProperty::Saturation, start.saturation, end.saturation),
+        (RenderProperty::Invert, start.invert, end.invert),
+    ]
+    .into_iter()
+    .filter_map(|(prop, s, e)| Some((prop, s?, e?)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::modifiers::{AdjustParams, EffectLayer, Selector};
     use crate::model::{AnimatedValue, Effect, EffectTrigger, EffectType};
     use std::collections::HashMap;
 
@@ -1628,7 +1778,7 @@ mod tests {
         }
     }
 
-    fn make_context(start: f64, end: f64) -> TriggerContext {
+    fn make_context(start: f64, end: f64) -> TriggerContext<'static> {
         TriggerContext {
             start_time: start,
             end_time: end,
@@ -1636,6 +1786,8 @@ mod tests {
             active: true,
             char_index: None,
             char_count: None,
+            audio: Default::default(),
+            section: None,
         }
     }
 
@@ -1680,6 +1832,31 @@ mod tests {
         assert!(approx_eq(progress, 1.0, 1e-9));
     }
 
+    #[test]
+    fn test_calculate_progress_exit() {
+        // Exit effects end with the line
+        let mut effect = make_effect(Some(2.0), 0.0);
+        effect.trigger = EffectTrigger::Exit;
+        let ctx = make_context(0.0, 10.0);
+
+        assert!(EffectEngine::calculate_progress(7.5, &effect, &ctx) < 0.0);
+        assert!(approx_eq(
+            EffectEngine::calculate_progress(8.0, &effect, &ctx),
+            0.0,
+            1e-9
+        ));
+        assert!(approx_eq(
+            EffectEngine::calculate_progress(9.0, &effect, &ctx),
+            0.5,
+            1e-9
+        ));
+        assert!(approx_eq(
+            EffectEngine::calculate_progress(10.0, &effect, &ctx),
+            1.0,
+            1e-9
+        ));
+    }
+
     // ============================================================================
     // TRANSFORM APPLICATION TESTS (3 tests)
     // ============================================================================
@@ -2002,6 +2179,52 @@ mod tests {
         // Hoisted transform should have value
         assert!(approx_eq(hoisted.opacity as f64, 0.5, 1e-6));
     }
+
+    #[test]
+    fn test_color_adjust_keyframes_and_modifier() {
+        let effect: Effect = serde_json::from_value(serde_json::json!({
+            "type": "keyframe",
+            "duration": 2.0,
+            "keyframes": [
+                { "time": 0.0, "hueShift": 0.0, "saturation": 1.0 },
+                { "time": 1.0, "hueShift": 180.0, "saturation": 0.0 }
+            ]
+        }))
+        .unwrap();
+        let ctx = make_context(0.0, 10.0);
+
+        let base = RenderTransform::default();
+        let result = EffectEngine::apply_to_render_transform(1.0, base, &[&effect], &ctx);
+        assert!(approx_eq(result.hue_shift as f64, 90.0, 1e-4));
+        assert!(approx_eq(result.saturation as f64, 0.5, 1e-4));
+        assert!(approx_eq(result.brightness as f64, 1.0, 1e-6));
+
+        let result = EffectEngine::compute_transform(1.0, Transform::default(), &[&effect], &ctx);
+        assert!(approx_eq(result.hue_shift.unwrap() as f64, 90.0, 1e-4));
+        assert!(approx_eq(result.saturation.unwrap() as f64, 0.5, 1e-4));
+
+        // Adjust adds hue and invert, multiplies the factors
+        let layer = EffectLayer {
+            selector: Selector::All,
+            modifiers: vec![Modifier::Adjust(AdjustParams {
+                hue_shift: Some(ValueDriver::Fixed { val: 30.0 }),
+                brightness: Some(ValueDriver::Fixed { val: 0.5 }),
+                invert: Some(ValueDriver::Fixed { val: 1.0 }),
+                ..Default::default()
+            })],
+        };
+        assert!(EffectEngine::is_layer_global(&layer));
+        let base = RenderTransform {
+            hue_shift: 10.0,
+            brightness: 2.0,
+            ..Default::default()
+        };
+        let result = EffectEngine::apply_layers_to_render(0.0, base, &[layer], &ctx);
+        assert!(approx_eq(result.hue_shift as f64, 40.0, 1e-4));
+        assert!(approx_eq(result.brightness as f64, 1.0, 1e-4));
+        assert!(approx_eq(result.invert as f64, 1.0, 1e-4));
+        assert!(approx_eq(result.saturation as f64, 1.0, 1e-6));
+    }
 }
 
 #[cfg(test)]
@@ -2058,6 +2281,21 @@ mod tests_global_detection {
             },
             safe_mods.clone()
         )));
+        assert!(EffectEngine::is_layer_global(&make_layer(
+            Selector::Section("chorus".into()),
+            safe_mods.clone()
+        )));
+    }
+
+    #[test]
+    fn test_section_selector() {
+        let selector = Selector::Section("chorus".into());
+        let mut ctx = TriggerContext::default();
+        assert!(!EffectEngine::matches_selector(&selector, &ctx));
+        ctx.section = Some("chorus");
+        assert!(EffectEngine::matches_selector(&selector, &ctx));
+        ctx.section = Some("verse");
+        assert!(!EffectEngine::matches_selector(&selector, &ctx));
     }
 
     #[test]
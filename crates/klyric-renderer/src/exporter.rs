@@ -0,0 +1,870 @@
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::model::{
+    document::KLyricDocumentV2,
+    layout::{Anchor, PositionValue},
+    line::Line,
+    style::{FontStyle, Style},
+};
+use crate::style::StyleResolver;
+use crate::utils::parse_hex_color;
+
+/// Subtitle / lyric formats a document can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Line-level LRC (`[mm:ss.xx]text`)
+    Lrc,
+    /// Enhanced LRC with `<mm:ss.xx>` word timestamps
+    EnhancedLrc,
+    /// SubRip
+    Srt,
+    /// WebVTT with `<c>` karaoke timestamps
+    WebVtt,
+    /// Advanced SubStation Alpha with `\kf` karaoke
+    Ass,
+    /// TTML with word `<span>`s, `ttm:agent` singers and `x-bg` background vocals
+    Ttml,
+}
+
+impl ExportFormat {
+    /// Guess the format from a file name extension
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let ext = Path::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())?
+            .to_lowercase();
+        match ext.as_str() {
+            "lrc" => Some(Self::EnhancedLrc),
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::WebVtt),
+            "ass" | "ssa" => Some(Self::Ass),
+            "ttml" => Some(Self::Ttml),
+            _ => None,
+        }
+    }
+
+    /// Default file extension (without the dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Lrc | Self::EnhancedLrc => "lrc",
+            Self::Srt => "srt",
+            Self::WebVtt => "vtt",
+            Self::Ass => "ass",
+            Self::Ttml => "ttml",
+        }
+    }
+}
+
+/// Karaoke tag used for ASS syllables
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssKaraoke {
+    /// `\k`: instant highlight
+    K,
+    /// `\kf`: left-to-right fill sweep
+    #[default]
+    Kf,
+}
+
+/// Export a KLyricV2 Document to subtitle file content
+pub fn export_subtitle(doc: &KLyricDocumentV2, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Lrc => export_lrc(doc, false),
+        ExportFormat::EnhancedLrc => export_lrc(doc, true),
+        ExportFormat::Srt => export_srt(doc),
+        ExportFormat::WebVtt => export_vtt(doc),
+        ExportFormat::Ass => export_ass(doc, AssKaraoke::default()),
+        ExportFormat::Ttml => export_ttml(doc),
+    }
+}
+
+/// A run of characters highlighted together (a word, or a single CJK character)
+#[derive(Debug, Clone, PartialEq)]
+struct Segment {
+    text: String,
+    start: f64,
+    end: f64,
+    /// Ruby over the segment's text
+    ruby: Option<String>,
+}
+
+/// Full text of a line
+fn line_text(line: &Line) -> String {
+    match &line.text {
+        Some(text) if !text.is_empty() => text.clone(),
+        _ => line.chars.iter().map(|c| c.char.as_str()).collect(),
+    }
+}
+
+/// Characters that are timed individually rather than grouped into words
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        // Kana, CJK Extension A, CJK Unified, Hangul syllables, CJK Compatibility
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF
+    )
+}
+
+/// Group a line's chars into timed segments.
+///
+/// A segment ends after whitespace, and CJK characters and chars with ruby
+/// are segments of their own. Lines without chars become a single segment spanning the line.
+fn line_segments(line: &Line) -> Vec<Segment> {
+    if line.chars.is_empty() {
+        return vec![Segment {
+            text: line_text(line),
+            start: line.start,
+            end: line.end,
+            ruby: None,
+        }];
+    }
+
+    let mut segments: Vec<Segment> = Vec::new();
+    // Whether the next char may join the last segment
+    let mut open = false;
+    for ch in &line.chars {
+        let cjk = ch.char.chars().any(is_cjk) || ch.ruby.is_some();
+        let whitespace = !ch.char.is_empty() && ch.char.chars().all(char::is_whitespace);
+
+        match segments.last_mut() {
+            Some(seg) if whitespace || (open && !cjk) => {
+                seg.text.push_str(&ch.char);
+                seg.end = seg.end.max(ch.end);
+            }
+            _ => segments.push(Segment {
+                text: ch.char.clone(),
+                start: ch.start,
+                end: ch.end,
+                ruby: ch.ruby.clone(),
+            }),
+        }
+        // Whitespace closes the segment it trails; CJK characters stand alone
+        open = !whitespace && !cjk;
+    }
+    segments
+}
+
+/// Round seconds to whole units of `1 / per_second`
+fn time_units(seconds: f64, per_second: f64) -> u64 {
+    (seconds.max(0.0) * per_second).round() as u64
+}
+
+/// `mm:ss.xx`
+fn lrc_time(seconds: f64) -> String {
+    let cs = time_units(seconds, 100.0);
+    format!("{:02}:{:02}.{:02}", cs / 6000, (cs / 100) % 60, cs % 100)
+}
+
+/// `hh:mm:ss` + `sep` + `mmm`
+fn hms_millis(seconds: f64, sep: char) -> String {
+    let ms = time_units(seconds, 1000.0);
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        sep,
+        ms % 1000
+    )
+}
+
+/// `h:mm:ss.cc`
+fn ass_time(seconds: f64) -> String {
+    let cs = time_units(seconds, 100.0);
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        cs / 360_000,
+        (cs / 6000) % 60,
+        (cs / 100) % 60,
+        cs % 100
+    )
+}
+
+/// Export to LRC. With `word_timing`, each word carries an enhanced LRC
+/// `<mm:ss.xx>` timestamp and the line closes with its end time.
+pub fn export_lrc(doc: &KLyricDocumentV2, word_timing: bool) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "[ti:{}]", doc.project.title);
+    if let Some(artist) = doc.project.artist.as_deref().filter(|a| !a.is_empty()) {
+        let _ = writeln!(out, "[ar:{}]", artist);
+    }
+    if let Some(album) = doc.project.album.as_deref().filter(|a| !a.is_empty()) {
+        let _ = writeln!(out, "[al:{}]", album);
+    }
+    let length = time_units(doc.project.duration, 1.0);
+    let _ = writeln!(out, "[length:{:02}:{:02}]", length / 60, length % 60);
+
+    for (i, line) in doc.lines.iter().enumerate() {
+        let _ = write!(out, "[{}]", lrc_time(line.start));
+
+        let segments = line_segments(line);
+        if word_timing && !line.chars.is_empty() {
+            for seg in &segments {
+                let _ = write!(out, "<{}>{}", lrc_time(seg.start), seg.text);
+            }
+            let end = segments.last().map(|s| s.end).unwrap_or(line.end);
+            let _ = write!(out, "<{}>", lrc_time(end));
+        } else {
+            out.push_str(line_text(line).trim());
+        }
+        out.push('\n');
+
+        // LRC has no end times: clear the screen when there is a pause before the next line
+        let next_start = doc.lines.get(i + 1).map(|l| l.start);
+        if next_start.is_none_or(|next| next - line.end > 0.5) {
+            let _ = writeln!(out, "[{}]", lrc_time(line.end));
+        }
+    }
+
+    out
+}
+
+/// Export to SubRip
+pub fn export_srt(doc: &KLyricDocumentV2) -> String {
+    let mut out = String::new();
+    for (i, line) in doc.lines.iter().enumerate() {
+        let _ = writeln!(out, "{}", i + 1);
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            hms_millis(line.start, ','),
+            hms_millis(line.end, ',')
+        );
+        let _ = writeln!(out, "{}", line_text(line).trim());
+        out.push('\n');
+    }
+    out
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Export to WebVTT. Lines with word timing get karaoke timestamps:
+/// `<c>first</c><00:00:01.500><c>second</c>`.
+pub fn export_vtt(doc: &KLyricDocumentV2) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for line in &doc.lines {
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            hms_millis(line.start, '.'),
+            hms_millis(line.end, '.')
+        );
+
+        let segments = line_segments(line);
+        if segments.len() > 1 {
+            for (i, seg) in segments.iter().enumerate() {
+                // The first segment starts with the cue; timestamps must lie inside it
+                if i > 0 && seg.start > line.start && seg.start < line.end {
+                    let _ = write!(out, "<{}>", hms_millis(seg.start, '.'));
+                }
+                let _ = write!(out, "<c>{}</c>", escape_vtt(&seg.text));
+            }
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "{}", escape_vtt(line_text(line).trim()));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `&HAABBGGRR` (ASS alpha is inverted: 00 = opaque)
+fn ass_color(color: Option<&str>, fallback: (u8, u8, u8, u8)) -> String {
+    let (r, g, b, a) = color.and_then(parse_hex_color).unwrap_or(fallback);
+    format!("&H{:02X}{:02X}{:02X}{:02X}", 255 - a, b, g, r)
+}
+
+/// Numpad alignment used by `\an` and the style Alignment field
+fn ass_alignment(anchor: &Anchor) -> u8 {
+    match anchor {
+        Anchor::BottomLeft => 1,
+        Anchor::BottomCenter => 2,
+        Anchor::BottomRight => 3,
+        Anchor::CenterLeft => 4,
+        Anchor::Center => 5,
+        Anchor::CenterRight => 6,
+        Anchor::TopLeft => 7,
+        Anchor::TopCenter => 8,
+        Anchor::TopRight => 9,
+    }
+}
+
+/// ASS names may not contain commas
+fn ass_name(name: &str) -> String {
+    name.replace(',', ";")
+}
+
+fn ass_style_line(name: &str, style: &Style) -> String {
+    let font = style.font.clone().unwrap_or_default();
+    let fill =
+        |state: Option<&crate::model::style::FillStroke>| state.and_then(|fs| fs.fill.clone());
+    let colors = style.colors.as_ref();
+    let inactive = fill(colors.and_then(|c| c.inactive.as_ref()));
+    let active = fill(colors.and_then(|c| c.active.as_ref()))
+        .or_else(|| fill(colors.and_then(|c| c.complete.as_ref())));
+    let stroke = style.stroke.clone().unwrap_or_default();
+    let shadow = style.shadow.as_ref();
+
+    let shadow_depth = shadow
+        .map(|s| s.x_or_default().abs().max(s.y_or_default().abs()))
+        .unwrap_or(0.0);
+    let bold = if font.weight_or_default() >= 600 {
+        -1
+    } else {
+        0
+    };
+    let italic = if matches!(font.style_or_default(), FontStyle::Italic) {
+        -1
+    } else {
+        0
+    };
+
+    format!(
+        "Style: {},{},{},{},{},{},{},{},{},0,0,100,100,{},0,1,{},{},5,0,0,0,1",
+        ass_name(name),
+        ass_name(&font.family_or_default()),
+        font.size_or_default().round(),
+        // Primary = sung, Secondary = not yet sung
+        ass_color(active.as_deref(), (255, 255, 0, 255)),
+        ass_color(inactive.as_deref(), (136, 136, 136, 255)),
+        ass_color(stroke.color.as_deref(), (0, 0, 0, 255)),
+        ass_color(shadow.and_then(|s| s.color.as_deref()), (0, 0, 0, 128)),
+        bold,
+        italic,
+        font.letter_spacing_or_default(),
+        stroke.width_or_default(),
+        shadow_depth,
+    )
+}
+
+/// Per-line override tags for position and line-level style overrides
+fn ass_line_overrides(doc: &KLyricDocumentV2, line: &Line) -> String {
+    let mut tags = String::new();
+
+    if let Some(pos) = &line.position {
+        let resolve = |v: &Option<PositionValue>, size: u32| match v {
+            Some(PositionValue::Pixels(px)) => *px,
+            Some(PositionValue::Percentage(p)) => p * size as f32,
+            None => size as f32 / 2.0,
+        };
+        let res = &doc.project.resolution;
+        let _ = write!(
+            tags,
+            "\\an{}\\pos({},{})",
+            ass_alignment(&pos.anchor),
+            resolve(&pos.x, res.width).round(),
+            resolve(&pos.y, res.height).round()
+        );
+    }
+    if let Some(font) = &line.font {
+        if let Some(family) = &font.family {
+            let _ = write!(tags, "\\fn{}", family);
+        }
+        if let Some(size) = font.size {
+            let _ = write!(tags, "\\fs{}", size.round());
+        }
+    }
+    if let Some(stroke) = &line.stroke {
+        if let Some(width) = stroke.width {
+            let _ = write!(tags, "\\bord{}", width);
+        }
+        if let Some((r, g, b, _)) = stroke.color.as_deref().and_then(parse_hex_color) {
+            let _ = write!(tags, "\\3c&H{:02X}{:02X}{:02X}&", b, g, r);
+        }
+    }
+    if let Some(shadow) = &line.shadow {
+        let depth = shadow.x_or_default().abs().max(shadow.y_or_default().abs());
+        let _ = write!(tags, "\\shad{}", depth);
+    }
+
+    if tags.is_empty() {
+        tags
+    } else {
+        format!("{{{}}}", tags)
+    }
+}
+
+/// Escape text for an ASS event. Braces become `\{`/`\}` and line breaks
+/// `\N`. A backslash that would start an escape (or run into the next
+/// override block) is kept literal by a word joiner after it.
+fn escape_ass(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => {
+                out.push(c);
+                if matches!(chars.peek(), None | Some('N' | 'n' | 'h' | '{' | '}')) {
+                    out.push('\u{2060}');
+                }
+            }
+            '\n' => out.push_str("\\N"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Export to ASS with one style per document style (resolved through
+/// `extends`) and karaoke tags for lines with char timing.
+pub fn export_ass(doc: &KLyricDocumentV2, karaoke: AssKaraoke) -> String {
+    let mut out = String::new();
+    let res = &doc.project.resolution;
+
+    out.push_str("[Script Info]\n");
+    let _ = writeln!(out, "Title: {}", doc.project.title);
+    if let Some(artist) = doc.project.artist.as_deref().filter(|a| !a.is_empty()) {
+        let _ = writeln!(out, "Artist: {}", artist);
+    }
+    out.push_str("ScriptType: v4.00+\n");
+    let _ = writeln!(out, "PlayResX: {}", res.width);
+    let _ = writeln!(out, "PlayResY: {}", res.height);
+    out.push_str("WrapStyle: 2\nScaledBorderAndShadow: yes\n\n");
+
+    // Every defined style plus any referenced by lines, in a stable order
+    let mut names: BTreeSet<String> = doc.styles.keys().cloned().collect();
+    for line in &doc.lines {
+        names.insert(doc.line_style(line).to_string());
+    }
+
+    out.push_str("[V4+ Styles]\n");
+    out.push_str("Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n");
+    let resolver = StyleResolver::new(doc);
+    for name in &names {
+        let _ = writeln!(out, "{}", ass_style_line(name, &resolver.resolve(name)));
+    }
+    out.push('\n');
+
+    let tag = match karaoke {
+        AssKaraoke::K => "k",
+        AssKaraoke::Kf => "kf",
+    };
+
+    out.push_str("[Events]\n");
+    out.push_str(
+        "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for line in &doc.lines {
+        let style = doc.line_style(line);
+        let mut text = ass_line_overrides(doc, line);
+
+        // Untimed chars (e.g. plain imported dialogue) need no karaoke tags
+        if line.chars.iter().all(|c| c.end <= c.start) {
+            text.push_str(&escape_ass(line_text(line).trim()));
+        } else {
+            let segments = line_segments(line);
+            let start_cs = time_units(line.start, 100.0);
+            let first_cs = segments
+                .first()
+                .map(|s| time_units(s.start, 100.0))
+                .unwrap_or(start_cs);
+            // Lead-in before the first syllable
+            if first_cs > start_cs {
+                let _ = write!(text, "{{\\{}{}}}", tag, first_cs - start_cs);
+            }
+            for (i, seg) in segments.iter().enumerate() {
+                // Each syllable lasts until the next one starts, covering gaps
+                let seg_start = time_units(seg.start, 100.0);
+                let seg_end = segments
+                    .get(i + 1)
+                    .map(|next| time_units(next.start, 100.0))
+                    .unwrap_or_else(|| time_units(seg.end, 100.0));
+                let _ = write!(text, "{{\\{}{}}}", tag, seg_end.saturating_sub(seg_start));
+                match &seg.ruby {
+                    // Karaoke furigana, with trailing whitespace after the ruby
+                    Some(ruby) => {
+                        let base = seg.text.trim_end();
+                        let _ = write!(
+                            text,
+                            "{}|{}{}",
+                            escape_ass(base),
+                            escape_ass(ruby),
+                            escape_ass(&seg.text[base.len()..])
+                        );
+                    }
+                    None => text.push_str(&escape_ass(&seg.text)),
+                }
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "Dialogue: 0,{},{},{},{},0,0,0,,{}",
+            ass_time(line.start),
+            ass_time(line.end),
+            ass_name(style),
+            line.singer.as_deref().map(ass_name).unwrap_or_default(),
+            text
+        );
+    }
+
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    escape_vtt(text).replace('"', "&quot;")
+}
+
+/// Style name used for background vocal lines (matches the importer)
+const TTML_BACKGROUND_STYLE: &str = "background";
+
+/// Whether `name` can be used as an `xml:id`
+fn is_ncname(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Write a line's content as `<span>`s, keeping trailing spaces outside them
+fn write_ttml_spans(out: &mut String, line: &Line) {
+    if line.chars.is_empty() {
+        out.push_str(&escape_xml(line_text(line).trim()));
+        return;
+    }
+    for seg in line_segments(line) {
+        let word = seg.text.trim_end();
+        if !word.is_empty() {
+            let _ = write!(
+                out,
+                "<span begin=\"{}\" end=\"{}\">{}</span>",
+                hms_millis(seg.start, '.'),
+                hms_millis(seg.end, '.'),
+                escape_xml(word)
+            );
+        }
+        if word.len() < seg.text.len() {
+            out.push(' ');
+        }
+    }
+}
+
+/// Export to TTML. Singers, or for lines without one styles other than
+/// `base`, become `ttm:agent`s, and
+/// lines using the `background` style are nested as `ttm:role="x-bg"` spans
+/// inside the line they overlap.
+pub fn export_ttml(doc: &KLyricDocumentV2) -> String {
+    let is_background = |line: &Line| doc.line_style(line) == TTML_BACKGROUND_STYLE;
+    let agent_key = |line: &Line| {
+        line.singer
+            .as_deref()
+            .or_else(|| Some(doc.line_style(line)).filter(|s| *s != "base"))
+            .map(str::to_string)
+    };
+
+    // Agent ids in order of first appearance
+    let mut agents: Vec<(String, String)> = Vec::new();
+    for line in doc.lines.iter().filter(|l| !is_background(l)) {
+        let Some(key) = agent_key(line) else {
+            continue;
+        };
+        if !agents.iter().any(|(k, _)| *k == key) {
+            let id = if is_ncname(&key) {
+                key.clone()
+            } else {
+                format!("v{}", agents.len() + 1)
+            };
+            agents.push((key, id));
+        }
+    }
+    let agent_of = |line: &Line| {
+        let key = agent_key(line)?;
+        agents
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, id)| id.clone())
+    };
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<tt xmlns=\"http://www.w3.org/ns/ttml\" xmlns:ttm=\"http://www.w3.org/ns/ttml#metadata\">\n");
+    out.push_str("  <head>\n    <metadata>\n");
+    let _ = writeln!(
+        out,
+        "      <ttm:title>{}</ttm:title>",
+        escape_xml(&doc.project.title)
+    );
+    for (_, id) in &agents {
+        let _ = writeln!(out, "      <ttm:agent type=\"person\" xml:id=\"{}\"/>", id);
+    }
+    out.push_str("    </metadata>\n  </head>\n");
+    let _ = writeln!(
+        out,
+        "  <body dur=\"{}\">\n    <div>",
+        hms_millis(doc.project.duration, '.')
+    );
+
+    let lines: Vec<&Line> = doc.lines.iter().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        // Background lines that start within this one are nested in it
+        let mut backgrounds = Vec::new();
+        if !is_background(line) {
+            while let Some(next) = lines.get(i).filter(|l| is_background(l)) {
+                if next.start > line.end {
+                    break;
+                }
+                backgrounds.push(*next);
+                i += 1;
+            }
+        }
+
+        let _ = write!(
+            out,
+            "      <p begin=\"{}\" end=\"{}\"",
+            hms_millis(line.start, '.'),
+            hms_millis(line.end, '.')
+        );
+        if let Some(agent) = agent_of(line) {
+            let _ = write!(out, " ttm:agent=\"{}\"", agent);
+        }
+        out.push('>');
+
+        if is_background(line) {
+            // Orphaned background line: keep its role on a wrapping span
+            out.push_str("<span ttm:role=\"x-bg\">");
+            write_ttml_spans(&mut out, line);
+            out.push_str("</span>");
+        } else {
+            write_ttml_spans(&mut out, line);
+        }
+        for bg in backgrounds {
+            out.push_str("<span ttm:role=\"x-bg\">");
+            write_ttml_spans(&mut out, bg);
+            out.push_str("</span>");
+        }
+        out.push_str("</p>\n");
+    }
+
+    out.push_str("    </div>\n  </body>\n</tt>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::importer::import_subtitle;
+    use crate::model::line::Char;
+
+    fn char_at(c: &str, start: f64, end: f64) -> Char {
+        Char {
+            char: c.to_string(),
+            start,
+            end,
+            style: None,
+            font: None,
+            stroke: None,
+            shadow: None,
+            effects: vec![],
+            transform: None,
+            pitch: None,
+            ruby: None,
+            singer: None,
+        }
+    }
+
+    fn doc() -> KLyricDocumentV2 {
+        let mut doc = import_subtitle("[00:01.00]placeholder", Some("a.lrc")).unwrap();
+        doc.project.title = "Song".to_string();
+        doc.project.artist = Some("Band".to_string());
+        doc.lines = vec![
+            Line {
+                start: 1.0,
+                end: 3.0,
+                text: Some("Hi you".to_string()),
+                style: Some("base".to_string()),
+                chars: vec![
+                    char_at("H", 1.0, 1.25),
+                    char_at("i", 1.25, 1.5),
+                    char_at(" ", 1.5, 1.5),
+                    char_at("y", 2.0, 2.3),
+                    char_at("o", 2.3, 2.6),
+                    char_at("u", 2.6, 3.0),
+                ],
+                ..Default::default()
+            },
+            Line {
+                start: 3.5,
+                end: 4.5,
+                text: Some("你好".to_string()),
+                chars: vec![char_at("你", 3.5, 4.0), char_at("好", 4.0, 4.5)],
+                ..Default::default()
+            },
+        ];
+        doc
+    }
+
+    #[test]
+    fn test_segments() {
+        let doc = doc();
+        let segments = line_segments(&doc.lines[0]);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "Hi ");
+        assert_eq!(segments[1].text, "you");
+        assert_eq!(segments[1].start, 2.0);
+        assert_eq!(segments[1].end, 3.0);
+
+        // CJK characters are timed individually
+        assert_eq!(line_segments(&doc.lines[1]).len(), 2);
+    }
+
+    #[test]
+    fn test_export_lrc() {
+        let doc = doc();
+        let lrc = export_lrc(&doc, false);
+        assert!(lrc.contains("[ti:Song]\n[ar:Band]\n"));
+        assert!(lrc.contains("[00:01.00]Hi you\n"));
+        assert!(lrc.contains("[00:04.50]\n"));
+
+        let enhanced = export_lrc(&doc, true);
+        assert!(enhanced.contains("[00:01.00]<00:01.00>Hi <00:02.00>you<00:03.00>\n"));
+
+        // Enhanced LRC round-trips through the importer
+        let back = import_subtitle(&enhanced, Some("song.lrc")).unwrap();
+        assert_eq!(back.lines.len(), 2);
+        assert_eq!(back.lines[0].text.as_deref(), Some("Hi you"));
+        assert_eq!(back.lines[0].end, 3.0);
+        assert_eq!(back.lines[0].chars[3].start, 2.0);
+    }
+
+    #[test]
+    fn test_export_srt() {
+        let srt = export_srt(&doc());
+        assert!(srt.starts_with("1\n00:00:01,000 --> 00:00:03,000\nHi you\n\n2\n"));
+
+        let back = import_subtitle(&srt, Some("song.srt")).unwrap();
+        assert_eq!(back.lines.len(), 2);
+        assert_eq!(back.lines[1].start, 3.5);
+    }
+
+    #[test]
+    fn test_export_vtt() {
+        let vtt = export_vtt(&doc());
+        assert!(vtt.starts_with("WEBVTT\n\n00:00:01.000 --> 00:00:03.000\n"));
+        assert!(vtt.contains("<c>Hi </c><00:00:02.000><c>you</c>\n"));
+        assert!(vtt.contains("<c>你</c><00:00:04.000><c>好</c>"));
+    }
+
+    #[test]
+    fn test_export_ass() {
+        let mut doc = doc();
+        doc.lines[1].position = Some(crate::model::layout::Position {
+            x: Some(PositionValue::Percentage(0.5)),
+            y: Some(PositionValue::Pixels(900.0)),
+            anchor: Anchor::BottomCenter,
+        });
+        let ass = export_ass(&doc, AssKaraoke::Kf);
+
+        assert!(ass.contains("PlayResX: 1920\n"));
+        // Importer default style: 72px bold, yellow when sung, grey before.
+        // Its #00000080 shadow becomes ASS alpha 7F.
+        assert!(ass.contains(
+            "Style: base,Noto Sans SC,72,&H0000FFFF,&H00888888,&H00000000,&H7F000000,-1,0,"
+        ));
+        assert!(ass
+            .contains("Dialogue: 0,0:00:01.00,0:00:03.00,base,,0,0,0,,{\\kf100}Hi {\\kf100}you\n"));
+        assert!(ass.contains("{\\an2\\pos(960,900)}{\\kf50}你{\\kf50}好"));
+
+        let k = export_ass(&doc, AssKaraoke::K);
+        assert!(k.contains("{\\k100}Hi {\\k100}you"));
+
+        // Karaoke timing survives the importer
+        let back = import_subtitle(&k, Some("song.ass")).unwrap();
+        assert_eq!(back.lines[0].text.as_deref(), Some("Hi you"));
+        assert_eq!(back.lines[0].chars[3].start, 2.0);
+
+        // Ruby round-trips as karaoke furigana
+        doc.lines[1].chars[0].ruby = Some("nǐ".to_string());
+        let ass = export_ass(&doc, AssKaraoke::Kf);
+        assert!(ass.contains("{\\kf50}你|nǐ{\\kf50}好"));
+        let back = import_subtitle(&ass, Some("song.ass")).unwrap();
+        assert_eq!(back.lines[1].chars[0].ruby.as_deref(), Some("nǐ"));
+
+        // Singers are written as the actor
+        doc.lines[0].singer = Some("lead".to_string());
+        let ass = export_ass(&doc, AssKaraoke::Kf);
+        assert!(ass.contains("Dialogue: 0,0:00:01.00,0:00:03.00,base,lead,0,0,0,,"));
+        let back = import_subtitle(&ass, Some("song.ass")).unwrap();
+        assert_eq!(back.lines[0].singer.as_deref(), Some("lead"));
+        assert!(back.singers["lead"].color.is_none());
+    }
+
+    #[test]
+    fn test_export_ass_escapes_text() {
+        let mut doc = doc();
+        doc.lines.truncate(1);
+        doc.lines[0].chars.clear();
+        doc.lines[0].text = Some("{a}\\N\nb\\".to_string());
+        let ass = export_ass(&doc, AssKaraoke::Kf);
+
+        // Braces can't open a block, `\N` stays literal, the newline breaks
+        assert!(ass.contains(",,\\{a\\}\\\u{2060}N\\Nb\\\u{2060}\n"));
+
+        let back = import_subtitle(&ass, Some("song.ass")).unwrap();
+        assert_eq!(back.lines[0].text.as_deref(), Some("{a}\\N b\\"));
+    }
+
+    #[test]
+    fn test_export_ttml() {
+        let mut doc = doc();
+        doc.lines[0].style = Some("lead".to_string());
+        doc.lines[1].style = Some("second voice".to_string());
+        doc.lines.insert(
+            1,
+            Line {
+                start: 2.0,
+                end: 2.8,
+                text: Some("(ah)".to_string()),
+                style: Some("background".to_string()),
+                chars: vec![char_at("(ah)", 2.0, 2.8)],
+                ..Default::default()
+            },
+        );
+        let ttml = export_ttml(&doc);
+
+        assert!(ttml.contains("<ttm:title>Song</ttm:title>"));
+        assert!(ttml.contains("<ttm:agent type=\"person\" xml:id=\"lead\"/>"));
+        assert!(ttml.contains("<ttm:agent type=\"person\" xml:id=\"v2\"/>"));
+        assert!(ttml.contains(
+            "<p begin=\"00:00:01.000\" end=\"00:00:03.000\" ttm:agent=\"lead\"><span begin=\"00:00:01.000\" end=\"00:00:01.500\">Hi</span> <span begin=\"00:00:02.000\" end=\"00:00:03.000\">you</span><span ttm:role=\"x-bg\"><span begin=\"00:00:02.000\" end=\"00:00:02.800\">(ah)</span></span></p>"
+        ));
+
+        // Round trip: agents become singers, x-bg becomes a background line
+        let back = import_subtitle(&ttml, Some("song.ttml")).unwrap();
+        assert_eq!(back.project.title, "Song");
+        assert_eq!(back.lines.len(), 3);
+        assert_eq!(back.lines[0].text.as_deref(), Some("Hi you"));
+        assert_eq!(back.lines[0].singer.as_deref(), Some("lead"));
+        assert_eq!(back.lines[0].chars[3].start, 2.0);
+        assert_eq!(back.lines[1].style.as_deref(), Some("background"));
+        assert_eq!(back.lines[2].singer.as_deref(), Some("v2"));
+        assert!(back.singers["lead"].color.is_some());
+        assert!(back.styles.contains_key("background"));
+    }
+
+    #[test]
+    fn test_format_from_filename() {
+        assert_eq!(
+            ExportFormat::from_filename("a/b/song.VTT"),
+            Some(ExportFormat::WebVtt)
+        );
+        assert_eq!(ExportFormat::from_filename("song.txt"), None);
+        assert_eq!(ExportFormat::Ass.extension(), "ass");
+        assert_eq!(
+            ExportFormat::from_filename("song.ttml"),
+            Some(ExportFormat::Ttml)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::audio::AudioLevels;
 use anyhow::{anyhow, Result};
 use evalexpr::{
     build_operator_tree, eval_with_context, Context, DefaultNumericTypes, EvalexprError,
@@ -14,6 +15,8 @@ pub struct EvaluationContext {
     pub count: Option<usize>,
     pub char_width: Option<f64>,
     pub char_height: Option<f64>,
+    /// Audio levels at `t` (silent when no analysis is loaded)
+    pub audio: AudioLevels,
 }
 
 impl Default for EvaluationContext {
@@ -27,6 +30,7 @@ impl Default for EvaluationContext {
             count: None,
             char_width: None,
             char_height: None,
+            audio: AudioLevels::default(),
         }
     }
 }
@@ -41,6 +45,12 @@ pub struct FastEvaluationContext {
     count: Option<Value>,
     char_width: Option<Value>,
     char_height: Option<Value>,
+    // Audio levels
+    amplitude: Value,
+    bass: Value,
+    low_mid: Value,
+    mid: Value,
+    treble: Value,
     // Cached constants to return references to
     pi: Value,
     e: Value,
@@ -57,6 +67,11 @@ impl FastEvaluationContext {
             count: ctx.count.map(|v| Value::Int(v as i64)),
             char_width: ctx.char_width.map(Value::Float),
             char_height: ctx.char_height.map(Value::Float),
+            amplitude: Value::Float(ctx.audio.amplitude as f64),
+            bass: Value::Float(ctx.audio.bass as f64),
+            low_mid: Value::Float(ctx.audio.low_mid as f64),
+            mid: Value::Float(ctx.audio.mid as f64),
+            treble: Value::Float(ctx.audio.treble as f64),
             pi: Value::Float(std::f64::consts::PI),
             e: Value::Float(std::f64::consts::E),
         }
@@ -78,6 +93,14 @@ impl FastEvaluationContext {
         }
     }
 
+    pub fn set_audio(&mut self, audio: &AudioLevels) {
+        self.amplitude = Value::Float(audio.amplitude as f64);
+        self.bass = Value::Float(audio.bass as f64);
+        self.low_mid = Value::Float(audio.low_mid as f64);
+        self.mid = Value::Float(audio.mid as f64);
+        self.treble = Value::Float(audio.treble as f64);
+    }
+
     pub fn get_index_raw(&self) -> Option<i64> {
         self.index.as_ref().and_then(|v| {
             if let Value::Int(i) = v {
@@ -102,6 +125,11 @@ impl Context for FastEvaluationContext {
             "count" => self.count.as_ref(),
             "char_width" => self.char_width.as_ref(),
             "char_height" => self.char_height.as_ref(),
+            "amplitude" => Some(&self.amplitude),
+            "bass" => Some(&self.bass),
+            "low_mid" => Some(&self.low_mid),
+            "mid" => Some(&self.mid),
+            "treble" => Some(&self.treble),
             "PI" | "math::consts::PI" => Some(&self.pi),
             "E" | "math::consts::E" => Some(&self.e),
             _ => None,
@@ -170,8 +198,15 @@ impl ExpressionEvaluator {
 
     /// Verify if an expression string is valid
     pub fn validate(expression: &str) -> bool {
-        // Try parsing with dummy context
-        let ctx = EvaluationContext::default();
+        // Try parsing with a dummy context where every variable is bound,
+        // so only syntax errors and unknown names fail
+        let ctx = EvaluationContext {
+            index: Some(1),
+            count: Some(2),
+            char_width: Some(1.0),
+            char_height: Some(1.0),
+            ..Default::default()
+        };
         Self::evaluate(expression, &ctx).is_ok()
     }
 }
@@ -254,4 +289,42 @@ mod tests {
         let val_e = ExpressionEvaluator::evaluate("E", &ctx).unwrap();
         assert!((val_e - std::f64::consts::E).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_audio_variables() {
+        let ctx = EvaluationContext {
+            audio: AudioLevels {
+                bass: 0.5,
+                treble: 0.25,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            ExpressionEvaluator::evaluate("10 + bass * 20", &ctx).unwrap(),
+            20.0
+        );
+
+        let mut fast_ctx = FastEvaluationContext::new(&ctx);
+        let node = ExpressionEvaluator::compile("treble + amplitude").unwrap();
+        assert_eq!(
+            ExpressionEvaluator::evaluate_node_fast(&node, &fast_ctx).unwrap(),
+            0.25
+        );
+        fast_ctx.set_audio(&AudioLevels::default());
+        assert_eq!(
+            ExpressionEvaluator::evaluate_node_fast(&node, &fast_ctx).unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(ExpressionEvaluator::validate(
+            "index * 0.1 + char_width / count"
+        ));
+        assert!(ExpressionEvaluator::validate("5 + bass * 40"));
+        assert!(!ExpressionEvaluator::validate("1 +"));
+        assert!(!ExpressionEvaluator::validate("unknown_var * 2"));
+    }
 }
@@ -0,0 +1,1202 @@
+//! Advanced SubStation Alpha (`.ass`/`.ssa`) import
+//!
+//! `[V4+ Styles]` become document styles and the common override tags map
+//! onto line and char fields: `\pos`/`\an` to the position, `\fn`/`\fs`/`\b`/`\i`
+//! to the font, `\bord`/`\3c` to the stroke, `\shad`/`\4c` to the shadow and
+//! `\c`/`\2c` to a derived color style. `\fad` and `\move` become transition
+//! effects and `\kf`/`\K`/`\ko` a karaoke wipe. Karaoke furigana
+//! (`{\k}明日|あした`) becomes char ruby, and the event's `Name` (actor) its
+//! singer. Sizes and coordinates are scaled
+//! from the script's `PlayResX`/`PlayResY` to the 1920x1080 document.
+
+use anyhow::Result;
+use std::collections::HashMap;
+
+use super::{push_syllable, state_colors, CharFormat, LineFormat, ParsedLyric, ParsedSyllable};
+use crate::model::{
+    effect::{AnimatedValue, Direction, Effect, EffectTrigger, EffectType, KaraokeMode},
+    layout::{Align, Anchor, Layout, Position, PositionValue, Transform},
+    style::{Font, FontStyle, Shadow, Stroke, Style},
+};
+
+/// Document size imported scripts are scaled to
+const TARGET_WIDTH: f32 = 1920.0;
+const TARGET_HEIGHT: f32 = 1080.0;
+
+/// Effect given to lines timed with `\kf`, `\K` or `\ko`
+pub(super) const KARAOKE_WIPE_EFFECT: &str = "assKaraokeWipe";
+
+/// Event fields when the script has no `Format:` line
+const DEFAULT_EVENT_FORMAT: &str = "layer,start,end,style,name,marginl,marginr,marginv,effect,text";
+
+/// A parsed script: lines plus the styles and effects they reference
+pub(super) struct AssScript {
+    pub lyrics: Vec<ParsedLyric>,
+    pub metadata: HashMap<String, String>,
+    pub styles: HashMap<String, Style>,
+    pub effects: HashMap<String, Effect>,
+}
+
+/// A style line, in script coordinates
+#[derive(Debug, Clone)]
+struct AssStyle {
+    font: String,
+    size: f32,
+    primary: String,
+    secondary: String,
+    outline_color: String,
+    back_color: String,
+    weight: u32,
+    italic: bool,
+    scale_x: f32,
+    scale_y: f32,
+    spacing: f32,
+    angle: f32,
+    outline: f32,
+    shadow: f32,
+    alignment: u8,
+    margin_l: f32,
+    margin_r: f32,
+    margin_v: f32,
+}
+
+impl Default for AssStyle {
+    /// Values renderers assume for fields a style leaves out
+    fn default() -> Self {
+        Self {
+            font: "Arial".to_string(),
+            size: 18.0,
+            primary: "#FFFFFF".to_string(),
+            secondary: "#FF0000".to_string(),
+            outline_color: "#000000".to_string(),
+            back_color: "#000000".to_string(),
+            weight: 400,
+            italic: false,
+            scale_x: 100.0,
+            scale_y: 100.0,
+            spacing: 0.0,
+            angle: 0.0,
+            outline: 2.0,
+            shadow: 2.0,
+            alignment: 2,
+            margin_l: 10.0,
+            margin_r: 10.0,
+            margin_v: 10.0,
+        }
+    }
+}
+
+impl AssStyle {
+    /// Parse a `Style:` line against the section's `Format:` fields
+    fn parse(fields: &[&str], format: &[String], legacy: bool) -> (String, Self) {
+        let get = |key: &str| {
+            format
+                .iter()
+                .position(|f| f == key)
+                .and_then(|i| fields.get(i))
+                .map(|v| v.trim())
+        };
+        let num = |key: &str| get(key).and_then(|v| v.parse::<f32>().ok());
+        let color = |key: &str| get(key).and_then(parse_color);
+
+        let mut style = Self::default();
+        if let Some(font) = get("fontname") {
+            style.font = font.to_string();
+        }
+        style.size = num("fontsize").unwrap_or(style.size);
+        style.primary = color("primarycolour").unwrap_or(style.primary);
+        style.secondary = color("secondarycolour").unwrap_or(style.secondary);
+        // SSA v4 calls the outline color "TertiaryColour"
+        style.outline_color = color("outlinecolour")
+            .or_else(|| color("tertiarycolour"))
+            .unwrap_or(style.outline_color);
+        style.back_color = color("backcolour").unwrap_or(style.back_color);
+        style.weight = num("bold").map(font_weight).unwrap_or(style.weight);
+        style.italic = num("italic").is_some_and(|v| v != 0.0);
+        style.scale_x = num("scalex").unwrap_or(style.scale_x);
+        style.scale_y = num("scaley").unwrap_or(style.scale_y);
+        style.spacing = num("spacing").unwrap_or(style.spacing);
+        style.angle = num("angle").unwrap_or(style.angle);
+        style.outline = num("outline").unwrap_or(style.outline);
+        style.shadow = num("shadow").unwrap_or(style.shadow);
+        if let Some(alignment) = num("alignment") {
+            style.alignment = if legacy {
+                legacy_alignment(alignment as u8)
+            } else {
+                (alignment as u8).clamp(1, 9)
+            };
+        }
+        style.margin_l = num("marginl").unwrap_or(style.margin_l);
+        style.margin_r = num("marginr").unwrap_or(style.margin_r);
+        style.margin_v = num("marginv").unwrap_or(style.margin_v);
+
+        let name = get("name").unwrap_or("Default").trim_start_matches('*');
+        (name.to_string(), style)
+    }
+}
+
+/// Formatting set by override tags; `None` keeps the style's value
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TextState {
+    family: Option<String>,
+    size: Option<f32>,
+    weight: Option<u32>,
+    italic: Option<bool>,
+    spacing: Option<f32>,
+    primary: Option<String>,
+    secondary: Option<String>,
+    outline: Option<f32>,
+    outline_color: Option<String>,
+    shadow: Option<f32>,
+    shadow_color: Option<String>,
+}
+
+impl TextState {
+    fn same_font(&self, other: &Self) -> bool {
+        (
+            &self.family,
+            self.size,
+            self.weight,
+            self.italic,
+            self.spacing,
+        ) == (
+            &other.family,
+            other.size,
+            other.weight,
+            other.italic,
+            other.spacing,
+        )
+    }
+
+    fn same_colors(&self, other: &Self) -> bool {
+        (&self.primary, &self.secondary) == (&other.primary, &other.secondary)
+    }
+
+    fn same_stroke(&self, other: &Self) -> bool {
+        (self.outline, &self.outline_color) == (other.outline, &other.outline_color)
+    }
+
+    fn same_shadow(&self, other: &Self) -> bool {
+        (self.shadow, &self.shadow_color) == (other.shadow, &other.shadow_color)
+    }
+}
+
+/// Tags that apply to the whole line wherever they appear
+#[derive(Debug, Default)]
+struct LineTags {
+    alignment: Option<u8>,
+    pos: Option<(f32, f32)>,
+    /// `\move` start and end points
+    movement: Option<[f32; 4]>,
+    /// `\move` (t1, t2) in ms
+    move_times: Option<(f32, f32)>,
+    /// Fade in and out durations in ms
+    fade: Option<(f32, f32)>,
+    transform: Option<Transform>,
+}
+
+impl LineTags {
+    fn transform(&mut self) -> &mut Transform {
+        self.transform.get_or_insert_with(Transform::default)
+    }
+}
+
+/// A run of text and the formatting in effect for it
+#[derive(Debug)]
+struct Chunk {
+    text: String,
+    state: TextState,
+    /// Index of the karaoke syllable the run belongs to
+    syllable: usize,
+}
+
+#[derive(Debug)]
+struct ParsedText {
+    chunks: Vec<Chunk>,
+    /// (start offset, duration) per karaoke syllable, in seconds
+    syllables: Vec<(f64, f64)>,
+    tags: LineTags,
+    /// Whether any syllable uses a sweeping karaoke tag
+    wipe: bool,
+}
+
+/// Conversion state shared by every event of a script
+struct Converter {
+    /// `PlayResX`/`PlayResY`
+    width: f32,
+    height: f32,
+    ass_styles: HashMap<String, AssStyle>,
+    styles: HashMap<String, Style>,
+    effects: HashMap<String, Effect>,
+}
+
+pub(super) fn parse_ass<'a>(content: &'a str) -> Result<AssScript> {
+    let mut metadata = HashMap::new();
+    let mut section = String::new();
+    let mut style_format: Vec<String> = Vec::new();
+    let mut event_format: Vec<String> = DEFAULT_EVENT_FORMAT
+        .split(',')
+        .map(str::to_string)
+        .collect();
+    let mut play_res: (Option<f32>, Option<f32>) = (None, None);
+    let mut ass_styles = HashMap::new();
+    let mut events: Vec<Vec<&'a str>> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line.to_lowercase();
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+
+        match section.as_str() {
+            "[script info]" => match key.as_str() {
+                "playresx" => play_res.0 = value.trim().parse().ok(),
+                "playresy" => play_res.1 = value.trim().parse().ok(),
+                "title"
+                | "original script"
+                | "original translation"
+                | "script updated by"
+                | "update details"
+                | "artist" => {
+                    metadata.insert(key.replace(' ', ""), value.trim().to_string());
+                }
+                _ => {}
+            },
+            "[v4+ styles]" | "[v4 styles]" | "[v4 styles+]" => match key.as_str() {
+                "format" => style_format = format_fields(value),
+                "style" => {
+                    let fields: Vec<&str> = value.splitn(style_format.len().max(1), ',').collect();
+                    let (name, style) =
+                        AssStyle::parse(&fields, &style_format, section == "[v4 styles]");
+                    ass_styles.insert(name, style);
+                }
+                _ => {}
+            },
+            "[events]" => match key.as_str() {
+                "format" => event_format = format_fields(value),
+                "dialogue" => events.push(value.splitn(event_format.len(), ',').collect()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // Missing dimensions follow the 4:3 default of 384x288
+    let (width, height) = match play_res {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, w * 3.0 / 4.0),
+        (None, Some(h)) => (h * 4.0 / 3.0, h),
+        (None, None) => (384.0, 288.0),
+    };
+    let mut converter = Converter {
+        width,
+        height,
+        ass_styles,
+        styles: HashMap::new(),
+        effects: HashMap::new(),
+    };
+
+    let field = |fields: &[&'a str], key: &str| -> &'a str {
+        event_format
+            .iter()
+            .position(|f| f == key)
+            .and_then(|i| fields.get(i).copied())
+            .unwrap_or("")
+    };
+    let mut lyrics = Vec::new();
+    for fields in &events {
+        let (Some(start_time), Some(end_time)) = (
+            parse_time(field(fields, "start")),
+            parse_time(field(fields, "end")),
+        ) else {
+            continue;
+        };
+        if end_time <= start_time {
+            continue;
+        }
+        // Non-zero event margins replace the style's
+        let margin = |key: &str| {
+            field(fields, key)
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|m| *m != 0.0)
+        };
+        let margins = [margin("marginl"), margin("marginr"), margin("marginv")];
+
+        if let Some(mut lyric) = converter.convert_event(
+            field(fields, "style"),
+            margins,
+            start_time,
+            end_time,
+            field(fields, "text"),
+        ) {
+            // The actor (`Name`) field names the singer
+            let actor = field(fields, "name").trim();
+            lyric.singer = (!actor.is_empty()).then(|| actor.to_string());
+            lyrics.push(lyric);
+        }
+    }
+    lyrics.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    let (sx, sy) = converter.scale();
+    let mut styles = converter.styles;
+    for (name, style) in &converter.ass_styles {
+        styles.insert(name.clone(), style_to_klyric(style, sx, sy));
+    }
+
+    Ok(AssScript {
+        lyrics,
+        metadata,
+        styles,
+        effects: converter.effects,
+    })
+}
+
+impl Converter {
+    fn scale(&self) -> (f32, f32) {
+        (TARGET_WIDTH / self.width, TARGET_HEIGHT / self.height)
+    }
+
+    /// The event's style, falling back to `Default` like other renderers do
+    fn style_name(&mut self, name: &str) -> String {
+        let name = name.trim().trim_start_matches('*');
+        if self.ass_styles.contains_key(name) {
+            return name.to_string();
+        }
+        self.ass_styles.entry("Default".to_string()).or_default();
+        "Default".to_string()
+    }
+
+    fn convert_event(
+        &mut self,
+        style_name: &str,
+        margins: [Option<f32>; 3],
+        start_time: f64,
+        end_time: f64,
+        raw_text: &str,
+    ) -> Option<ParsedLyric> {
+        let style_name = self.style_name(style_name);
+        let base = self.ass_styles[&style_name].clone();
+        let parsed = parse_text(raw_text);
+        let line_state = parsed
+            .chunks
+            .first()
+            .map(|c| c.state.clone())
+            .unwrap_or_default();
+
+        let mut syllables: Vec<ParsedSyllable> = Vec::new();
+        for (index, &(offset, duration)) in parsed.syllables.iter().enumerate() {
+            let chunks: Vec<&Chunk> = parsed
+                .chunks
+                .iter()
+                .filter(|c| c.syllable == index)
+                .collect();
+            let joined: String = chunks.iter().map(|c| c.text.as_str()).collect();
+            // Karaoke furigana: `{\k}明日|あした`, continued by `{\k}#|...`
+            if let Some((text, ruby)) = joined.split_once('|') {
+                let spacing = &ruby[ruby.trim_end().len()..];
+                let ruby = ruby.trim();
+                if text.trim() == "#" {
+                    let previous = syllables.iter_mut().rev().find(|s| s.text != " ");
+                    if let Some(previous) = previous.filter(|s| s.ruby.is_some()) {
+                        previous.ruby.get_or_insert_with(String::new).push_str(ruby);
+                        previous.duration = offset + duration - previous.start_offset;
+                        continue;
+                    }
+                }
+                let first = syllables.len();
+                let text = format!("{}{}", text, spacing);
+                push_syllable(&mut syllables, &text, offset, duration, None);
+                let format = self.char_format(&style_name, &base, &line_state, &chunks[0].state);
+                for syllable in &mut syllables[first..] {
+                    syllable.format = format.clone();
+                    if syllable.text != " " && !ruby.is_empty() {
+                        syllable.ruby = Some(ruby.to_string());
+                    }
+                }
+                continue;
+            }
+            // Runs split by mid-syllable tags share the syllable's time by length
+            let total: usize = chunks.iter().map(|c| c.text.chars().count()).sum();
+            let share = |n: usize| {
+                if total == 0 {
+                    0.0
+                } else {
+                    duration * n as f64 / total as f64
+                }
+            };
+            let mut done = 0;
+            for chunk in chunks {
+                let count = chunk.text.chars().count();
+                let first = syllables.len();
+                push_syllable(
+                    &mut syllables,
+                    &chunk.text,
+                    offset + share(done),
+                    share(count),
+                    None,
+                );
+                done += count;
+
+                let format = self.char_format(&style_name, &base, &line_state, &chunk.state);
+                for syllable in &mut syllables[first..] {
+                    syllable.format = format.clone();
+                }
+            }
+        }
+        while syllables.first().is_some_and(|s| s.text == " ") {
+            syllables.remove(0);
+        }
+        while syllables.last().is_some_and(|s| s.text == " ") {
+            syllables.pop();
+        }
+        let text: String = syllables.iter().map(|s| s.text.as_str()).collect();
+        if text.is_empty() {
+            return None;
+        }
+
+        let (position, layout) = self.position(&base, &line_state, &parsed.tags, margins);
+        let effects = self.line_effects(&parsed);
+        let style = if line_state.primary.is_some() || line_state.secondary.is_some() {
+            self.color_style(&style_name, &base, &line_state)
+        } else {
+            style_name.clone()
+        };
+
+        Some(ParsedLyric {
+            text,
+            start_time,
+            end_time,
+            syllables: Some(syllables),
+            raw_text: Some(raw_text.to_string()),
+            style: Some(style),
+            singer: None,
+            format: LineFormat {
+                position: Some(position),
+                layout: Some(layout),
+                font: self.font(&line_state),
+                stroke: (line_state.outline.is_some() || line_state.outline_color.is_some())
+                    .then(|| self.stroke(&base, &line_state)),
+                shadow: (line_state.shadow.is_some() || line_state.shadow_color.is_some())
+                    .then(|| self.shadow(&base, &line_state)),
+                transform: parsed.tags.transform,
+                effects: Some(effects),
+            },
+        })
+    }
+
+    /// Overrides for a run whose formatting differs from the line's
+    fn char_format(
+        &mut self,
+        style_name: &str,
+        base: &AssStyle,
+        line: &TextState,
+        state: &TextState,
+    ) -> CharFormat {
+        CharFormat {
+            style: if state.same_colors(line) {
+                None
+            } else {
+                Some(self.color_style(style_name, base, state))
+            },
+            font: if state.same_font(line) {
+                None
+            } else {
+                self.font(state)
+            },
+            stroke: (!state.same_stroke(line)).then(|| self.stroke(base, state)),
+            shadow: (!state.same_shadow(line)).then(|| self.shadow(base, state)),
+        }
+    }
+
+    /// Style extending `style_name` with `\c`/`\2c` colors
+    fn color_style(&mut self, style_name: &str, base: &AssStyle, state: &TextState) -> String {
+        let primary = state.primary.as_deref().unwrap_or(&base.primary);
+        let secondary = state.secondary.as_deref().unwrap_or(&base.secondary);
+        let name = format!("{} {}/{}", style_name, primary, secondary);
+        self.styles.entry(name.clone()).or_insert_with(|| Style {
+            extends: Some(style_name.to_string()),
+            colors: Some(state_colors(secondary, primary, primary)),
+            ..Default::default()
+        });
+        name
+    }
+
+    fn font(&self, state: &TextState) -> Option<Font> {
+        if state.same_font(&TextState::default()) {
+            return None;
+        }
+        let (sx, sy) = self.scale();
+        Some(Font {
+            family: state.family.clone(),
+            size: state.size.map(|s| s * sy),
+            weight: state.weight,
+            style: state.italic.map(font_style),
+            letter_spacing: state.spacing.map(|s| s * sx),
+        })
+    }
+
+    fn stroke(&self, base: &AssStyle, state: &TextState) -> Stroke {
+        let (_, sy) = self.scale();
+        Stroke {
+            width: Some(state.outline.unwrap_or(base.outline) * sy),
+            color: Some(
+                state
+                    .outline_color
+                    .clone()
+                    .unwrap_or_else(|| base.outline_color.clone()),
+            ),
+        }
+    }
+
+    fn shadow(&self, base: &AssStyle, state: &TextState) -> Shadow {
+        let (_, sy) = self.scale();
+        let depth = state.shadow.unwrap_or(base.shadow) * sy;
+        let color = state
+            .shadow_color
+            .clone()
+            .unwrap_or_else(|| base.back_color.clone());
+        Shadow {
+            // `\shad0` hides the style's shadow
+            color: Some(if depth > 0.0 {
+                color
+            } else {
+                "#00000000".to_string()
+            }),
+            x: Some(depth),
+            y: Some(depth),
+            blur: Some(0.0),
+            inset: None,
+        }
+    }
+
+    /// Line position from `\pos`/`\move` or the alignment and margins.
+    ///
+    /// The renderer puts the text baseline at `y`, so the alignment point is
+    /// moved to the bottom edge of the text and the anchor set to match.
+    fn position(
+        &self,
+        base: &AssStyle,
+        state: &TextState,
+        tags: &LineTags,
+        margins: [Option<f32>; 3],
+    ) -> (Position, Layout) {
+        let alignment = tags.alignment.unwrap_or(base.alignment);
+        let column = (alignment - 1) % 3;
+        let row = (alignment - 1) / 3;
+        let margin_l = margins[0].unwrap_or(base.margin_l);
+        let margin_r = margins[1].unwrap_or(base.margin_r);
+        let margin_v = margins[2].unwrap_or(base.margin_v);
+
+        let (x, y) = match tags.pos.or(tags.movement.map(|m| (m[0], m[1]))) {
+            Some(point) => point,
+            None => (
+                match column {
+                    0 => margin_l,
+                    1 => (self.width + margin_l - margin_r) / 2.0,
+                    _ => self.width - margin_r,
+                },
+                match row {
+                    0 => self.height - margin_v,
+                    1 => self.height / 2.0,
+                    _ => margin_v,
+                },
+            ),
+        };
+        let size = state.size.unwrap_or(base.size);
+        let y = y + size * [0.0, 0.5, 1.0][row as usize];
+
+        let (sx, sy) = self.scale();
+        let (anchor, align) = match column {
+            0 => (Anchor::BottomLeft, Align::Left),
+            1 => (Anchor::BottomCenter, Align::Center),
+            _ => (Anchor::BottomRight, Align::Right),
+        };
+        (
+            Position {
+                x: Some(PositionValue::Pixels(x * sx)),
+                y: Some(PositionValue::Pixels(y * sy)),
+                anchor,
+            },
+            Layout {
+                align,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Register and name the effects for `\kf`, `\fad` and `\move`
+    fn line_effects(&mut self, parsed: &ParsedText) -> Vec<String> {
+        let (sx, sy) = self.scale();
+        let mut names = Vec::new();
+        let mut add = |name: String, effect: Effect| {
+            self.effects.entry(name.clone()).or_insert(effect);
+            names.push(name);
+        };
+
+        if parsed.wipe {
+            add(
+                KARAOKE_WIPE_EFFECT.to_string(),
+                Effect {
+                    effect_type: EffectType::Karaoke,
+                    mode: Some(KaraokeMode::Wipe),
+                    direction: Some(Direction::Ltr),
+                    iterations: 1,
+                    ..Default::default()
+                },
+            );
+        }
+        if let Some((fade_in, fade_out)) = parsed.tags.fade {
+            if fade_in > 0.0 {
+                add(
+                    format!("assFadeIn{}", fade_in.round()),
+                    transition(
+                        EffectTrigger::Enter,
+                        Some(fade_in as f64 / 1000.0),
+                        0.0,
+                        &[("opacity", 0.0, 1.0)],
+                    ),
+                );
+            }
+            if fade_out > 0.0 {
+                add(
+                    format!("assFadeOut{}", fade_out.round()),
+                    transition(
+                        EffectTrigger::Exit,
+                        Some(fade_out as f64 / 1000.0),
+                        0.0,
+                        &[("opacity", 1.0, 0.0)],
+                    ),
+                );
+            }
+        }
+        if let Some([x1, y1, x2, y2]) = parsed.tags.movement {
+            let (dx, dy) = (((x2 - x1) * sx).round(), ((y2 - y1) * sy).round());
+            let (t1, t2) = parsed.tags.move_times.unwrap_or((0.0, 0.0));
+            // Without times the move spans the whole line
+            let duration = (t2 > t1).then(|| (t2 - t1) as f64 / 1000.0);
+            add(
+                format!("assMove{}_{}_{}_{}", dx, dy, t1.round(), t2.round()),
+                transition(
+                    EffectTrigger::Enter,
+                    duration,
+                    t1.max(0.0) as f64 / 1000.0,
+                    &[("x", 0.0, dx as f64), ("y", 0.0, dy as f64)],
+                ),
+            );
+        }
+        names
+    }
+}
+
+fn transition(
+    trigger: EffectTrigger,
+    duration: Option<f64>,
+    delay: f64,
+    properties: &[(&str, f64, f64)],
+) -> Effect {
+    Effect {
+        effect_type: EffectType::Transition,
+        trigger,
+        duration,
+        delay,
+        properties: properties
+            .iter()
+            .map(|(name, from, to)| {
+                (
+                    name.to_string(),
+                    AnimatedValue::Range {
+                        from: *from,
+                        to: *to,
+                    },
+                )
+            })
+            .collect(),
+        iterations: 1,
+        ..Default::default()
+    }
+}
+
+fn style_to_klyric(style: &AssStyle, sx: f32, sy: f32) -> Style {
+    let has_transform = style.scale_x != 100.0 || style.scale_y != 100.0 || style.angle != 0.0;
+    Style {
+        font: Some(Font {
+            family: Some(style.font.clone()),
+            size: Some(style.size * sy),
+            weight: Some(style.weight),
+            style: Some(font_style(style.italic)),
+            letter_spacing: Some(style.spacing * sx),
+        }),
+        // Primary is the sung color, Secondary the color before the syllable
+        colors: Some(state_colors(
+            &style.secondary,
+            &style.primary,
+            &style.primary,
+        )),
+        stroke: Some(Stroke {
+            width: Some(style.outline * sy),
+            color: Some(style.outline_color.clone()),
+        }),
+        shadow: (style.shadow > 0.0).then(|| Shadow {
+            color: Some(style.back_color.clone()),
+            x: Some(style.shadow * sy),
+            y: Some(style.shadow * sy),
+            blur: Some(0.0),
+            inset: None,
+        }),
+        transform: has_transform.then(|| Transform {
+            scale_x: Some(style.scale_x / 100.0),
+            scale_y: Some(style.scale_y / 100.0),
+            // ASS angles turn counter-clockwise
+            rotation: Some(-style.angle),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Byte offset of the first override block, skipping escaped `\{`
+fn block_start(text: &str) -> Option<usize> {
+    text.match_indices('{')
+        .map(|(i, _)| i)
+        .find(|&i| !text[..i].ends_with('\\'))
+}
+
+/// Split dialogue text into runs and apply its override blocks
+fn parse_text(raw: &str) -> ParsedText {
+    let mut state = TextState::default();
+    let mut tags = LineTags::default();
+    let mut chunks = Vec::new();
+    let mut syllables: Vec<(f64, f64)> = Vec::new();
+    let mut offset = 0.0;
+    // Duration of a `\k` still waiting for its text
+    let mut pending: Option<f64> = None;
+    let mut wipe = false;
+
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let (text, block, tail) = match block_start(rest) {
+            Some(open) => match rest[open..].find('}') {
+                Some(close) => (
+                    &rest[..open],
+                    Some(&rest[open + 1..open + close]),
+                    &rest[open + close + 1..],
+                ),
+                None => (rest, None, ""),
+            },
+            None => (rest, None, ""),
+        };
+
+        if !text.is_empty() {
+            let text = text
+                .replace("\\N", " ")
+                .replace("\\n", " ")
+                .replace("\\h", " ")
+                .replace("\\{", "{")
+                .replace("\\}", "}")
+                // A word joiner keeps a backslash from starting an escape
+                .replace("\\\u{2060}", "\\");
+            if let Some(duration) = pending.take() {
+                syllables.push((offset, duration));
+                offset += duration;
+            } else if syllables.is_empty() {
+                syllables.push((0.0, 0.0));
+            }
+            chunks.push(Chunk {
+                text,
+                state: state.clone(),
+                syllable: syllables.len() - 1,
+            });
+        }
+
+        for tag in block.map(split_tags).unwrap_or_default() {
+            let Some((name, args)) = tag_name(tag) else {
+                continue;
+            };
+            match name {
+                "k" | "K" | "kf" | "ko" => {
+                    wipe |= name != "k";
+                    let duration = number(args).unwrap_or(0.0) as f64 / 100.0;
+                    // A `\k` followed directly by another is a pause
+                    if let Some(gap) = pending.replace(duration) {
+                        offset += gap;
+                    }
+                }
+                _ => apply_tag(name, args, &mut state, &mut tags),
+            }
+        }
+        rest = tail;
+    }
+
+    ParsedText {
+        chunks,
+        syllables,
+        tags,
+        wipe,
+    }
+}
+
+/// Split an override block into tags, keeping `\t(...)` arguments whole
+fn split_tags(block: &str) -> Vec<&str> {
+    let mut tags = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    for (i, c) in block.char_indices() {
+        match c {
+            '\\' if depth == 0 => {
+                if let Some(s) = start {
+                    tags.push(&block[s..i]);
+                }
+                start = Some(i + 1);
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tags.push(&block[s..]);
+    }
+    tags
+}
+
+/// Tag names, longest first where one is a prefix of another
+const TAG_NAMES: [&str; 48] = [
+    "alpha", "iclip", "clip", "blur", "bord", "xbord", "ybord", "xshad", "yshad", "shad", "fade",
+    "fad", "fscx", "fscy", "fsp", "fs", "frx", "fry", "frz", "fr", "fn", "fe", "move", "pos",
+    "org", "an", "kf", "ko", "k", "K", "1c", "2c", "3c", "4c", "1a", "2a", "3a", "4a", "c", "a",
+    "be", "b", "i", "u", "s", "q", "r", "t",
+];
+
+/// Split a tag into its name and arguments
+fn tag_name(tag: &str) -> Option<(&'static str, &str)> {
+    TAG_NAMES
+        .iter()
+        .find(|name| tag.starts_with(*name))
+        .map(|name| (*name, &tag[name.len()..]))
+}
+
+fn apply_tag(name: &str, args: &str, state: &mut TextState, tags: &mut LineTags) {
+    match name {
+        "fn" => state.family = Some(args.trim().to_string()).filter(|f| !f.is_empty()),
+        "fs" => state.size = number(args),
+        "fsp" => state.spacing = number(args),
+        "b" => state.weight = number(args).map(font_weight),
+        "i" => state.italic = number(args).map(|v| v != 0.0),
+        "c" | "1c" => state.primary = parse_color(args),
+        "2c" => state.secondary = parse_color(args),
+        "3c" => state.outline_color = parse_color(args),
+        "4c" => state.shadow_color = parse_color(args),
+        "bord" => state.outline = number(args),
+        "shad" => state.shadow = number(args),
+        "r" => *state = TextState::default(),
+        "an" => {
+            tags.alignment = number(args)
+                .map(|a| a as u8)
+                .filter(|a| (1..=9).contains(a))
+        }
+        "a" => tags.alignment = number(args).map(|a| legacy_alignment(a as u8)),
+        // Only the first position tag counts
+        "pos" => {
+            if let [x, y] = arguments(args)[..] {
+                tags.pos.get_or_insert((x, y));
+            }
+        }
+        "move" if tags.movement.is_none() => match arguments(args)[..] {
+            [x1, y1, x2, y2] => tags.movement = Some([x1, y1, x2, y2]),
+            [x1, y1, x2, y2, t1, t2] => {
+                tags.movement = Some([x1, y1, x2, y2]);
+                tags.move_times = Some((t1, t2));
+            }
+            _ => {}
+        },
+        "fad" => {
+            if let [fade_in, fade_out] = arguments(args)[..] {
+                tags.fade = Some((fade_in, fade_out));
+            }
+        }
+        // `\fade(a1,a2,a3,t1,t2,t3,t4)`, approximated as a plain fade in and out
+        "fade" => {
+            if let [_, _, _, t1, t2, t3, t4] = arguments(args)[..] {
+                tags.fade = Some((t2 - t1, t4 - t3));
+            }
+        }
+        "fscx" => tags.transform().scale_x = number(args).map(|v| v / 100.0),
+        "fscy" => tags.transform().scale_y = number(args).map(|v| v / 100.0),
+        "frz" | "fr" => tags.transform().rotation = number(args).map(|v| -v),
+        "blur" | "be" => tags.transform().blur = number(args),
+        "alpha" => {
+            tags.transform().opacity = parse_hex(args).map(|a| 1.0 - (a & 0xFF) as f32 / 255.0)
+        }
+        _ => {}
+    }
+}
+
+fn number(args: &str) -> Option<f32> {
+    args.trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Comma-separated arguments of `\tag(a,b,...)`
+fn arguments(args: &str) -> Vec<f32> {
+    args.trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(|a| a.trim().parse().ok())
+        .collect::<Option<Vec<f32>>>()
+        .unwrap_or_default()
+}
+
+fn format_fields(value: &str) -> Vec<String> {
+    value.split(',').map(|f| f.trim().to_lowercase()).collect()
+}
+
+/// `&HAABBGGRR&`, `&HBBGGRR&` or a decimal SSA color value
+fn parse_hex(value: &str) -> Option<u32> {
+    let value = value.trim().trim_start_matches('&');
+    match value.strip_prefix(['H', 'h']) {
+        Some(hex) => u32::from_str_radix(hex.trim_end_matches('&'), 16).ok(),
+        None => value.parse::<i64>().ok().map(|n| n as u32),
+    }
+}
+
+/// ASS color to `#RRGGBB`, or `#RRGGBBAA` when not opaque (ASS alpha is inverted)
+fn parse_color(value: &str) -> Option<String> {
+    let n = parse_hex(value)?;
+    let [r, g, b, a] = n.to_le_bytes();
+    Some(if a == 0 {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, 255 - a)
+    })
+}
+
+/// `h:mm:ss.cc`
+fn parse_time(value: &str) -> Option<f64> {
+    let mut parts = value.trim().splitn(3, ':');
+    let h: f64 = parts.next()?.parse().ok()?;
+    let m: f64 = parts.next()?.parse().ok()?;
+    let s: f64 = parts.next()?.parse().ok()?;
+    Some(h * 3600.0 + m * 60.0 + s)
+}
+
+/// `-1`/`1` mean bold; other values are weights
+fn font_weight(value: f32) -> u32 {
+    match value as i32 {
+        0 => 400,
+        1 | -1 => 700,
+        w => w.unsigned_abs(),
+    }
+}
+
+fn font_style(italic: bool) -> FontStyle {
+    if italic {
+        FontStyle::Italic
+    } else {
+        FontStyle::Normal
+    }
+}
+
+/// SSA v4 alignment (1-3 bottom, +4 top, +8 middle) to numpad layout
+fn legacy_alignment(value: u8) -> u8 {
+    match value {
+        1..=3 => value,
+        5..=7 => value + 2,
+        9..=11 => value - 5,
+        _ => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "[Script Info]\nTitle: Song\nPlayResX: 1280\nPlayResY: 720\n\n[V4+ Styles]\nFormat: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\nStyle: Main,Arial,48,&H000000FF,&H8000FF00,&H00202020,&H80000000,-1,0,0,0,100,100,0,0,1,2,1,2,10,10,30,1\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n";
+
+    fn script(events: &str) -> AssScript {
+        parse_ass(&format!("{}{}", HEADER, events)).unwrap()
+    }
+
+    fn pixels(value: &Option<PositionValue>) -> f32 {
+        match value {
+            Some(PositionValue::Pixels(px)) => *px,
+            other => panic!("expected pixels, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_styles_and_default_position() {
+        let script = script("Dialogue: 0,0:00:01.00,0:00:03.00,Main,,0,0,0,,Hello, world\n");
+        assert_eq!(
+            script.metadata.get("title").map(String::as_str),
+            Some("Song")
+        );
+
+        // 720p script scaled by 1.5
+        let style = &script.styles["Main"];
+        let font = style.font.as_ref().unwrap();
+        assert_eq!(font.family.as_deref(), Some("Arial"));
+        assert_eq!(font.size, Some(72.0));
+        assert_eq!(font.weight, Some(700));
+        let colors = style.colors.as_ref().unwrap();
+        assert_eq!(
+            colors.active.as_ref().unwrap().fill.as_deref(),
+            Some("#FF0000")
+        );
+        assert_eq!(
+            colors.complete.as_ref().unwrap().fill.as_deref(),
+            Some("#FF0000")
+        );
+        // Alpha 80 is half transparent
+        assert_eq!(
+            colors.inactive.as_ref().unwrap().fill.as_deref(),
+            Some("#00FF007F")
+        );
+        assert_eq!(style.stroke.as_ref().unwrap().width, Some(3.0));
+        assert_eq!(style.shadow.as_ref().unwrap().x, Some(1.5));
+
+        let line = &script.lyrics[0];
+        assert_eq!(line.text, "Hello, world");
+        assert_eq!(line.style.as_deref(), Some("Main"));
+        // Bottom-center: baseline MarginV above the bottom edge
+        let position = line.format.position.as_ref().unwrap();
+        assert_eq!(pixels(&position.x), 960.0);
+        assert_eq!(pixels(&position.y), 1035.0);
+        assert!(matches!(position.anchor, Anchor::BottomCenter));
+        // Plain dialogue shows the primary color at once and has no fade
+        let syllables = line.syllables.as_ref().unwrap();
+        assert!(syllables.iter().all(|s| s.duration == 0.0));
+        assert_eq!(line.format.effects.as_deref(), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_override_tags() {
+        let script = script(
+            "Dialogue: 0,0:00:01.00,0:00:03.00,Main,,0,0,0,,{\\an7\\pos(100,50)\\fs20\\bord0\\c&H00FF00&\\fad(200,300)}Hi {\\c&H0000FF&\\fnImpact}there\n",
+        );
+        let line = &script.lyrics[0];
+        assert_eq!(line.text, "Hi there");
+
+        // Top-left at (100,50), moved down to the baseline of 20px text
+        let position = line.format.position.as_ref().unwrap();
+        assert_eq!(pixels(&position.x), 150.0);
+        assert_eq!(pixels(&position.y), 105.0);
+        assert!(matches!(position.anchor, Anchor::BottomLeft));
+        assert!(matches!(
+            line.format.layout.as_ref().unwrap().align,
+            Align::Left
+        ));
+        assert_eq!(line.format.font.as_ref().unwrap().size, Some(30.0));
+        assert_eq!(line.format.stroke.as_ref().unwrap().width, Some(0.0));
+
+        // `\c` becomes a derived style
+        let style = line.style.as_deref().unwrap();
+        assert_eq!(script.styles[style].extends.as_deref(), Some("Main"));
+        let active = script.styles[style].colors.as_ref().unwrap().active.clone();
+        assert_eq!(active.unwrap().fill.as_deref(), Some("#00FF00"));
+
+        // Mid-line tags override the chars after them
+        let syllables = line.syllables.as_ref().unwrap();
+        let there = syllables.iter().find(|s| s.text == "there").unwrap();
+        assert_ne!(there.format.style.as_deref(), Some(style));
+        assert!(there.format.style.as_deref().unwrap().contains("#FF0000"));
+        assert_eq!(
+            there.format.font.as_ref().unwrap().family.as_deref(),
+            Some("Impact")
+        );
+        assert!(syllables[0].format.style.is_none());
+
+        let effects = line.format.effects.as_ref().unwrap();
+        assert_eq!(effects, &["assFadeIn200", "assFadeOut300"]);
+        let fade_out = &script.effects["assFadeOut300"];
+        assert!(matches!(fade_out.trigger, EffectTrigger::Exit));
+        assert_eq!(fade_out.duration, Some(0.3));
+    }
+
+    #[test]
+    fn test_move_and_karaoke() {
+        let script = script(
+            "Dialogue: 0,0:00:01.00,0:00:04.00,Main,,0,0,0,,{\\move(0,0,100,50,0,500)\\kf50}Ka{\\kf100}ra{\\k30}{\\k30}ok{\\c&HFFFFFF&}e\n",
+        );
+        let line = &script.lyrics[0];
+        assert_eq!(line.text, "Karaoke");
+
+        let syllables = line.syllables.as_ref().unwrap();
+        let texts: Vec<&str> = syllables.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Ka", "ra", "ok", "e"]);
+        assert_eq!(
+            (syllables[1].start_offset, syllables[1].duration),
+            (0.5, 1.0)
+        );
+        // The bare `\k30` is a pause; "e" shares the last syllable's time by length
+        assert!((syllables[2].start_offset - 1.8).abs() < 1e-9);
+        assert!((syllables[2].duration - 0.2).abs() < 1e-9);
+        assert!((syllables[3].start_offset - 2.0).abs() < 1e-9);
+        assert!((syllables[3].duration - 0.1).abs() < 1e-9);
+        assert!(syllables[3].format.style.is_some());
+
+        let effects = line.format.effects.as_ref().unwrap();
+        assert_eq!(effects[0], KARAOKE_WIPE_EFFECT);
+        assert!(matches!(
+            script.effects[KARAOKE_WIPE_EFFECT].mode,
+            Some(KaraokeMode::Wipe)
+        ));
+        let movement = &script.effects[&effects[1]];
+        assert_eq!(movement.duration, Some(0.5));
+        assert!(matches!(
+            movement.properties["x"],
+            AnimatedValue::Range { to, .. } if to == 150.0
+        ));
+        let position = line.format.position.as_ref().unwrap();
+        assert_eq!(pixels(&position.x), 0.0);
+    }
+
+    #[test]
+    fn test_furigana() {
+        let events = "Dialogue: 0,0:00:01.00,0:00:04.00,Main,,0,0,0,,{\\k50}明日|あした{\\k40}の{\\k30}空|そ{\\k20}#|ら\n";
+        let script = script(events);
+        let line = &script.lyrics[0];
+        assert_eq!(line.text, "明日の空");
+        let syllables = line.syllables.as_ref().unwrap();
+        let ruby: Vec<(&str, Option<&str>)> = syllables
+            .iter()
+            .map(|s| (s.text.as_str(), s.ruby.as_deref()))
+            .collect();
+        assert_eq!(
+            ruby,
+            vec![("明日", Some("あした")), ("の", None), ("空", Some("そら"))]
+        );
+        // `#|` extends the previous syllable over its own time
+        assert!((syllables[2].start_offset - 0.9).abs() < 1e-9);
+        assert!((syllables[2].duration - 0.5).abs() < 1e-9);
+
+        let content = format!("{}{}", HEADER, events);
+        let doc = crate::importer::import_subtitle(&content, None).unwrap();
+        let chars = &doc.lines[0].chars;
+        assert_eq!(chars.len(), 3);
+        assert_eq!(chars[0].char, "明日");
+        assert_eq!(chars[0].ruby.as_deref(), Some("あした"));
+        assert_eq!((chars[2].start, chars[2].end), (1.9, 2.4));
+    }
+
+    #[test]
+    fn test_colors_and_alignment() {
+        assert_eq!(parse_color("&H00FF8000").as_deref(), Some("#0080FF"));
+        assert_eq!(parse_color("&HFF0000&").as_deref(), Some("#0000FF"));
+        assert_eq!(parse_color("255").as_deref(), Some("#FF0000"));
+        assert_eq!(legacy_alignment(6), 8);
+        assert_eq!(legacy_alignment(10), 5);
+        assert_eq!(parse_time("1:02:03.45"), Some(3723.45));
+    }
+}
@@ -0,0 +1,519 @@
+//! Standard MIDI File / `.kar` karaoke import
+//!
+//! Syllables come from lyric meta events (`FF 05`) or, in KAR files, from
+//! text events (`FF 01`). `/` and `\` start new lines (KAR), as do trailing
+//! carriage returns (lyric events). Ticks are converted to seconds through the
+//! file's tempo map, and each syllable takes the pitch of the melody note that
+//! starts with it.
+
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashMap;
+
+use super::{push_syllable, LineFormat, ParsedLyric, ParsedSyllable};
+
+/// Default tempo: 120 BPM
+const DEFAULT_TEMPO: u32 = 500_000;
+/// General MIDI percussion channel (10, zero-based)
+const DRUM_CHANNEL: u8 = 9;
+
+/// Whether the data is a Standard MIDI File
+pub(super) fn is_midi(data: &[u8]) -> bool {
+    data.starts_with(b"MThd")
+}
+
+#[derive(Debug, Clone)]
+enum Event {
+    Tempo(u32),
+    Text(Vec<u8>),
+    Lyric(Vec<u8>),
+    TrackName(Vec<u8>),
+    NoteOn(u8),
+    NoteOff(u8),
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8> {
+        let b = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| anyhow!("Unexpected end of MIDI data"))?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos + len;
+        let slice = self
+            .data
+            .get(self.pos..end)
+            .ok_or_else(|| anyhow!("Unexpected end of MIDI data"))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// Variable-length quantity
+    fn vlq(&mut self) -> Result<u32> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let b = self.byte()?;
+            value = (value << 7) | u32::from(b & 0x7F);
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("Invalid variable-length quantity")
+    }
+}
+
+/// Events of one track as (absolute tick, event)
+fn parse_track(data: &[u8]) -> Result<Vec<(u64, Event)>> {
+    let mut r = Reader { data, pos: 0 };
+    let mut events = Vec::new();
+    let mut tick = 0u64;
+    let mut running_status = 0u8;
+
+    while r.pos < data.len() {
+        tick += u64::from(r.vlq()?);
+        let mut status = r.byte()?;
+        if status < 0x80 {
+            // Running status: this byte is already the first data byte
+            r.pos -= 1;
+            status = running_status;
+        }
+
+        match status {
+            0xFF => {
+                let kind = r.byte()?;
+                let len = r.vlq()? as usize;
+                let body = r.bytes(len)?;
+                match kind {
+                    0x01 => events.push((tick, Event::Text(body.to_vec()))),
+                    0x03 => events.push((tick, Event::TrackName(body.to_vec()))),
+                    0x05 => events.push((tick, Event::Lyric(body.to_vec()))),
+                    0x51 if len == 3 => {
+                        let tempo = u32::from_be_bytes([0, body[0], body[1], body[2]]);
+                        events.push((tick, Event::Tempo(tempo)));
+                    }
+                    0x2F => break,
+                    _ => {}
+                }
+            }
+            0xF0 | 0xF7 => {
+                let len = r.vlq()? as usize;
+                r.bytes(len)?;
+            }
+            0x80..=0xEF => {
+                running_status = status;
+                let channel = status & 0x0F;
+                let data_len = match status & 0xF0 {
+                    0xC0 | 0xD0 => 1,
+                    _ => 2,
+                };
+                let body = r.bytes(data_len)?;
+                if channel != DRUM_CHANNEL {
+                    match (status & 0xF0, body) {
+                        (0x90, [note, vel]) if *vel > 0 => {
+                            events.push((tick, Event::NoteOn(*note)))
+                        }
+                        (0x90, [note, _]) | (0x80, [note, _]) => {
+                            events.push((tick, Event::NoteOff(*note)))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => bail!("Invalid MIDI status byte {:#04X}", status),
+        }
+    }
+    Ok(events)
+}
+
+/// Converts ticks to seconds
+struct TempoMap {
+    /// Ticks per quarter note, or `None` for SMPTE timing
+    ticks_per_quarter: Option<f64>,
+    /// Ticks per second for SMPTE timing
+    ticks_per_second: f64,
+    /// (tick, microseconds per quarter note), sorted
+    changes: Vec<(u64, u32)>,
+}
+
+impl TempoMap {
+    fn seconds(&self, tick: u64) -> f64 {
+        let Some(tpq) = self.ticks_per_quarter else {
+            return tick as f64 / self.ticks_per_second;
+        };
+        let mut seconds = 0.0;
+        let mut last_tick = 0u64;
+        let mut tempo = DEFAULT_TEMPO;
+        for &(change_tick, change_tempo) in &self.changes {
+            if change_tick >= tick {
+                break;
+            }
+            seconds += (change_tick - last_tick) as f64 * tempo as f64 / 1e6 / tpq;
+            last_tick = change_tick;
+            tempo = change_tempo;
+        }
+        seconds + (tick - last_tick) as f64 * tempo as f64 / 1e6 / tpq
+    }
+}
+
+/// MIDI text has no declared encoding: use UTF-8 when valid, else Latin-1
+fn decode_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Pair note on/off events into (on tick, off tick, note)
+fn track_notes(events: &[(u64, Event)]) -> Vec<(u64, u64, u8)> {
+    let mut open: HashMap<u8, u64> = HashMap::new();
+    let mut notes = Vec::new();
+    for (tick, event) in events {
+        match event {
+            Event::NoteOn(note) => {
+                if let Some(start) = open.insert(*note, *tick) {
+                    notes.push((start, *tick, *note));
+                }
+            }
+            Event::NoteOff(note) => {
+                if let Some(start) = open.remove(note) {
+                    notes.push((start, *tick, *note));
+                }
+            }
+            _ => {}
+        }
+    }
+    notes.sort_by_key(|n| n.0);
+    notes
+}
+
+pub(super) fn parse_midi(data: &[u8]) -> Result<(Vec<ParsedLyric>, HashMap<String, String>)> {
+    let mut r = Reader { data, pos: 0 };
+    if r.bytes(4)? != b"MThd" {
+        bail!("Not a MIDI file");
+    }
+    let header_len = r.u32()? as usize;
+    let header = r.bytes(header_len)?;
+    if header.len() < 6 {
+        bail!("MIDI header too short");
+    }
+    let division = u16::from_be_bytes([header[4], header[5]]);
+
+    let mut tracks = Vec::new();
+    while r.pos + 8 <= data.len() {
+        let id = r.bytes(4)?;
+        let len = r.u32()? as usize;
+        let body = r.bytes(len)?;
+        if id == b"MTrk" {
+            tracks.push(parse_track(body)?);
+        }
+    }
+
+    let mut changes: Vec<(u64, u32)> = tracks
+        .iter()
+        .flatten()
+        .filter_map(|(tick, e)| match e {
+            Event::Tempo(t) => Some((*tick, *t)),
+            _ => None,
+        })
+        .collect();
+    changes.sort_by_key(|c| c.0);
+    let tempo_map = if division & 0x8000 == 0 {
+        TempoMap {
+            ticks_per_quarter: Some(f64::from(division.max(1))),
+            ticks_per_second: 0.0,
+            changes,
+        }
+    } else {
+        // SMPTE: negative frames per second in the high byte, ticks per frame in the low byte
+        let fps = -f64::from((division >> 8) as u8 as i8);
+        let ticks_per_frame = f64::from(division & 0xFF);
+        TempoMap {
+            ticks_per_quarter: None,
+            ticks_per_second: (fps * ticks_per_frame).max(1.0),
+            changes,
+        }
+    };
+
+    // Metadata: KAR `@T` tags (title, then artist), else the first track name
+    let mut metadata = HashMap::new();
+    let mut titles = tracks.iter().flatten().filter_map(|(_, e)| match e {
+        Event::Text(t) => decode_text(t)
+            .strip_prefix("@T")
+            .map(|s| s.trim().to_string()),
+        _ => None,
+    });
+    if let Some(title) = titles.next() {
+        metadata.insert("title".to_string(), title);
+        if let Some(artist) = titles.next() {
+            metadata.insert("artist".to_string(), artist);
+        }
+    } else if let Some(name) = tracks.first().and_then(|t| {
+        t.iter().find_map(|(_, e)| match e {
+            Event::TrackName(n) => Some(decode_text(n).trim().to_string()),
+            _ => None,
+        })
+    }) {
+        metadata.insert("title".to_string(), name);
+    }
+
+    // Lyric source: the track with the most lyric events, else the most KAR text events
+    let lyric_events = |track: &[(u64, Event)], text_events: bool| -> Vec<(u64, String)> {
+        track
+            .iter()
+            .filter_map(|(tick, e)| match (e, text_events) {
+                (Event::Lyric(t), false) => Some((*tick, decode_text(t))),
+                (Event::Text(t), true) => {
+                    let text = decode_text(t);
+                    (!text.starts_with('@') && !text.starts_with('%')).then_some((*tick, text))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+    let pick = |text_events: bool| {
+        tracks
+            .iter()
+            .map(|t| lyric_events(t, text_events))
+            .max_by_key(|events| events.len())
+            .filter(|events| !events.is_empty())
+    };
+    let words = pick(false)
+        .or_else(|| pick(true))
+        .ok_or_else(|| anyhow!("MIDI file contains no lyrics"))?;
+
+    // Split into lines of (tick, syllable text)
+    let mut lines: Vec<Vec<(u64, String)>> = vec![Vec::new()];
+    for (tick, text) in words {
+        let body = text.trim_start_matches(['/', '\\', '\r', '\n']);
+        if body.len() != text.len() {
+            lines.push(Vec::new());
+        }
+        let word = body.trim_end_matches(['\r', '\n']);
+        if !word.is_empty() {
+            lines
+                .last_mut()
+                .expect("at least one line")
+                .push((tick, word.to_string()));
+        }
+        if word.len() != body.len() {
+            lines.push(Vec::new());
+        }
+    }
+    lines.retain(|l| !l.is_empty());
+
+    // Melody: the track whose note onsets line up with the most syllables
+    let tolerance = tempo_map
+        .ticks_per_quarter
+        .map(|tpq| (tpq / 8.0) as u64)
+        .unwrap_or(1);
+    let matches = |notes: &[(u64, u64, u8)], tick: u64| {
+        notes
+            .iter()
+            .filter(|n| n.0.abs_diff(tick) <= tolerance)
+            .min_by_key(|n| n.0.abs_diff(tick))
+            .copied()
+    };
+    let melody = tracks
+        .iter()
+        .map(|t| track_notes(t))
+        .max_by_key(|notes| {
+            lines
+                .iter()
+                .flatten()
+                .filter(|(tick, _)| matches(notes, *tick).is_some())
+                .count()
+        })
+        .unwrap_or_default();
+
+    let starts: Vec<u64> = lines.iter().flatten().map(|(tick, _)| *tick).collect();
+    let quarter = tempo_map.ticks_per_quarter.unwrap_or(1.0) as u64;
+    let mut lyrics = Vec::new();
+    let mut index = 0;
+    for line in lines {
+        let start_time = tempo_map.seconds(line[0].0);
+        let mut end_time = start_time;
+        let mut syllables: Vec<ParsedSyllable> = Vec::new();
+
+        for (tick, text) in &line {
+            let next = starts.get(index + 1).copied();
+            index += 1;
+            let note = matches(&melody, *tick);
+            // Hold until the note ends, but never past the next syllable
+            let end_tick = match (note, next) {
+                (Some(n), Some(next)) => n.1.min(next),
+                (Some(n), None) => n.1,
+                (None, Some(next)) => next,
+                (None, None) => tick + quarter,
+            }
+            .max(*tick);
+
+            let start = tempo_map.seconds(*tick);
+            let end = tempo_map.seconds(end_tick);
+            end_time = end_time.max(end);
+            push_syllable(
+                &mut syllables,
+                text,
+                start - start_time,
+                end - start,
+                note.map(|n| i32::from(n.2)),
+            );
+        }
+
+        while syllables.first().is_some_and(|s| s.text == " ") {
+            syllables.remove(0);
+        }
+        while syllables.last().is_some_and(|s| s.text == " ") {
+            syllables.pop();
+        }
+        let text: String = syllables.iter().map(|s| s.text.as_str()).collect();
+        if text.is_empty() {
+            continue;
+        }
+        lyrics.push(ParsedLyric {
+            text,
+            start_time,
+            end_time,
+            syllables: Some(syllables),
+            raw_text: None,
+            style: None,
+            singer: None,
+            format: LineFormat::default(),
+        });
+    }
+
+    Ok((lyrics, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vlq(mut n: u32) -> Vec<u8> {
+        let mut out = vec![(n & 0x7F) as u8];
+        n >>= 7;
+        while n > 0 {
+            out.insert(0, (n & 0x7F) as u8 | 0x80);
+            n >>= 7;
+        }
+        out
+    }
+
+    fn meta(kind: u8, body: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xFF, kind];
+        out.extend(vlq(body.len() as u32));
+        out.extend(body);
+        out
+    }
+
+    fn track(events: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (delta, event) in events {
+            body.extend(vlq(*delta));
+            body.extend(event);
+        }
+        body.extend([0x00, 0xFF, 0x2F, 0x00]);
+        let mut out = b"MTrk".to_vec();
+        out.extend((body.len() as u32).to_be_bytes());
+        out.extend(body);
+        out
+    }
+
+    fn smf(tracks: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = b"MThd".to_vec();
+        out.extend(6u32.to_be_bytes());
+        out.extend(1u16.to_be_bytes());
+        out.extend((tracks.len() as u16).to_be_bytes());
+        out.extend(480u16.to_be_bytes());
+        for t in tracks {
+            out.extend(t);
+        }
+        out
+    }
+
+    #[test]
+    fn test_parse_midi_lyrics() {
+        // 120 BPM, then 240 BPM from tick 960
+        let conductor = track(&[
+            (0, meta(0x03, b"Song")),
+            (0, meta(0x51, &[0x07, 0xA1, 0x20])),
+            (960, meta(0x51, &[0x03, 0xD0, 0x90])),
+        ]);
+        let melody = track(&[
+            (0, meta(0x05, b"Hel")),
+            (0, vec![0x90, 60, 100]),
+            (240, vec![0x80, 60, 0]),
+            (0, meta(0x05, b"lo ")),
+            // Running status note on, then note-off as velocity 0
+            (0, vec![0x90, 62, 100]),
+            (240, vec![62, 0]),
+            (0, meta(0x05, b"world\r")),
+            (0, vec![64, 100]),
+            (240, vec![64, 0]),
+            (240, meta(0x05, b"Bye")),
+            (0, vec![65, 100]),
+            (480, vec![65, 0]),
+        ]);
+        let data = smf(&[conductor, melody]);
+        assert!(is_midi(&data));
+
+        let (lyrics, metadata) = parse_midi(&data).unwrap();
+        assert_eq!(metadata.get("title").map(String::as_str), Some("Song"));
+        assert_eq!(lyrics.len(), 2);
+
+        let first = &lyrics[0];
+        assert_eq!(first.text, "Hello world");
+        let syllables = first.syllables.as_ref().unwrap();
+        let pitches: Vec<Option<i32>> = syllables.iter().map(|s| s.pitch).collect();
+        assert_eq!(pitches, vec![Some(60), Some(62), None, Some(64)]);
+        assert!((syllables[1].start_offset - 0.25).abs() < 1e-9);
+        // "world" holds for its note (240 ticks), not until "Bye"
+        assert!((first.end_time - 0.75).abs() < 1e-9);
+
+        // After the tempo change 480 ticks last 0.25s
+        let second = &lyrics[1];
+        assert_eq!(second.text, "Bye");
+        assert!((second.start_time - 1.0).abs() < 1e-9);
+        assert!((second.end_time - 1.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_kar_text() {
+        let info = track(&[
+            (0, meta(0x01, b"@KMIDI KARAOKE FILE")),
+            (0, meta(0x01, b"@TKaraoke Song")),
+            (0, meta(0x01, b"@TThe Band")),
+        ]);
+        let words = track(&[
+            (0, meta(0x01, b"\\One")),
+            (480, meta(0x01, b" two")),
+            (480, meta(0x01, b"/Three")),
+        ]);
+        let (lyrics, metadata) = parse_midi(&smf(&[info, words])).unwrap();
+        assert_eq!(
+            metadata.get("title").map(String::as_str),
+            Some("Karaoke Song")
+        );
+        assert_eq!(metadata.get("artist").map(String::as_str), Some("The Band"));
+
+        let texts: Vec<&str> = lyrics.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["One two", "Three"]);
+        // No melody notes: syllables run until the next one
+        assert!((lyrics[0].end_time - 1.0).abs() < 1e-9);
+        assert!(lyrics[0].syllables.as_ref().unwrap()[0].pitch.is_none());
+
+        assert!(parse_midi(b"MThd").is_err());
+    }
+}
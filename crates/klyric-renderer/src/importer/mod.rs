@@ -0,0 +1,1032 @@
+mod ass;
+mod midi;
+mod ttml;
+mod ultrastar;
+
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::migrate::CURRENT_VERSION;
+use crate::model::{
+    document::KLyricDocumentV2,
+    layout::{Anchor, Layout, Position, PositionValue, Transform},
+    line::{Char, Line, Translation},
+    overlay::Overlay,
+    project::Project,
+    singer::Singer,
+    style::FillStroke,
+    style::Shadow,
+    style::Stroke,
+    style::{Font, Style},
+    theme::Theme,
+};
+
+fn file_extension(filename: Option<&str>) -> String {
+    filename
+        .and_then(|f| Path::new(f).extension())
+        .and_then(|e| e.to_str())
+        .map(|s| s.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Extras added to an imported document; all off by default
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Add an intro title card (see [`add_title_card`]) when the source
+    /// names a title
+    pub title_card: bool,
+}
+
+/// Parse a lyrics file from raw bytes. MIDI/KAR files are read as binary;
+/// anything else is decoded as UTF-8 and passed to [`import_subtitle`].
+pub fn import_subtitle_bytes(data: &[u8], filename: Option<&str>) -> Result<KLyricDocumentV2> {
+    import_subtitle_bytes_with(data, filename, ImportOptions::default())
+}
+
+/// [`import_subtitle_bytes`] with import options
+pub fn import_subtitle_bytes_with(
+    data: &[u8],
+    filename: Option<&str>,
+    options: ImportOptions,
+) -> Result<KLyricDocumentV2> {
+    let extension = file_extension(filename);
+    if midi::is_midi(data) || matches!(extension.as_str(), "mid" | "midi" | "kar") {
+        let (lyrics, metadata) = midi::parse_midi(data)?;
+        return convert_to_klyric(lyrics, metadata, options);
+    }
+    import_subtitle_with(&String::from_utf8_lossy(data), filename, options)
+}
+
+/// Parse a subtitle file content into a KLyricV2 Document
+pub fn import_subtitle(content: &str, filename: Option<&str>) -> Result<KLyricDocumentV2> {
+    import_subtitle_with(content, filename, ImportOptions::default())
+}
+
+/// [`import_subtitle`] with import options
+pub fn import_subtitle_with(
+    content: &str,
+    filename: Option<&str>,
+    options: ImportOptions,
+) -> Result<KLyricDocumentV2> {
+    let extension = file_extension(filename);
+
+    if extension == "klyric" || extension == "json" {
+        // Try parsing as KLyric JSON first
+        if let Ok(doc) = crate::parser::parse_document(content) {
+            return Ok(doc);
+        }
+    }
+
+    if extension == "ass" || extension == "ssa" || content.contains("[Script Info]") {
+        // ASS brings its own styles and effects
+        let script = ass::parse_ass(content)?;
+        let mut doc = convert_to_klyric(script.lyrics, script.metadata, options)?;
+        // Actors keep the colors of their ASS styles
+        for singer in doc.singers.values_mut() {
+            singer.color = None;
+        }
+        doc.styles.extend(script.styles);
+        doc.effects.extend(script.effects);
+        return Ok(doc);
+    }
+
+    let (lyrics, metadata) = if ultrastar::is_ultrastar(content) {
+        ultrastar::parse_ultrastar(content)?
+    } else if extension == "ttml" || extension == "xml" || content.contains("<tt") {
+        ttml::parse_ttml(content)?
+    } else if extension == "srt"
+        || Regex::new(r"^\d+\s*\n\d{2}:\d{2}:\d{2}")
+            .map(|r| r.is_match(content))
+            .unwrap_or(false)
+    {
+        parse_srt(content)?
+    } else {
+        // Default to LRC
+        parse_lrc(content)?
+    };
+
+    convert_to_klyric(lyrics, metadata, options)
+}
+
+/// Attach the lines of a second subtitle file (any supported format) to
+/// `doc` as a translation track named `lang`.
+///
+/// Each translated line goes to the line of `doc` it overlaps most in time;
+/// lines overlapping none are dropped. Tracks use the `translation` style,
+/// which is added when the document has none. Returns the number of lines
+/// that received a translation.
+pub fn merge_translation(
+    doc: &mut KLyricDocumentV2,
+    content: &str,
+    filename: Option<&str>,
+    lang: Option<&str>,
+) -> Result<usize> {
+    let translated = import_subtitle(content, filename)?;
+
+    let mut touched = HashSet::new();
+    for source in translated.lines {
+        let text = source
+            .text
+            .unwrap_or_else(|| source.chars.iter().map(|c| c.char.as_str()).collect());
+        let overlap = |line: &Line| source.end.min(line.end) - source.start.max(line.start);
+        let Some((idx, target)) = doc
+            .lines
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, line)| overlap(line) > 0.0)
+            .max_by(|(_, a), (_, b)| overlap(a).total_cmp(&overlap(b)))
+        else {
+            continue;
+        };
+        // Several translated lines for one line join into one track entry
+        if touched.contains(&idx) {
+            if let Some(last) = target.translations.last_mut() {
+                last.text.push(' ');
+                last.text.push_str(&text);
+            }
+            continue;
+        }
+        touched.insert(idx);
+        target.translations.push(Translation {
+            text,
+            lang: lang.map(str::to_string),
+            style: Some(TRANSLATION_STYLE.to_string()),
+        });
+    }
+
+    if !touched.is_empty() && !doc.styles.contains_key(TRANSLATION_STYLE) {
+        doc.styles
+            .insert(TRANSLATION_STYLE.to_string(), translation_style());
+    }
+    Ok(touched.len())
+}
+
+#[derive(Debug, Clone)]
+struct ParsedLyric {
+    pub text: String,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub syllables: Option<Vec<ParsedSyllable>>,
+    #[allow(dead_code)]
+    pub raw_text: Option<String>,
+    /// Style name for the line; `base` when unset
+    pub style: Option<String>,
+    /// Singer of the line (TTML agent, ASS actor, UltraStar player)
+    pub singer: Option<String>,
+    pub format: LineFormat,
+}
+
+#[derive(Debug, Clone)]
+struct ParsedSyllable {
+    pub text: String,
+    pub start_offset: f64,
+    pub duration: f64,
+    /// MIDI note number of the sung note, when the format has one
+    pub pitch: Option<i32>,
+    /// Ruby text over the whole syllable (ASS `base|ruby` furigana)
+    pub ruby: Option<String>,
+    pub format: CharFormat,
+}
+
+/// Line presentation taken from the source file (ASS override tags)
+#[derive(Debug, Clone, Default)]
+struct LineFormat {
+    pub position: Option<Position>,
+    pub layout: Option<Layout>,
+    pub font: Option<Font>,
+    pub stroke: Option<Stroke>,
+    pub shadow: Option<Shadow>,
+    pub transform: Option<Transform>,
+    /// Effects named by the source (ASS `\fad`, `\move`, ...)
+    pub effects: Option<Vec<String>>,
+}
+
+/// Per-char overrides for the chars of a syllable
+#[derive(Debug, Clone, Default)]
+struct CharFormat {
+    pub style: Option<String>,
+    pub font: Option<Font>,
+    pub stroke: Option<Stroke>,
+    pub shadow: Option<Shadow>,
+}
+
+/// Append a syllable, splitting off surrounding whitespace as zero-length
+/// syllables so spaces take no highlight time
+fn push_syllable(
+    syllables: &mut Vec<ParsedSyllable>,
+    text: &str,
+    start_offset: f64,
+    duration: f64,
+    pitch: Option<i32>,
+) {
+    let blank = |syllables: &mut Vec<ParsedSyllable>, at: f64| {
+        if syllables.last().is_some_and(|s| s.text == " ") {
+            return;
+        }
+        syllables.push(ParsedSyllable {
+            text: " ".to_string(),
+            start_offset: at,
+            duration: 0.0,
+            pitch: None,
+            ruby: None,
+            format: CharFormat::default(),
+        });
+    };
+
+    let word = text.trim();
+    if text.starts_with(char::is_whitespace) {
+        blank(syllables, start_offset);
+    }
+    if !word.is_empty() {
+        syllables.push(ParsedSyllable {
+            text: word.to_string(),
+            start_offset,
+            duration,
+            pitch,
+            ruby: None,
+            format: CharFormat::default(),
+        });
+        if text.ends_with(char::is_whitespace) {
+            blank(syllables, start_offset + duration);
+        }
+    }
+}
+
+fn convert_to_klyric(
+    lyrics: Vec<ParsedLyric>,
+    metadata: HashMap<String, String>,
+    options: ImportOptions,
+) -> Result<KLyricDocumentV2> {
+    let duration = lyrics.last().map(|l| l.end_time).unwrap_or(0.0) + 2.0;
+
+    // Default Style
+    let mut styles = HashMap::new();
+    let default_style = Style {
+        font: Some(Font {
+            family: Some("Noto Sans SC".to_string()),
+            size: Some(72.0),
+            weight: Some(700),
+            style: Some(crate::model::style::FontStyle::Normal),
+            letter_spacing: Some(0.0),
+        }),
+        colors: Some(crate::model::style::StateColors {
+            inactive: Some(FillStroke {
+                fill: Some("#888888".to_string()),
+                stroke: None,
+                gradient: None,
+                pattern: None,
+            }),
+            active: Some(FillStroke {
+                fill: Some("#FFFF00".to_string()),
+                stroke: None,
+                gradient: None,
+                pattern: None,
+            }),
+            complete: Some(FillStroke {
+                fill: Some("#FFFFFF".to_string()),
+                stroke: None,
+                gradient: None,
+                pattern: None,
+            }),
+        }),
+        stroke: Some(Stroke {
+            width: Some(3.0),
+            color: Some("#000000".to_string()),
+        }),
+        shadow: Some(Shadow {
+            color: Some("#00000080".to_string()),
+            x: Some(2.0),
+            y: Some(2.0),
+            blur: Some(4.0),
+            inset: None,
+        }),
+        ..Default::default()
+    };
+    styles.insert("base".to_string(), default_style);
+
+    // Background vocals (TTML `x-bg`) get their own style
+    if lyrics
+        .iter()
+        .any(|l| l.style.as_deref() == Some(BACKGROUND_STYLE))
+    {
+        styles.insert(BACKGROUND_STYLE.to_string(), background_style());
+    }
+
+    // Each singer gets a highlight color, in order of first appearance
+    let mut singers = HashMap::new();
+    let mut palette = SINGER_COLORS.iter().cycle();
+    for id in lyrics.iter().filter_map(|l| l.singer.as_deref()) {
+        if !singers.contains_key(id) {
+            let singer = Singer {
+                color: palette.next().map(|c| c.to_string()),
+                ..Default::default()
+            };
+            singers.insert(id.to_string(), singer);
+        }
+    }
+
+    // Default Effect
+    let effects = HashMap::new();
+    // Simplified default effect for now
+    // effects.insert("fadeIn".to_string(), Effect::default());
+
+    let has_title = metadata.contains_key("title") || metadata.contains_key("ti");
+    let mut doc = KLyricDocumentV2 {
+        schema: None,
+        version: CURRENT_VERSION.to_string(),
+        project: Project {
+            title: metadata
+                .get("title")
+                .or(metadata.get("ti"))
+                .cloned()
+                .unwrap_or_else(|| "Untitled".to_string()),
+            artist: Some(
+                metadata
+                    .get("artist")
+                    .or(metadata.get("ar"))
+                    .cloned()
+                    .unwrap_or_else(|| "".to_string()),
+            ),
+            duration,
+            resolution: crate::model::Resolution {
+                width: 1920,
+                height: 1080,
+            },
+            fps: 30,
+            audio: None,
+            album: metadata.get("album").or(metadata.get("al")).cloned(),
+            created: Some(chrono::Utc::now().to_rfc3339()),
+            modified: Some(chrono::Utc::now().to_rfc3339()),
+        },
+        theme: Some(Theme::default()),
+        styles,
+        effects,
+        particle_presets: HashMap::new(),
+        imports: Vec::new(),
+        sections: Vec::new(),
+        singers,
+        tracks: HashMap::new(),
+        overlays: Vec::new(),
+        imported: Default::default(),
+        base_dir: None,
+        lines: lyrics
+            .into_iter()
+            .enumerate()
+            .map(|(idx, lyric)| convert_line_to_klyric(lyric, idx))
+            .collect(),
+    };
+    if options.title_card && has_title {
+        add_title_card(&mut doc);
+    }
+
+    Ok(doc)
+}
+
+/// Shortest intro that gets a title card, in seconds
+const TITLE_CARD_MIN: f64 = 2.0;
+
+/// Longest a title card stays up, in seconds
+const TITLE_CARD_MAX: f64 = 8.0;
+
+/// Add an intro title card: `project.title`, with the artist and album
+/// below it, shown until the first line starts. Done on import with
+/// [`ImportOptions::title_card`] when the source names a title.
+///
+/// Uses the `titleCard` and `titleCredit` styles, which are added when the
+/// document has none. Returns false when the title is empty or the first
+/// line starts too early to fit a card.
+pub fn add_title_card(doc: &mut KLyricDocumentV2) -> bool {
+    let title = doc.project.title.trim();
+    let first_line = doc
+        .lines
+        .iter()
+        .map(|line| line.start)
+        .fold(doc.project.duration, f64::min);
+    if title.is_empty() || first_line < TITLE_CARD_MIN {
+        return false;
+    }
+    let end = first_line.min(TITLE_CARD_MAX);
+
+    let card = |text: &str, style: &str, y: f32| Overlay {
+        start: 0.0,
+        end,
+        text: Some(text.to_string()),
+        style: Some(style.to_string()),
+        effects: vec!["fade".to_string()],
+        position: Some(Position {
+            x: None,
+            y: Some(PositionValue::Percentage(y)),
+            anchor: Anchor::default(),
+        }),
+        ..Default::default()
+    };
+    doc.overlays.push(card(title, TITLE_CARD_STYLE, 0.45));
+
+    let credit: Vec<&str> = [&doc.project.artist, &doc.project.album]
+        .into_iter()
+        .filter_map(|s| s.as_deref().map(str::trim))
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !credit.is_empty() {
+        let credit = credit.join(" — ");
+        doc.overlays.push(card(&credit, TITLE_CREDIT_STYLE, 0.56));
+    }
+
+    doc.styles
+        .entry(TITLE_CARD_STYLE.to_string())
+        .or_insert_with(|| title_card_style(96.0, 800, "#FFFFFF"));
+    doc.styles
+        .entry(TITLE_CREDIT_STYLE.to_string())
+        .or_insert_with(|| title_card_style(48.0, 400, "#CCCCCC"));
+    true
+}
+
+/// Style name given to secondary (background vocal) lines
+const BACKGROUND_STYLE: &str = "background";
+
+/// Style name given to translation tracks merged by `merge_translation`
+const TRANSLATION_STYLE: &str = "translation";
+
+/// Style names of the title card added by `add_title_card`
+const TITLE_CARD_STYLE: &str = "titleCard";
+const TITLE_CREDIT_STYLE: &str = "titleCredit";
+
+/// Highlight colors handed out to singers, in order
+const SINGER_COLORS: [&str; 6] = [
+    "#FFFF00", "#00E5FF", "#FF66CC", "#7CFF4F", "#FFA040", "#B58CFF",
+];
+
+fn state_colors(inactive: &str, active: &str, complete: &str) -> crate::model::style::StateColors {
+    let fill = |c: &str| {
+        Some(FillStroke {
+            fill: Some(c.to_string()),
+            stroke: None,
+            gradient: None,
+            pattern: None,
+        })
+    };
+    crate::model::style::StateColors {
+        inactive: fill(inactive),
+        active: fill(active),
+        complete: fill(complete),
+    }
+}
+
+fn background_style() -> Style {
+    Style {
+        extends: Some("base".to_string()),
+        font: Some(Font {
+            family: Some("Noto Sans SC".to_string()),
+            size: Some(48.0),
+            weight: Some(400),
+            style: Some(crate::model::style::FontStyle::Italic),
+            letter_spacing: Some(0.0),
+        }),
+        colors: Some(state_colors("#666666", "#CCCC66", "#BBBBBB")),
+        ..Default::default()
+    }
+}
+
+fn translation_style() -> Style {
+    Style {
+        extends: Some("base".to_string()),
+        font: Some(Font {
+            family: Some("Noto Sans SC".to_string()),
+            size: Some(44.0),
+            weight: Some(400),
+            style: Some(crate::model::style::FontStyle::Normal),
+            letter_spacing: Some(0.0),
+        }),
+        // Translations are not highlighted, so every state looks the same
+        colors: Some(state_colors("#DDDDDD", "#DDDDDD", "#DDDDDD")),
+        ..Default::default()
+    }
+}
+
+fn title_card_style(size: f32, weight: u32, color: &str) -> Style {
+    Style {
+        extends: Some("base".to_string()),
+        font: Some(Font {
+            family: Some("Noto Sans SC".to_string()),
+            size: Some(size),
+            weight: Some(weight),
+            style: Some(crate::model::style::FontStyle::Normal),
+            letter_spacing: Some(0.0),
+        }),
+        colors: Some(state_colors(color, color, color)),
+        ..Default::default()
+    }
+}
+
+fn convert_line_to_klyric(lyric: ParsedLyric, idx: usize) -> Line {
+    let mut char_data = Vec::new();
+
+    if let Some(syllables) = lyric.syllables {
+        for syllable in syllables {
+            // Ruby spans its whole base, so the syllable stays one char
+            if let Some(ruby) = syllable.ruby {
+                let start = lyric.start_time + syllable.start_offset;
+                char_data.push(Char {
+                    char: syllable.text,
+                    start: (start * 1000.0).round() / 1000.0,
+                    end: ((start + syllable.duration) * 1000.0).round() / 1000.0,
+                    style: syllable.format.style,
+                    font: syllable.format.font,
+                    stroke: syllable.format.stroke,
+                    shadow: syllable.format.shadow,
+                    effects: vec![],
+                    transform: None,
+                    pitch: syllable.pitch,
+                    ruby: Some(ruby),
+                    singer: None,
+                });
+                continue;
+            }
+            let chars: Vec<char> = syllable.text.chars().collect();
+            let char_count = chars.len();
+            if char_count > 0 {
+                let char_duration = syllable.duration / char_count as f64;
+                for (i, c) in chars.iter().enumerate() {
+                    let char_start =
+                        lyric.start_time + syllable.start_offset + (i as f64 * char_duration);
+                    let char_end = char_start + char_duration;
+                    char_data.push(Char {
+                        char: c.to_string(),
+                        start: (char_start * 1000.0).round() / 1000.0,
+                        end: (char_end * 1000.0).round() / 1000.0,
+                        style: syllable.format.style.clone(),
+                        font: syllable.format.font.clone(),
+                        stroke: syllable.format.stroke.clone(),
+                        shadow: syllable.format.shadow.clone(),
+                        effects: vec![],
+                        transform: None,
+                        pitch: syllable.pitch,
+                        ruby: None,
+                        singer: None,
+                    });
+                }
+            }
+        }
+    } else {
+        // Even distribution
+        let chars: Vec<char> = lyric.text.chars().collect();
+        let total_duration = lyric.end_time - lyric.start_time;
+        if !chars.is_empty() {
+            let char_duration = total_duration / chars.len() as f64;
+            for (i, c) in chars.iter().enumerate() {
+                let char_start = lyric.start_time + (i as f64 * char_duration);
+                let char_end = lyric.start_time + ((i + 1) as f64 * char_duration);
+                char_data.push(Char {
+                    char: c.to_string(),
+                    start: (char_start * 1000.0).round() / 1000.0,
+                    end: (char_end * 1000.0).round() / 1000.0,
+                    style: None,
+                    font: None,
+                    stroke: None,
+                    shadow: None,
+                    effects: vec![],
+                    transform: None,
+                    pitch: None,
+                    ruby: None,
+                    singer: None,
+                });
+            }
+        }
+    }
+
+    let format = lyric.format;
+    let style = lyric.style.unwrap_or_else(|| "base".to_string());
+    let is_background = style == BACKGROUND_STYLE;
+
+    Line {
+        id: Some(format!("line-{}", idx)),
+        start: (lyric.start_time * 1000.0).round() / 1000.0,
+        end: (lyric.end_time * 1000.0).round() / 1000.0,
+        text: Some(lyric.text),
+        style: Some(style),
+        singer: lyric.singer,
+        effects: format.effects.unwrap_or_default(),
+        position: Some(format.position.unwrap_or(Position {
+            x: Some(crate::model::layout::PositionValue::Pixels(960.0)),
+            // Background vocals sit below the main line
+            y: Some(crate::model::layout::PositionValue::Pixels(
+                if is_background { 660.0 } else { 540.0 },
+            )),
+            anchor: Anchor::Center,
+        })),
+        transform: format.transform,
+        font: format.font,
+        stroke: format.stroke,
+        shadow: format.shadow,
+        mask: None,
+        layout: format.layout,
+        chars: char_data,
+        translations: Vec::new(),
+        track: None,
+    }
+}
+
+fn parse_lrc(content: &str) -> Result<(Vec<ParsedLyric>, HashMap<String, String>)> {
+    let mut lyrics = Vec::new();
+    let mut metadata = HashMap::new();
+
+    // [mm:ss.xx] or [mm:ss:xx]
+    let timestamp_regex = Regex::new(r"\[(\d{2}):(\d{2})[.:](\d{2,3})\]").unwrap();
+    // Enhanced LRC (A2) word timestamps: <mm:ss.xx>
+    let word_regex = Regex::new(r"<(\d{2}):(\d{2})[.:](\d{2,3})>").unwrap();
+    let metadata_regex = Regex::new(r"\[(ti|ar|al|au|length|by|offset|re|ve):([^\]]*)\]").unwrap();
+
+    // [offset:] applies to every timestamp, wherever the tag appears.
+    // A positive offset (ms) makes the lyrics appear sooner.
+    let offset = content
+        .lines()
+        .filter_map(|line| metadata_regex.captures(line.trim()))
+        .find(|cap| &cap[1] == "offset")
+        .and_then(|cap| cap[2].trim().parse::<f64>().ok())
+        .map(|ms| ms / 1000.0)
+        .unwrap_or(0.0);
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(cap) = metadata_regex.captures(trimmed) {
+            let key = cap[1].to_lowercase();
+            let value = cap[2].trim().to_string();
+            metadata.insert(key, value);
+            continue;
+        }
+
+        // We need to verify if the line HAS timestamps
+        if !timestamp_regex.is_match(trimmed) {
+            continue;
+        }
+
+        let timestamps: Vec<f64> = timestamp_regex
+            .captures_iter(trimmed)
+            .map(|cap| (lrc_time(&cap) - offset).max(0.0))
+            .collect();
+
+        if timestamps.is_empty() {
+            continue;
+        }
+
+        // Word timestamps are absolute; make them relative to the first line timestamp
+        // so repeated lines ([00:10.00][01:20.00]...) share the same syllable timing.
+        let body = timestamp_regex.replace_all(trimmed, "");
+        let (text, syllables, words_end) =
+            parse_lrc_words(&body, &word_regex, timestamps[0] + offset);
+        if text.is_empty() {
+            continue;
+        }
+
+        for start_time in timestamps {
+            lyrics.push(ParsedLyric {
+                text: text.clone(),
+                start_time,
+                // Calculated later unless a trailing word timestamp marks the end
+                end_time: words_end.map(|end| start_time + end).unwrap_or(0.0),
+                syllables: syllables.clone(),
+                raw_text: None,
+                style: None,
+                singer: None,
+                format: LineFormat::default(),
+            });
+        }
+    }
+
+    lyrics.sort_by(|a, b| {
+        a.start_time
+            .partial_cmp(&b.start_time)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Fill end times
+    let len = lyrics.len();
+    for i in 0..len {
+        if lyrics[i].end_time <= lyrics[i].start_time {
+            if i < len - 1 {
+                lyrics[i].end_time = lyrics[i + 1].start_time - 0.1;
+            } else {
+                lyrics[i].end_time = lyrics[i].start_time + 3.0;
+            }
+        }
+
+        if lyrics[i].end_time < lyrics[i].start_time {
+            lyrics[i].end_time = lyrics[i].start_time + 0.5;
+        }
+    }
+
+    // A last word without a closing timestamp lasts until the line ends
+    for lyric in &mut lyrics {
+        let line_duration = lyric.end_time - lyric.start_time;
+        if let Some(last) = lyric.syllables.as_mut().and_then(|s| s.last_mut()) {
+            if last.duration <= 0.0 {
+                last.duration = (line_duration - last.start_offset).max(0.0);
+            }
+        }
+    }
+
+    Ok((lyrics, metadata))
+}
+
+/// Seconds from an LRC `mm`, `ss`, `xx` capture (2-digit centiseconds or 3-digit milliseconds)
+fn lrc_time(cap: &regex::Captures) -> f64 {
+    let min: f64 = cap[1].parse().unwrap_or(0.0);
+    let sec: f64 = cap[2].parse().unwrap_or(0.0);
+    let raw_cs = &cap[3];
+    let cs: f64 = raw_cs.parse().unwrap_or(0.0);
+
+    // Handle 2 or 3 digit centiseconds/milliseconds
+    let cs_val = if raw_cs.len() == 3 {
+        cs / 1000.0
+    } else {
+        cs / 100.0
+    };
+
+    min * 60.0 + sec + cs_val
+}
+
+/// Split an LRC line body into enhanced LRC word timings.
+///
+/// `line_start` is the (un-offset) line timestamp. Text before the first
+/// `<mm:ss.xx>` tag starts with the line, and a trailing tag with no text
+/// after it marks when the last word ends.
+///
+/// Returns the clean text, the syllables (None without word timestamps) and
+/// the end of the last word relative to the line start, if given.
+fn parse_lrc_words(
+    body: &str,
+    word_regex: &Regex,
+    line_start: f64,
+) -> (String, Option<Vec<ParsedSyllable>>, Option<f64>) {
+    if !word_regex.is_match(body) {
+        return (body.trim().to_string(), None, None);
+    }
+
+    let relative = |cap: &regex::Captures| (lrc_time(cap) - line_start).max(0.0);
+
+    // (text, start relative to the line)
+    let mut words: Vec<(String, f64)> = Vec::new();
+    let mut word_start = 0.0;
+    let mut last_end = 0;
+    for cap in word_regex.captures_iter(body) {
+        let m = cap.get(0).unwrap();
+        let text = &body[last_end..m.start()];
+        if !text.is_empty() {
+            words.push((text.to_string(), word_start));
+        }
+        word_start = relative(&cap);
+        last_end = m.end();
+    }
+
+    let tail = &body[last_end..];
+    let words_end = if tail.trim().is_empty() {
+        Some(word_start)
+    } else {
+        words.push((tail.to_string(), word_start));
+        None
+    };
+
+    if let Some(first) = words.first_mut() {
+        first.0 = first.0.trim_start().to_string();
+    }
+    if let Some(last) = words.last_mut() {
+        last.0 = last.0.trim_end().to_string();
+    }
+    words.retain(|(text, _)| !text.is_empty());
+
+    let syllables: Vec<ParsedSyllable> = words
+        .iter()
+        .enumerate()
+        .map(|(i, (text, start))| {
+            let end = words.get(i + 1).map(|(_, next)| *next).or(words_end);
+            ParsedSyllable {
+                text: text.clone(),
+                start_offset: *start,
+                // 0 = unknown, extended to the line end once it is known
+                duration: end.map(|e| (e - start).max(0.0)).unwrap_or(0.0),
+                pitch: None,
+                ruby: None,
+                format: CharFormat::default(),
+            }
+        })
+        .collect();
+
+    let text: String = syllables.iter().map(|s| s.text.as_str()).collect();
+    (text, Some(syllables), words_end)
+}
+
+fn parse_srt(content: &str) -> Result<(Vec<ParsedLyric>, HashMap<String, String>)> {
+    let mut lyrics = Vec::new();
+    let metadata = HashMap::new();
+
+    // Split by double newline
+    let blocks: Vec<&str> = Regex::new(r"\n\s*\n").unwrap().split(content).collect();
+
+    // 00:00:20,000 --> 00:00:24,400
+    let timestamp_regex = Regex::new(
+        r"(\d{2}):(\d{2}):(\d{2})[,.](\d{3})\s*-->\s*(\d{2}):(\d{2}):(\d{2})[,.](\d{3})",
+    )
+    .unwrap();
+    let html_tag_re = Regex::new(r"<[^>]+>").unwrap();
+    let ass_tag_re = Regex::new(r"\{[^}]+\}").unwrap();
+
+    for block in blocks {
+        let lines: Vec<&str> = block.lines().collect();
+        if lines.len() < 2 {
+            continue;
+        }
+
+        let mut timestamp_line = "";
+        let mut text_lines = Vec::new();
+        let mut found_timestamp = false;
+
+        for line in lines {
+            if !found_timestamp && timestamp_regex.is_match(line) {
+                timestamp_line = line;
+                found_timestamp = true;
+            } else if found_timestamp {
+                text_lines.push(line.trim());
+            }
+        }
+
+        if !found_timestamp || text_lines.is_empty() {
+            continue;
+        }
+
+        let cap = timestamp_regex.captures(timestamp_line).unwrap();
+
+        // Start
+        let s_h: f64 = cap[1].parse().unwrap_or(0.0);
+        let s_m: f64 = cap[2].parse().unwrap_or(0.0);
+        let s_s: f64 = cap[3].parse().unwrap_or(0.0);
+        let s_ms: f64 = cap[4].parse().unwrap_or(0.0);
+        let start_time = s_h * 3600.0 + s_m * 60.0 + s_s + s_ms / 1000.0;
+
+        // End
+        let e_h: f64 = cap[5].parse().unwrap_or(0.0);
+        let e_m: f64 = cap[6].parse().unwrap_or(0.0);
+        let e_s: f64 = cap[7].parse().unwrap_or(0.0);
+        let e_ms: f64 = cap[8].parse().unwrap_or(0.0);
+        let end_time = e_h * 3600.0 + e_m * 60.0 + e_s + e_ms / 1000.0;
+
+        let raw_text = text_lines.join(" ");
+        // Strip HTML tags roughly
+        let text = html_tag_re.replace_all(&raw_text, "").to_string();
+        // Strip ASS/SSA tags roughly
+        let text = ass_tag_re.replace_all(&text, "").trim().to_string();
+
+        if !text.is_empty() {
+            lyrics.push(ParsedLyric {
+                text,
+                start_time,
+                end_time,
+                syllables: None,
+                raw_text: Some(raw_text), // Keep original with tags maybe?
+                style: None,
+                singer: None,
+                format: LineFormat::default(),
+            });
+        }
+    }
+
+    Ok((lyrics, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lrc() {
+        let content = "[00:12.00]Line 1\n[00:15.50]Line 2";
+        let (lyrics, _) = parse_lrc(content).unwrap();
+        assert_eq!(lyrics.len(), 2);
+        assert_eq!(lyrics[0].text, "Line 1");
+        assert_eq!(lyrics[0].start_time, 12.0);
+        assert_eq!(lyrics[0].end_time, 15.4); // 15.5 - 0.1
+    }
+
+    #[test]
+    fn test_parse_enhanced_lrc() {
+        let content = "[00:12.00]<00:12.00>Hello <00:12.50>big <00:13.00>world<00:14.00>\n[00:20.00]Next line";
+        let (lyrics, _) = parse_lrc(content).unwrap();
+        assert_eq!(lyrics.len(), 2);
+        assert_eq!(lyrics[0].text, "Hello big world");
+        // The trailing word timestamp ends the line
+        assert_eq!(lyrics[0].end_time, 14.0);
+
+        let syllables = lyrics[0].syllables.as_ref().unwrap();
+        assert_eq!(syllables.len(), 3);
+        assert_eq!(syllables[0].text, "Hello ");
+        assert_eq!(syllables[1].start_offset, 0.5);
+        assert_eq!(syllables[1].duration, 0.5);
+        assert_eq!(syllables[2].text, "world");
+        assert_eq!(syllables[2].duration, 1.0);
+        assert!(lyrics[1].syllables.is_none());
+    }
+
+    #[test]
+    fn test_parse_enhanced_lrc_open_end() {
+        // No closing timestamp: the last word lasts until the line ends
+        let content = "[00:10.00]One <00:11.00>two\n[00:14.10]Three";
+        let (lyrics, _) = parse_lrc(content).unwrap();
+        let syllables = lyrics[0].syllables.as_ref().unwrap();
+        assert_eq!(syllables[0].text, "One ");
+        assert_eq!(syllables[0].start_offset, 0.0);
+        assert_eq!(syllables[0].duration, 1.0);
+        assert!((syllables[1].duration - 3.0).abs() < 1e-9);
+
+        let doc = convert_to_klyric(lyrics, HashMap::new(), ImportOptions::default()).unwrap();
+        let chars = &doc.lines[0].chars;
+        assert_eq!(chars.len(), 7);
+        assert_eq!(chars[4].char, "t");
+        assert_eq!(chars[4].start, 11.0);
+        assert_eq!(chars[6].end, 14.0);
+    }
+
+    #[test]
+    fn test_parse_lrc_offset() {
+        // Positive offset shows lyrics sooner, even when the tag comes after the lines
+        let content = "[00:12.00]<00:12.00>Hi <00:13.00>there<00:14.00>\n[offset:+500]";
+        let (lyrics, metadata) = parse_lrc(content).unwrap();
+        assert_eq!(metadata.get("offset").map(String::as_str), Some("+500"));
+        assert_eq!(lyrics[0].start_time, 11.5);
+        assert_eq!(lyrics[0].end_time, 13.5);
+
+        let syllables = lyrics[0].syllables.as_ref().unwrap();
+        assert_eq!(syllables[1].start_offset, 1.0);
+        assert_eq!(syllables[1].duration, 1.0);
+    }
+
+    #[test]
+    fn test_merge_translation() {
+        let mut doc = import_subtitle(
+            "[00:01.00]你好\n[00:03.00]世界\n[00:05.00]再见\n[00:07.00]",
+            Some("song.lrc"),
+        )
+        .unwrap();
+        let srt = "1\n00:00:01,100 --> 00:00:02,900\nHello\n\n\
+                   2\n00:00:03,000 --> 00:00:03,900\nworld,\n\n\
+                   3\n00:00:04,000 --> 00:00:04,900\nyou\n\n\
+                   4\n00:00:30,000 --> 00:00:31,000\nlost";
+        let merged = merge_translation(&mut doc, srt, Some("en.srt"), Some("en")).unwrap();
+
+        assert_eq!(merged, 2);
+        let track = &doc.lines[0].translations[0];
+        assert_eq!(track.text, "Hello");
+        assert_eq!(track.lang.as_deref(), Some("en"));
+        assert_eq!(track.style.as_deref(), Some(TRANSLATION_STYLE));
+        assert_eq!(doc.lines[1].translations[0].text, "world, you");
+        assert!(doc.lines[2].translations.is_empty());
+        assert!(doc.styles.contains_key(TRANSLATION_STYLE));
+    }
+
+    #[test]
+    fn test_title_card() {
+        let options = ImportOptions { title_card: true };
+        let song = "[ti:Song]\n[ar:Band]\n[al:Record]\n[00:12.00]你好\n[00:14.00]";
+
+        // Opt-in only
+        let doc = import_subtitle(song, Some("song.lrc")).unwrap();
+        assert!(doc.overlays.is_empty());
+        assert!(!doc.styles.contains_key(TITLE_CARD_STYLE));
+
+        let doc = import_subtitle_with(song, Some("song.lrc"), options).unwrap();
+        assert_eq!(doc.project.album.as_deref(), Some("Record"));
+        assert_eq!(doc.overlays.len(), 2);
+        assert_eq!(doc.overlays[0].text.as_deref(), Some("Song"));
+        assert_eq!(doc.overlays[1].text.as_deref(), Some("Band — Record"));
+        assert_eq!(doc.overlays[0].end, TITLE_CARD_MAX);
+        assert!(doc.styles.contains_key(TITLE_CARD_STYLE));
+        assert!(doc.styles.contains_key(TITLE_CREDIT_STYLE));
+
+        // No room before the first line, or no title
+        let doc = import_subtitle_with(
+            "[ti:Song]\n[00:01.00]你好\n[00:03.00]",
+            Some("a.lrc"),
+            options,
+        )
+        .unwrap();
+        assert!(doc.overlays.is_empty());
+        let doc =
+            import_subtitle_with("[00:05.00]你好\n[00:07.00]", Some("a.lrc"), options).unwrap();
+        assert!(doc.overlays.is_empty());
+        assert!(!doc.styles.contains_key(TITLE_CARD_STYLE));
+    }
+
+    #[test]
+    fn test_parse_srt() {
+        let content = "1\n00:00:20,000 --> 00:00:24,400\nHello World\n\n2\n00:00:25,000 --> 00:00:28,000\nLine 2";
+        let (lyrics, _) = parse_srt(content).unwrap();
+        assert_eq!(lyrics.len(), 2);
+        assert_eq!(lyrics[0].text, "Hello World");
+        assert_eq!(lyrics[0].start_time, 20.0);
+        assert_eq!(lyrics[0].end_time, 24.4);
+    }
+}
@@ -0,0 +1,383 @@
+//! TTML (Timed Text Markup Language) lyrics import
+//!
+//! Targets the word-timed flavour used by music services: each `<p>` is a
+//! line, timed `<span>`s are syllables, `ttm:agent` identifies the singer and
+//! `ttm:role="x-bg"` spans hold background vocals.
+
+use anyhow::{anyhow, Result};
+use roxmltree::{Document, Node};
+use std::collections::{BTreeSet, HashMap};
+
+use super::{CharFormat, LineFormat, ParsedLyric, ParsedSyllable, BACKGROUND_STYLE};
+
+/// A run of text, timed when it came from a span with `begin`/`end`
+#[derive(Debug, Clone)]
+struct Run {
+    text: String,
+    time: Option<(f64, f64)>,
+}
+
+pub(super) fn parse_ttml(content: &str) -> Result<(Vec<ParsedLyric>, HashMap<String, String>)> {
+    let document = Document::parse(content)?;
+    let root = document.root_element();
+    if !is(root, "tt") {
+        return Err(anyhow!(
+            "Not a TTML document: root is <{}>",
+            root.tag_name().name()
+        ));
+    }
+
+    let mut metadata = HashMap::new();
+    if let Some(title) = find(root, "head").and_then(|h| find(h, "title")) {
+        let title = collapse_whitespace(&text(title));
+        if !title.is_empty() {
+            metadata.insert("title".to_string(), title);
+        }
+    }
+
+    let body = find(root, "body").ok_or_else(|| anyhow!("TTML document has no <body>"))?;
+    let paragraphs: Vec<Node> = body.descendants().filter(|n| is(*n, "p")).collect();
+
+    // Agents only become singers when there is more than one to tell apart
+    let agents: BTreeSet<&str> = paragraphs
+        .iter()
+        .filter_map(|p| attr(*p, "agent"))
+        .collect();
+    let use_agents = agents.len() > 1;
+    let relative = relative_span_times(&paragraphs);
+
+    let mut lyrics = Vec::new();
+    for p in paragraphs {
+        let p_begin = attr(p, "begin").and_then(parse_time);
+        let p_end = attr(p, "end").and_then(parse_time).or_else(|| {
+            let dur = attr(p, "dur").and_then(parse_time)?;
+            Some(p_begin? + dur)
+        });
+        let offset = if relative {
+            p_begin.unwrap_or(0.0)
+        } else {
+            0.0
+        };
+
+        let mut main = Vec::new();
+        let mut background = Vec::new();
+        collect_runs(p, offset, false, &mut main, &mut background);
+
+        if let Some(mut lyric) = build_lyric(main, p_begin, p_end, None) {
+            lyric.singer = attr(p, "agent")
+                .filter(|_| use_agents)
+                .map(|a| a.to_string());
+            lyrics.push(lyric);
+        }
+        if let Some(lyric) = build_lyric(background, None, None, Some(BACKGROUND_STYLE.into())) {
+            lyrics.push(lyric);
+        }
+    }
+
+    lyrics.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    Ok((lyrics, metadata))
+}
+
+/// Whether element `node` has the local name `name`
+fn is(node: Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+/// First descendant (depth-first, including self) with the given local name
+fn find<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.descendants().find(|n| is(*n, name))
+}
+
+/// Attribute value by local name, ignoring any namespace
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| a.name() == name)
+        .map(|a| a.value())
+}
+
+/// Concatenated text content of a node and its descendants
+fn text(node: Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+/// Music-service TTML writes absolute span times; strict TTML makes them
+/// relative to the paragraph. The document is read as relative when any
+/// span starts before its paragraph does.
+fn relative_span_times(paragraphs: &[Node]) -> bool {
+    paragraphs.iter().any(|p| {
+        let Some(p_begin) = attr(*p, "begin").and_then(parse_time) else {
+            return false;
+        };
+        p.descendants()
+            .filter(|n| is(*n, "span"))
+            .filter_map(|span| attr(span, "begin").and_then(parse_time))
+            .any(|begin| begin < p_begin)
+    })
+}
+
+/// Flatten an element's content into runs, routing `x-bg` spans to `background`
+fn collect_runs(
+    element: Node,
+    offset: f64,
+    in_background: bool,
+    main: &mut Vec<Run>,
+    background: &mut Vec<Run>,
+) {
+    for child in element.children() {
+        let out = if in_background {
+            &mut *background
+        } else {
+            &mut *main
+        };
+        if child.is_text() {
+            out.push(Run {
+                text: child.text().unwrap_or_default().to_string(),
+                time: None,
+            });
+        } else if is(child, "br") {
+            out.push(Run {
+                text: " ".to_string(),
+                time: None,
+            });
+        } else if is(child, "span") {
+            if attr(child, "role") == Some("x-bg") {
+                collect_runs(child, offset, true, main, background);
+                continue;
+            }
+            match span_time(child, offset) {
+                // Timed leaf span: one syllable
+                Some(time) if !child.children().any(|c| c.is_element()) => out.push(Run {
+                    text: text(child),
+                    time: Some(time),
+                }),
+                _ => collect_runs(child, offset, in_background, main, background),
+            }
+        }
+    }
+}
+
+/// Span timing in absolute seconds; `offset` is the paragraph start when the
+/// document's span times are relative
+fn span_time(span: Node, offset: f64) -> Option<(f64, f64)> {
+    let begin = attr(span, "begin").and_then(parse_time)?;
+    let end = attr(span, "end")
+        .and_then(parse_time)
+        .or_else(|| attr(span, "dur").and_then(parse_time).map(|d| begin + d))?;
+    Some((begin + offset, end + offset))
+}
+
+/// Turn a paragraph's runs into a line. Untimed text between spans becomes a
+/// zero-length syllable at the end of the previous one.
+fn build_lyric(
+    runs: Vec<Run>,
+    begin: Option<f64>,
+    end: Option<f64>,
+    style: Option<String>,
+) -> Option<ParsedLyric> {
+    let timed = runs.iter().filter_map(|r| r.time);
+    let first = timed.clone().map(|t| t.0).reduce(f64::min);
+    let last = timed.map(|t| t.1).reduce(f64::max);
+    let start_time = begin.or(first)?;
+    let end_time = end.or(last)?.max(start_time);
+
+    // Collapse whitespace across run boundaries
+    let mut pieces: Vec<(String, Option<(f64, f64)>)> = Vec::new();
+    let mut last_space = true;
+    for run in runs {
+        let mut text = String::new();
+        for c in run.text.chars() {
+            if c.is_whitespace() {
+                if !last_space {
+                    text.push(' ');
+                }
+                last_space = true;
+            } else {
+                text.push(c);
+                last_space = false;
+            }
+        }
+        if !text.is_empty() {
+            pieces.push((text, run.time));
+        }
+    }
+    // Drop trailing space
+    if let Some((text, _)) = pieces.last_mut() {
+        let trimmed_len = text.trim_end().len();
+        text.truncate(trimmed_len);
+        if text.is_empty() {
+            pieces.pop();
+        }
+    }
+
+    let text: String = pieces.iter().map(|(t, _)| t.as_str()).collect();
+    if text.is_empty() {
+        return None;
+    }
+
+    let syllables = if first.is_some() {
+        let mut syllables: Vec<ParsedSyllable> = Vec::new();
+        for (text, time) in pieces {
+            let (start_offset, duration) = match time {
+                Some((b, e)) => (b - start_time, (e - b).max(0.0)),
+                None => (
+                    syllables
+                        .last()
+                        .map(|s| s.start_offset + s.duration)
+                        .unwrap_or(0.0),
+                    0.0,
+                ),
+            };
+            syllables.push(ParsedSyllable {
+                text,
+                start_offset,
+                duration,
+                pitch: None,
+                ruby: None,
+                format: CharFormat::default(),
+            });
+        }
+        Some(syllables)
+    } else {
+        None
+    };
+
+    Some(ParsedLyric {
+        text,
+        start_time,
+        end_time,
+        syllables,
+        raw_text: None,
+        style,
+        singer: None,
+        format: LineFormat::default(),
+    })
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parse a TTML time expression: clock time (`hh:mm:ss.fff`, `mm:ss.fff`),
+/// offset time (`12.5s`, `350ms`, `1.5m`, `1h`) or bare seconds.
+fn parse_time(s: &str) -> Option<f64> {
+    let s = s.trim();
+    if s.contains(':') {
+        let parts: Vec<f64> = s
+            .split(':')
+            .map(|p| p.parse::<f64>().ok())
+            .collect::<Option<_>>()?;
+        return match parts[..] {
+            [m, sec] => Some(m * 60.0 + sec),
+            [h, m, sec] => Some(h * 3600.0 + m * 60.0 + sec),
+            // hh:mm:ss:frames, assuming 30 fps
+            [h, m, sec, frames] => Some(h * 3600.0 + m * 60.0 + sec + frames / 30.0),
+            _ => None,
+        };
+    }
+
+    if let Some(n) = s.strip_suffix("ms") {
+        return n.parse::<f64>().ok().map(|ms| ms / 1000.0);
+    }
+    let (number, scale) = if let Some(n) = s.strip_suffix('h') {
+        (n, 3600.0)
+    } else if let Some(n) = s.strip_suffix('m') {
+        (n, 60.0)
+    } else if let Some(n) = s.strip_suffix('s') {
+        (n, 1.0)
+    } else {
+        (s, 1.0)
+    };
+    number.parse::<f64>().ok().map(|n| n * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("00:01:02.500"), Some(62.5));
+        assert_eq!(parse_time("1:02.5"), Some(62.5));
+        assert_eq!(parse_time("12.5s"), Some(12.5));
+        assert_eq!(parse_time("350ms"), Some(0.35));
+        assert_eq!(parse_time("1.5m"), Some(90.0));
+        assert_eq!(parse_time("4.25"), Some(4.25));
+        assert_eq!(parse_time("abc"), None);
+    }
+
+    #[test]
+    fn test_parse_ttml() {
+        let ttml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xmlns:ttm="http://www.w3.org/ns/ttml#metadata">
+  <head>
+    <metadata>
+      <ttm:title>Duet</ttm:title>
+      <ttm:agent type="person" xml:id="v1"/>
+      <ttm:agent type="person" xml:id="v2"/>
+    </metadata>
+  </head>
+  <body>
+    <div>
+      <p begin="00:00:01.000" end="00:00:03.000" ttm:agent="v1"><span begin="00:00:01.000" end="00:00:01.500">Hel</span><span begin="00:00:01.500" end="00:00:02.000">lo</span> <span begin="00:00:02.000" end="00:00:03.000">world</span><span ttm:role="x-bg"><span begin="00:00:02.200" end="00:00:02.800">(oh)</span></span></p>
+      <p begin="4s" end="5s" ttm:agent="v2">Plain &amp; <![CDATA[simple]]></p>
+    </div>
+  </body>
+</tt>"#;
+
+        let (lyrics, metadata) = parse_ttml(ttml).unwrap();
+        assert_eq!(metadata.get("title").map(String::as_str), Some("Duet"));
+        assert_eq!(lyrics.len(), 3);
+
+        let first = &lyrics[0];
+        assert_eq!(first.text, "Hello world");
+        assert_eq!(first.singer.as_deref(), Some("v1"));
+        assert_eq!((first.start_time, first.end_time), (1.0, 3.0));
+        let syllables = first.syllables.as_ref().unwrap();
+        let texts: Vec<&str> = syllables.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hel", "lo", " ", "world"]);
+        assert!((syllables[1].start_offset - 0.5).abs() < 1e-9);
+        // The space sits at the end of "lo" with no duration
+        assert!((syllables[2].start_offset - 1.0).abs() < 1e-9);
+        assert_eq!(syllables[2].duration, 0.0);
+
+        let bg = &lyrics[1];
+        assert_eq!(bg.text, "(oh)");
+        assert_eq!(bg.style.as_deref(), Some(BACKGROUND_STYLE));
+        assert!((bg.start_time - 2.2).abs() < 1e-9);
+
+        let plain = &lyrics[2];
+        assert_eq!(plain.text, "Plain & simple");
+        assert_eq!(plain.singer.as_deref(), Some("v2"));
+        assert!(plain.syllables.is_none());
+        assert_eq!((plain.start_time, plain.end_time), (4.0, 5.0));
+    }
+
+    #[test]
+    fn test_relative_span_times() {
+        let ttml = r#"<tt><body><p begin="10s" end="12s"><span begin="0s" end="1s">a</span><span begin="1s" end="2s">b</span></p></body></tt>"#;
+        let (lyrics, _) = parse_ttml(ttml).unwrap();
+        let syllables = lyrics[0].syllables.as_ref().unwrap();
+        assert_eq!(syllables[0].start_offset, 0.0);
+        assert_eq!(syllables[1].start_offset, 1.0);
+        // Single/no agent: no singer
+        assert!(lyrics[0].singer.is_none());
+
+        // Relative for the whole document, even where a span time is past
+        // its paragraph's start
+        let ttml = r#"<tt><body><p begin="2s" end="8s"><span begin="0s" end="3s">a</span><span begin="3s" end="6s">b</span></p><p begin="10s" end="20s"><span begin="0s" end="1s">c</span></p></body></tt>"#;
+        let (lyrics, _) = parse_ttml(ttml).unwrap();
+        let syllables = lyrics[0].syllables.as_ref().unwrap();
+        assert_eq!(syllables[1].start_offset, 3.0);
+        assert_eq!((lyrics[1].start_time, lyrics[1].end_time), (10.0, 20.0));
+    }
+
+    #[test]
+    fn test_malformed_xml() {
+        assert!(parse_ttml("<tt><body></tt>").is_err());
+        assert!(parse_ttml("just text").is_err());
+        assert!(parse_ttml("<tt><body><p ttm:agent=\"v1\">a</p></body></tt>").is_err());
+    }
+}
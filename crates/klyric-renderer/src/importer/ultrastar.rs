@@ -0,0 +1,267 @@
+//! UltraStar karaoke `.txt` import
+//!
+//! Header tags (`#BPM:`, `#GAP:`, ...) followed by note lines
+//! `<type> <beat> <length> <pitch> <syllable>` and `-` line breaks.
+//! Beats are quarter-notes of the BPM: one beat lasts `60 / (BPM * 4)` seconds.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+use super::{push_syllable, LineFormat, ParsedLyric, ParsedSyllable};
+
+/// UltraStar pitch 0 is middle C
+const MIDDLE_C: i32 = 60;
+
+/// Whether the content looks like an UltraStar file
+pub(super) fn is_ultrastar(content: &str) -> bool {
+    content
+        .lines()
+        .take_while(|l| l.trim_start().starts_with('#') || l.trim().is_empty())
+        .any(|l| l.trim_start().to_uppercase().starts_with("#BPM:"))
+}
+
+fn parse_number(s: &str) -> Option<f64> {
+    // Decimal commas are common (`#BPM:302,5`)
+    s.trim().replace(',', ".").parse().ok()
+}
+
+/// Split `<beat> <length> <pitch> <text>`; the text keeps its own spacing
+fn note_fields(rest: &str) -> Option<([f64; 3], &str)> {
+    let mut rest = rest;
+    let mut numbers = [0.0; 3];
+    for n in &mut numbers {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        *n = parse_number(&rest[..end])?;
+        rest = &rest[end..];
+    }
+    let text = rest
+        .strip_prefix(' ')
+        .or_else(|| rest.strip_prefix('\t'))
+        .unwrap_or(rest);
+    Some((numbers, text))
+}
+
+#[derive(Default)]
+struct LineBuilder {
+    /// Absolute (start, end, text, pitch) per note
+    notes: Vec<(f64, f64, String, Option<i32>)>,
+}
+
+impl LineBuilder {
+    fn finish(&mut self, singer: Option<String>, lyrics: &mut Vec<ParsedLyric>) {
+        let notes = std::mem::take(&mut self.notes);
+        let (Some(first), Some(last)) = (notes.first(), notes.last()) else {
+            return;
+        };
+        let start_time = first.0;
+        let end_time = notes.iter().map(|n| n.1).fold(last.1, f64::max);
+
+        let mut syllables: Vec<ParsedSyllable> = Vec::new();
+        for (start, end, text, pitch) in &notes {
+            push_syllable(
+                &mut syllables,
+                text,
+                start - start_time,
+                end - start,
+                *pitch,
+            );
+        }
+        // No leading or trailing blanks on the line itself
+        while syllables.first().is_some_and(|s| s.text.trim().is_empty()) {
+            syllables.remove(0);
+        }
+        while syllables.last().is_some_and(|s| s.text.trim().is_empty()) {
+            syllables.pop();
+        }
+
+        let text: String = syllables.iter().map(|s| s.text.as_str()).collect();
+        if text.is_empty() {
+            return;
+        }
+        lyrics.push(ParsedLyric {
+            text,
+            start_time,
+            end_time,
+            syllables: Some(syllables),
+            raw_text: None,
+            style: None,
+            singer,
+            format: LineFormat::default(),
+        });
+    }
+}
+
+pub(super) fn parse_ultrastar(
+    content: &str,
+) -> Result<(Vec<ParsedLyric>, HashMap<String, String>)> {
+    let mut metadata = HashMap::new();
+    let mut bpm = None;
+    let mut gap = 0.0;
+    let mut relative = false;
+
+    let mut lyrics = Vec::new();
+    let mut line = LineBuilder::default();
+    // Beat offset of the current line in RELATIVE mode
+    let mut line_offset = 0.0;
+    let mut player: Option<String> = None;
+
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        let trimmed = raw.trim_start();
+
+        if let Some(tag) = trimmed.strip_prefix('#') {
+            if let Some((key, value)) = tag.split_once(':') {
+                let value = value.trim();
+                match key.trim().to_uppercase().as_str() {
+                    "TITLE" => {
+                        metadata.insert("title".to_string(), value.to_string());
+                    }
+                    "ARTIST" => {
+                        metadata.insert("artist".to_string(), value.to_string());
+                    }
+                    "BPM" => {
+                        let value = parse_number(value)
+                            .filter(|b| b.is_finite() && *b > 0.0)
+                            .ok_or_else(|| {
+                                anyhow!("UltraStar #BPM must be a positive number, got {:?}", value)
+                            })?;
+                        bpm = Some(value);
+                    }
+                    "GAP" => gap = parse_number(value).unwrap_or(0.0) / 1000.0,
+                    "RELATIVE" => relative = value.eq_ignore_ascii_case("yes"),
+                    _ => {}
+                }
+            }
+            continue;
+        }
+
+        let mut chars = trimmed.chars();
+        let Some(kind) = chars.next() else {
+            continue;
+        };
+        // Header tags may come in any order, so only notes need the BPM
+        if matches!(kind, ':' | '*' | 'F' | 'R' | 'G' | '-') && bpm.is_none() {
+            return Err(anyhow!("UltraStar file has no #BPM"));
+        }
+        match kind {
+            ':' | '*' | 'F' | 'R' | 'G' => {
+                let beat_secs = 60.0 / (bpm.unwrap_or_default() * 4.0);
+                let time = |beat: f64| gap + beat * beat_secs;
+                let Some(([beat, length, pitch], text)) = note_fields(chars.as_str()) else {
+                    log::warn!("Skipping malformed UltraStar note: {}", raw);
+                    continue;
+                };
+                let start = time(line_offset + beat);
+                let end = time(line_offset + beat + length);
+                // Freestyle and rap notes have no meaningful pitch
+                let pitch = matches!(kind, ':' | '*').then_some(pitch as i32 + MIDDLE_C);
+
+                // `~` continues the previous syllable on a new note
+                if text.trim() == "~" {
+                    if let Some(prev) = line.notes.last_mut() {
+                        prev.1 = end;
+                        continue;
+                    }
+                }
+                line.notes.push((start, end, text.to_string(), pitch));
+            }
+            '-' => {
+                line.finish(player.clone(), &mut lyrics);
+                if relative {
+                    let beats: Vec<f64> = chars
+                        .as_str()
+                        .split_whitespace()
+                        .filter_map(parse_number)
+                        .collect();
+                    // `- <end> <next start>`; with one value the next line starts there
+                    line_offset += beats.get(1).or(beats.first()).copied().unwrap_or(0.0);
+                }
+            }
+            'P' => {
+                line.finish(player.clone(), &mut lyrics);
+                player = Some(format!("P{}", chars.as_str().trim()));
+                line_offset = 0.0;
+            }
+            'E' => break,
+            _ => {}
+        }
+    }
+    line.finish(player, &mut lyrics);
+
+    if bpm.is_none() {
+        return Err(anyhow!("UltraStar file has no #BPM"));
+    }
+    lyrics.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    Ok((lyrics, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SONG: &str = "#TITLE:Test Song\n#ARTIST:Someone\n#BPM:150\n#GAP:1000\n: 0 4 0 Hel\n: 4 4 2 lo \n* 8 4 4 world\n: 12 2 5 ~\n- 16\nF 20 4 0 yeah\nE\n";
+
+    #[test]
+    fn test_parse_ultrastar() {
+        assert!(is_ultrastar(SONG));
+        let (lyrics, metadata) = parse_ultrastar(SONG).unwrap();
+        assert_eq!(metadata.get("title").map(String::as_str), Some("Test Song"));
+        assert_eq!(metadata.get("artist").map(String::as_str), Some("Someone"));
+        assert_eq!(lyrics.len(), 2);
+
+        // 150 BPM: one beat = 0.1s, offset by the 1s gap
+        let first = &lyrics[0];
+        assert_eq!(first.text, "Hello world");
+        assert!((first.start_time - 1.0).abs() < 1e-9);
+        // `~` extends "world" to beat 14
+        assert!((first.end_time - 2.4).abs() < 1e-9);
+
+        let syllables = first.syllables.as_ref().unwrap();
+        let texts: Vec<&str> = syllables.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hel", "lo", " ", "world"]);
+        assert_eq!(syllables[0].pitch, Some(60));
+        assert_eq!(syllables[1].pitch, Some(62));
+        assert_eq!(syllables[2].duration, 0.0);
+        assert_eq!(syllables[3].pitch, Some(64));
+        assert!((syllables[3].duration - 0.6).abs() < 1e-9);
+
+        // Freestyle note: no pitch
+        assert_eq!(lyrics[1].syllables.as_ref().unwrap()[0].pitch, None);
+    }
+
+    #[test]
+    fn test_relative_and_duet() {
+        let song = "#BPM:300\n#RELATIVE:YES\nP1\n: 0 2 0 a\n- 4 10\n: 0 2 0 b\nP2\n: 0 2 7 c\nE\n";
+        let (lyrics, _) = parse_ultrastar(song).unwrap();
+        assert_eq!(lyrics.len(), 3);
+        assert_eq!(lyrics[0].singer.as_deref(), Some("P1"));
+        // P2 restarts at beat 0
+        assert_eq!(lyrics[1].text, "c");
+        assert_eq!(lyrics[1].singer.as_deref(), Some("P2"));
+        assert_eq!(lyrics[1].syllables.as_ref().unwrap()[0].pitch, Some(67));
+        // One beat = 0.05s; the second P1 line starts 10 beats later
+        assert_eq!(lyrics[2].text, "b");
+        assert!((lyrics[2].start_time - 0.5).abs() < 1e-9);
+
+        assert!(parse_ultrastar(": 0 1 0 x\n").is_err());
+    }
+
+    #[test]
+    fn test_invalid_bpm() {
+        for bpm in ["0", "-120", "inf", "NaN", "fast"] {
+            let song = format!("#BPM:{}\n: 0 2 0 a\nE\n", bpm);
+            let err = parse_ultrastar(&song).unwrap_err();
+            assert!(err.to_string().contains("#BPM"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_blank_line_before_bpm() {
+        let song = "#TITLE:Gaps\n\n#BPM:150\n  \n: 0 4 0 a\nE\n";
+        let (lyrics, metadata) = parse_ultrastar(song).unwrap();
+        assert_eq!(metadata.get("title").map(String::as_str), Some("Gaps"));
+        assert_eq!(lyrics.len(), 1);
+        assert!((lyrics[0].end_time - 0.4).abs() < 1e-9);
+    }
+}
@@ -37,12 +37,18 @@ pub struct GlyphInfo {
     /// [Bolt Optimization] Cached Path Bounds (Native only)
     #[cfg(not(target_arch = "wasm32"))]
     pub bounds: Option<Rect>,
+    /// For ruby glyphs, the part `(from, to)` of the base char's duration,
+    /// as fractions, in which this glyph is sung. `None` for base text.
+    pub ruby: Option<(f32, f32)>,
 }
 
 pub struct LayoutEngine;
 
 impl LayoutEngine {
-    /// Calculate glyph positions for a line of text
+    /// Calculate glyph positions for a line of text.
+    ///
+    /// Ruby glyphs follow all base glyphs in the result, centered above the
+    /// char they annotate on a shared baseline.
     pub fn layout_line(
         line: &Line,
         resolved_style: &Style,
@@ -84,6 +90,10 @@ impl LayoutEngine {
             .and_then(|f| f.size)
             .unwrap_or(style_size);
 
+        // Chars with ruby, laid out once the base text is placed:
+        // (char index, run start x, run end x, typeface, base size)
+        let mut ruby_runs: Vec<(usize, f32, f32, Option<Typeface>, f32)> = Vec::new();
+
         // Cache for ResolvedFont to avoid recreation overhead
         #[cfg(not(target_arch = "wasm32"))]
         let mut cached_font: Option<ResolvedFont> = None;
@@ -190,6 +200,8 @@ impl LayoutEngine {
                 (shadow_col, stroke_col)
             };
 
+            let run_start = cursor_x;
+
             // For each character in the string
             for ch in ch_str.chars() {
                 // Try to get font
@@ -242,6 +254,7 @@ impl LayoutEngine {
                         path,
                         #[cfg(not(target_arch = "wasm32"))]
                         bounds,
+                        ruby: None,
                     });
 
                     cursor_x += advance;
@@ -252,10 +265,40 @@ impl LayoutEngine {
                 }
             }
 
+            if char_data.ruby.as_deref().is_some_and(|r| !r.is_empty()) {
+                ruby_runs.push((i, run_start, cursor_x, font_ref.clone(), size));
+            }
+
             // Add gap after each KLyric char unit
             cursor_x += gap;
         }
 
+        // Ruby shares one baseline above the tallest base glyph
+        if !ruby_runs.is_empty() {
+            let ruby_style = resolved_style.ruby.clone().unwrap_or_default();
+            let top = glyphs.iter().map(glyph_top).fold(0.0, f32::min);
+            let baseline = top - ruby_style.gap_or_default();
+            for (i, left, right, typeface, size) in ruby_runs {
+                let (Some(typeface), Some(text)) = (typeface, line.chars[i].ruby.as_deref()) else {
+                    continue;
+                };
+                // The char's first glyph, for its index and override colors
+                let Some(base) = glyphs.iter().find(|g| g.char_index == i) else {
+                    continue;
+                };
+                let ruby = Self::layout_ruby(
+                    text,
+                    base,
+                    (left, right),
+                    baseline,
+                    &typeface,
+                    size * ruby_style.scale_or_default(),
+                    renderer,
+                );
+                glyphs.extend(ruby);
+            }
+        }
+
         // Remove trailing gap for width calculation
         let total_width = if cursor_x > gap { cursor_x - gap } else { 0.0 };
 
@@ -281,6 +324,114 @@ impl LayoutEngine {
 
         glyphs
     }
+
+    /// Lowest point of laid out text, relative to the line baseline
+    pub fn text_bottom(glyphs: &[GlyphInfo]) -> f32 {
+        glyphs
+            .iter()
+            .map(|g| g.y + glyph_bottom(g))
+            .fold(0.0, f32::max)
+    }
+
+    /// Move `glyphs` down so their top sits under `bottom`, the
+    /// `text_bottom` of the text above, separated by a quarter of their
+    /// font size. Used to stack translation tracks under a line.
+    pub fn stack_below(glyphs: &mut [GlyphInfo], bottom: f32) {
+        let Some(first) = glyphs.first() else {
+            return;
+        };
+        let gap = first.font_size * 0.25;
+        let top = glyphs
+            .iter()
+            .map(|g| g.y + glyph_top(g))
+            .fold(f32::INFINITY, f32::min);
+        let offset = bottom + gap - top;
+        for glyph in glyphs {
+            glyph.y += offset;
+        }
+    }
+
+    /// Lay out ruby `text` centered over `left..right`, the run of `base`'s
+    /// char. Each glyph is sung in an equal share of the char's duration, in
+    /// order, and keeps the char's override colors.
+    fn layout_ruby(
+        text: &str,
+        base: &GlyphInfo,
+        (left, right): (f32, f32),
+        baseline: f32,
+        typeface: &Typeface,
+        size: f32,
+        renderer: &mut TextRenderer,
+    ) -> Vec<GlyphInfo> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let font = renderer.get_resolved_font(typeface, size);
+
+        let count = text.chars().count() as f32;
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut cursor_x = 0.0;
+        for (k, ch) in text.chars().enumerate() {
+            #[cfg(not(target_arch = "wasm32"))]
+            let (advance, height, glyph_id) = renderer.measure_char_with_font(&font, ch);
+            #[cfg(target_arch = "wasm32")]
+            let (advance, height, glyph_id) = renderer.measure_char(typeface, ch, size);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let path = renderer.get_path_cached(typeface, size, glyph_id);
+
+            glyphs.push(GlyphInfo {
+                char: ch,
+                x: cursor_x,
+                y: baseline,
+                width: advance,
+                height,
+                advance,
+                char_index: base.char_index,
+                glyph_id,
+                font_size: size,
+                typeface: Some(typeface.clone()),
+                #[cfg(not(target_arch = "wasm32"))]
+                override_shadow_color: base.override_shadow_color,
+                #[cfg(not(target_arch = "wasm32"))]
+                override_stroke_color: base.override_stroke_color,
+                #[cfg(not(target_arch = "wasm32"))]
+                bounds: path.as_ref().map(|p| p.bounds()),
+                #[cfg(not(target_arch = "wasm32"))]
+                path,
+                ruby: Some((k as f32 / count, (k + 1) as f32 / count)),
+            });
+            cursor_x += advance;
+        }
+
+        let offset = (left + right - cursor_x) / 2.0;
+        for glyph in &mut glyphs {
+            glyph.x += offset;
+        }
+        glyphs
+    }
+}
+
+/// Top of a glyph relative to its baseline (negative is up)
+fn glyph_top(glyph: &GlyphInfo) -> f32 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Some(bounds) = glyph.bounds {
+            return bounds.top;
+        }
+    }
+    // Roughly the cap height
+    -glyph.font_size * 0.8
+}
+
+/// Bottom of a glyph relative to its baseline (positive is down)
+fn glyph_bottom(glyph: &GlyphInfo) -> f32 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if let Some(bounds) = glyph.bounds {
+            return bounds.bottom;
+        }
+    }
+    // Roughly the descender
+    glyph.font_size * 0.2
 }
 
 #[cfg(test)]
@@ -302,10 +453,16 @@ mod tests {
             colors: None,
             stroke: None,
             shadow: None,
+            strokes: None,
+            shadows: None,
             glow: None,
+            mask: None,
             transform: None,
             effects: None,
             layers: None,
+            ruby: None,
+            reset: Vec::new(),
+            append: Vec::new(),
         }
     }
 
@@ -317,9 +474,11 @@ mod tests {
             end: 5.0,
             text: Some(chars.join("")),
             style: None,
+            singer: None,
             font: None,
             stroke: None,
             shadow: None,
+            mask: None,
             effects: Vec::new(),
             position: None,
             transform: None,
@@ -337,8 +496,13 @@ mod tests {
                     shadow: None,
                     effects: Vec::new(),
                     transform: None,
+                    pitch: None,
+                    ruby: None,
+                    singer: None,
                 })
                 .collect(),
+            translations: Vec::new(),
+            track: None,
         }
     }
 
@@ -388,8 +552,52 @@ mod tests {
         assert_eq!(glyphs[1].char_index, 1);
     }
 
+    #[test]
+    fn test_layout_ruby() {
+        // Ruby glyphs follow the base glyphs, centered above their char
+        let mut line = test_line(vec!["AB", "C"]);
+        line.chars[0].ruby = Some("xy".to_string());
+        let style = test_style();
+        let mut renderer = TextRenderer::new();
+
+        let glyphs = LayoutEngine::layout_line(&line, &style, &mut renderer);
+        assert_eq!(glyphs.len(), 5);
+        assert!(glyphs[..3].iter().all(|g| g.ruby.is_none()));
+        let ruby = &glyphs[3..];
+        assert_eq!(ruby[0].char, 'x');
+        assert_eq!(ruby[0].char_index, 0);
+        assert_eq!(ruby[0].ruby, Some((0.0, 0.5)));
+        assert_eq!(ruby[1].ruby, Some((0.5, 1.0)));
+        assert!(ruby[0].y < 0.0, "Ruby should sit above the baseline");
+        assert!(approx_eq(ruby[0].font_size, 24.0, 0.01));
+
+        let base_center = (glyphs[0].x + glyphs[1].x + glyphs[1].advance) / 2.0;
+        let ruby_center = (ruby[0].x + ruby[1].x + ruby[1].advance) / 2.0;
+        assert!(
+            approx_eq(base_center, ruby_center, 0.5),
+            "Ruby should be centered over its base: {} vs {}",
+            ruby_center,
+            base_center
+        );
+    }
+
     // --- Alignment Tests ---
 
+    #[test]
+    fn test_stack_below() {
+        let mut renderer = TextRenderer::new();
+        let style = test_style();
+        let main = LayoutEngine::layout_line(&test_line(vec!["A", "g"]), &style, &mut renderer);
+        let bottom = LayoutEngine::text_bottom(&main);
+        assert!(bottom > 0.0, "Descenders reach below the baseline");
+
+        let mut below = LayoutEngine::layout_line(&test_line(vec!["B"]), &style, &mut renderer);
+        LayoutEngine::stack_below(&mut below, bottom);
+        let top = below[0].y + glyph_top(&below[0]);
+        assert!(approx_eq(top, bottom + 12.0, 0.01), "top {}", top);
+        assert!(LayoutEngine::text_bottom(&below) > bottom);
+    }
+
     #[test]
     fn test_alignment_left() {
         // Left alignment: first glyph at x=0
@@ -42,9 +42,12 @@
 //! └─────────────────┘
 //! ```
 
+pub mod audio;
 pub mod effects;
+pub mod exporter;
 pub mod importer;
 pub mod layout;
+pub mod migrate;
 pub mod model;
 pub mod parser;
 pub mod particle;
@@ -53,14 +56,16 @@ pub mod renderer;
 pub mod style;
 pub mod text;
 pub mod utils;
+pub mod validate;
 
 pub mod expressions;
 
 // Re-exports for convenience
 pub use model::*;
-pub use parser::parse_document;
+pub use parser::{load_document, parse_document, parse_document_with_report};
 pub use renderer::Renderer;
 pub use text::TextRenderer;
+pub use validate::{validate_document, validate_json, Diagnostic, Severity};
 
 pub use particle::{Particle, ParticleConfig, ParticleEmitter};
 pub use presets::{EffectPreset, PresetFactory};
@@ -0,0 +1,450 @@
+//! Document format versioning
+//!
+//! Documents are upgraded on the raw `serde_json::Value` before they reach the
+//! model: each `Migration` step rewrites one version into the next and records
+//! what it changed. The writer goes the other way and stamps the oldest
+//! version that can still represent the document, so files that don't use new
+//! features stay readable by older renderers, and files that do are rejected
+//! by them instead of being rendered wrong.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use std::borrow::Cow;
+
+use crate::model::{KLyricDocumentV2, Modifier, Shadow, Transform};
+
+/// Newest format version this build reads and writes
+pub const CURRENT_VERSION: &str = "2.2";
+
+/// One upgrade step between consecutive format versions
+pub struct Migration {
+    /// Version this step upgrades from
+    pub from: &'static str,
+    /// Version this step produces
+    pub to: &'static str,
+    /// Rewrite the document in place, describing each change
+    pub upgrade: fn(&mut Value, &mut Vec<String>),
+    /// Whether a current document needs `to` to be read correctly.
+    ///
+    /// Steps that only add optional fields return true when those fields are
+    /// used; steps that restructure existing data always return true.
+    pub needs: fn(&KLyricDocumentV2) -> bool,
+}
+
+/// Upgrade steps, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    // 2.1: `imports` of external style libraries
+    Migration {
+        from: "2.0",
+        to: "2.1",
+        upgrade: |_, _| {},
+        needs: |doc| !doc.imports.is_empty(),
+    },
+    // 2.2: style `reset`/`append` merge directives, ruby text, translations,
+    // singers, sections, tracks, overlays, masks, gradient and pattern fills,
+    // layered strokes and shadows, brightness/contrast/saturation/invert
+    // color adjustments
+    Migration {
+        from: "2.1",
+        to: "2.2",
+        upgrade: |_, _| {},
+        needs: needs_2_2,
+    },
+];
+
+/// Whether `doc` uses any field added in 2.2
+fn needs_2_2(doc: &KLyricDocumentV2) -> bool {
+    let inset = |shadow: &Option<Shadow>| shadow.as_ref().is_some_and(|s| s.inset.is_some());
+    let adjusts = |transform: &Option<Transform>| {
+        transform.as_ref().is_some_and(|t| {
+            t.brightness.is_some()
+                || t.contrast.is_some()
+                || t.saturation.is_some()
+                || t.invert.is_some()
+        })
+    };
+    let styles = doc.styles.values().any(|s| {
+        !s.reset.is_empty()
+            || !s.append.is_empty()
+            || s.ruby.is_some()
+            || s.mask.is_some()
+            || s.strokes.is_some()
+            || s.shadows.is_some()
+            || inset(&s.shadow)
+            || adjusts(&s.transform)
+            || s.layers
+                .iter()
+                .flatten()
+                .any(|l| l.modifiers.iter().any(|m| matches!(m, Modifier::Adjust(_))))
+            || s.colors.as_ref().is_some_and(|c| {
+                [&c.inactive, &c.active, &c.complete]
+                    .into_iter()
+                    .flatten()
+                    .any(|f| f.gradient.is_some() || f.pattern.is_some())
+            })
+    });
+    let effects = doc.effects.values().flat_map(|e| &e.keyframes).any(|k| {
+        k.hue_shift.is_some()
+            || k.brightness.is_some()
+            || k.contrast.is_some()
+            || k.saturation.is_some()
+            || k.invert.is_some()
+    });
+    styles
+        || effects
+        || !doc.singers.is_empty()
+        || !doc.sections.is_empty()
+        || !doc.tracks.is_empty()
+        || !doc.overlays.is_empty()
+        || doc.theme.as_ref().is_some_and(|t| !t.sections.is_empty())
+        || doc.lines.iter().any(|l| {
+            !l.translations.is_empty()
+                || l.singer.is_some()
+                || l.track.is_some()
+                || l.mask.is_some()
+                || inset(&l.shadow)
+                || adjusts(&l.transform)
+        })
+        || doc.lines.iter().flat_map(|l| &l.chars).any(|c| {
+            c.ruby.is_some()
+                || c.singer.is_some()
+                || c.pitch.is_some()
+                || inset(&c.shadow)
+                || adjusts(&c.transform)
+        })
+}
+
+/// What `migrate` did to a document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationReport {
+    /// Version the document was written in
+    pub from_version: String,
+    /// Human-readable description of every change applied
+    pub changes: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Whether the document was already in the current format
+    pub fn is_current(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Parse `"major.minor"` (a bare major means `.0`)
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let (major, minor) = version.trim().split_once('.').unwrap_or((version, "0"));
+    Some((major.trim().parse().ok()?, minor.trim().parse().ok()?))
+}
+
+/// Whether `migrate` can bring this version up to `CURRENT_VERSION`
+pub fn is_supported(version: &str) -> bool {
+    migrate(serde_json::json!({ "version": version })).is_ok()
+}
+
+/// Upgrade a raw document to `CURRENT_VERSION`
+pub fn migrate(value: Value) -> Result<(Value, MigrationReport)> {
+    migrate_with(value, MIGRATIONS)
+}
+
+fn migrate_with(mut value: Value, steps: &[Migration]) -> Result<(Value, MigrationReport)> {
+    let from_version = value
+        .get("version")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("Missing KLyric version"))?
+        .to_string();
+    let current = parse_version(CURRENT_VERSION).expect("valid current version");
+    let mut version = parse_version(&from_version)
+        .ok_or_else(|| anyhow!("Unsupported KLyric version: {}", from_version))?;
+    if version > current {
+        bail!(
+            "Unsupported KLyric version: {} is newer than {}, the newest this renderer reads",
+            from_version,
+            CURRENT_VERSION
+        );
+    }
+
+    let mut changes = Vec::new();
+    while version < current {
+        let step = steps
+            .iter()
+            .find(|step| parse_version(step.from) == Some(version))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unsupported KLyric version: {} (no migration to {})",
+                    from_version,
+                    CURRENT_VERSION
+                )
+            })?;
+        (step.upgrade)(&mut value, &mut changes);
+        changes.push(format!("upgraded {} to {}", step.from, step.to));
+        version = parse_version(step.to).expect("valid migration version");
+    }
+
+    if from_version != CURRENT_VERSION {
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".to_string(), CURRENT_VERSION.into());
+        }
+    }
+    Ok((
+        value,
+        MigrationReport {
+            from_version,
+            changes,
+        },
+    ))
+}
+
+/// Oldest format version that can represent `doc`
+pub fn required_version(doc: &KLyricDocumentV2) -> &'static str {
+    required_version_with(doc, MIGRATIONS)
+}
+
+fn required_version_with(doc: &KLyricDocumentV2, steps: &[Migration]) -> &'static str {
+    let mut version = CURRENT_VERSION;
+    for step in steps.iter().rev() {
+        if step.to != version || (step.needs)(doc) {
+            break;
+        }
+        version = step.from;
+    }
+    version
+}
+
+/// Serialize a document stamped with `required_version` instead of the
+/// version it was loaded from
+pub fn write_document(doc: &KLyricDocumentV2, pretty: bool) -> Result<String, serde_json::Error> {
+    let version = required_version(doc);
+    let doc = if doc.version == version {
+        Cow::Borrowed(doc)
+    } else {
+        let mut stamped = doc.clone();
+        stamped.version = version.to_string();
+        Cow::Owned(stamped)
+    };
+    if pretty {
+        serde_json::to_string_pretty(doc.as_ref())
+    } else {
+        serde_json::to_string(doc.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // 1.0 called the lines array `lyrics`; 1.5 added optional `effects`
+    const STEPS: &[Migration] = &[
+        Migration {
+            from: "1.0",
+            to: "1.5",
+            upgrade: |value, changes| {
+                if let Some(lyrics) = value.as_object_mut().and_then(|o| o.remove("lyrics")) {
+                    value["lines"] = lyrics;
+                    changes.push("renamed `lyrics` to `lines`".to_string());
+                }
+            },
+            needs: |_| true,
+        },
+        Migration {
+            from: "1.5",
+            to: CURRENT_VERSION,
+            upgrade: |_, _| {},
+            needs: |doc| !doc.effects.is_empty(),
+        },
+    ];
+
+    fn doc(version: &str) -> Value {
+        json!({
+            "version": version,
+            "project": { "title": "", "duration": 0, "resolution": { "width": 1, "height": 1 } },
+            "lyrics": []
+        })
+    }
+
+    #[test]
+    fn test_migrate_steps() {
+        let (value, report) = migrate_with(doc("1.0"), STEPS).unwrap();
+        assert_eq!(value["version"], CURRENT_VERSION);
+        assert!(value.get("lyrics").is_none());
+        assert!(value["lines"].is_array());
+        assert_eq!(report.from_version, "1.0");
+        assert_eq!(
+            report.changes,
+            vec![
+                "renamed `lyrics` to `lines`".to_string(),
+                "upgraded 1.0 to 1.5".to_string(),
+                format!("upgraded 1.5 to {}", CURRENT_VERSION),
+            ]
+        );
+
+        let (_, report) = migrate_with(json!({ "version": CURRENT_VERSION }), STEPS).unwrap();
+        assert!(report.is_current());
+    }
+
+    #[test]
+    fn test_migrate_rejects_unknown_versions() {
+        let err = migrate_with(doc("3.1"), STEPS).unwrap_err().to_string();
+        assert!(err.contains("is newer than"), "{}", err);
+        let err = migrate_with(doc("0.9"), STEPS).unwrap_err().to_string();
+        assert!(err.contains("no migration"), "{}", err);
+        assert!(migrate_with(doc("two"), STEPS).is_err());
+        assert!(migrate_with(json!({}), STEPS).is_err());
+    }
+
+    #[test]
+    fn test_required_version() {
+        let (value, _) = migrate_with(doc("1.0"), STEPS).unwrap();
+        let mut doc: KLyricDocumentV2 = serde_json::from_value(value).unwrap();
+        // 1.5 readers understand everything but effects
+        assert_eq!(required_version_with(&doc, STEPS), "1.5");
+        doc.effects.insert("pop".to_string(), Default::default());
+        assert_eq!(required_version_with(&doc, STEPS), CURRENT_VERSION);
+
+        // Without imports, 2.0 renderers can read the document
+        assert_eq!(required_version(&doc), "2.0");
+        doc.version = CURRENT_VERSION.to_string();
+        let json: Value = serde_json::from_str(&write_document(&doc, false).unwrap()).unwrap();
+        assert_eq!(json["version"], "2.0");
+        doc.imports.push(crate::model::Import::Path(
+            "house.klyric-styles".to_string(),
+        ));
+        assert_eq!(required_version(&doc), "2.1");
+        doc.styles.insert(
+            "child".to_string(),
+            crate::model::Style {
+                append: vec!["effects".to_string()],
+                ..Default::default()
+            },
+        );
+        assert_eq!(required_version(&doc), "2.2");
+
+        assert!(is_supported("2.0"));
+        assert!(!is_supported("1.0"));
+    }
+
+    /// `fields` set on top of `base`
+    fn with(mut base: Value, fields: Value) -> Value {
+        for (key, value) in fields.as_object().unwrap() {
+            base[key] = value.clone();
+        }
+        base
+    }
+
+    #[test]
+    fn test_needs_2_2_triggers() {
+        let base = json!({
+            "version": "2.0",
+            "project": { "title": "", "duration": 0, "resolution": { "width": 1, "height": 1 } },
+            "lines": []
+        });
+        let style = |fields: Value| json!({ "styles": { "s": fields } });
+        let line = |fields: Value| json!({ "lines": [with(json!({ "start": 0, "end": 1, "chars": [] }), fields)] });
+        let char = |fields: Value| {
+            line(json!({ "chars": [with(json!({ "char": "a", "start": 0, "end": 1 }), fields)] }))
+        };
+        let keyframe = |fields: Value| json!({ "effects": { "e": { "type": "keyframe", "keyframes": [with(json!({ "time": 0 }), fields)] } } });
+        let fill = |fields: Value| style(json!({ "colors": { "active": fields } }));
+        let cases = [
+            ("style reset", style(json!({ "reset": ["font"] }))),
+            ("style append", style(json!({ "append": ["effects"] }))),
+            ("style ruby", style(json!({ "ruby": {} }))),
+            ("style mask", style(json!({ "mask": {} }))),
+            ("style strokes", style(json!({ "strokes": [] }))),
+            ("style shadows", style(json!({ "shadows": [] }))),
+            (
+                "style inset shadow",
+                style(json!({ "shadow": { "inset": true } })),
+            ),
+            (
+                "style brightness",
+                style(json!({ "transform": { "brightness": 1.2 } })),
+            ),
+            (
+                "style contrast",
+                style(json!({ "transform": { "contrast": 1.2 } })),
+            ),
+            (
+                "style saturation",
+                style(json!({ "transform": { "saturation": 0.5 } })),
+            ),
+            (
+                "style invert",
+                style(json!({ "transform": { "invert": 1.0 } })),
+            ),
+            (
+                "adjust modifier",
+                style(
+                    json!({ "layers": [{ "selector": { "mode": "All" }, "modifiers": [{ "type": "Adjust", "params": {} }] }] }),
+                ),
+            ),
+            (
+                "gradient fill",
+                fill(json!({ "gradient": { "colors": ["#000000", "#FFFFFF"] } })),
+            ),
+            (
+                "pattern fill",
+                fill(json!({ "pattern": { "src": "a.png" } })),
+            ),
+            ("keyframe hueShift", keyframe(json!({ "hueShift": 90 }))),
+            (
+                "keyframe brightness",
+                keyframe(json!({ "brightness": 1.2 })),
+            ),
+            ("keyframe contrast", keyframe(json!({ "contrast": 1.2 }))),
+            (
+                "keyframe saturation",
+                keyframe(json!({ "saturation": 0.5 })),
+            ),
+            ("keyframe invert", keyframe(json!({ "invert": 1.0 }))),
+            ("singers", json!({ "singers": { "lead": {} } })),
+            (
+                "sections",
+                json!({ "sections": [{ "name": "chorus", "start": 0, "end": 1 }] }),
+            ),
+            ("tracks", json!({ "tracks": { "credits": {} } })),
+            (
+                "overlays",
+                json!({ "overlays": [{ "start": 0, "end": 1, "text": "x" }] }),
+            ),
+            (
+                "theme sections",
+                json!({ "theme": { "sections": { "chorus": {} } } }),
+            ),
+            (
+                "line translations",
+                line(json!({ "translations": [{ "text": "x" }] })),
+            ),
+            ("line singer", line(json!({ "singer": "lead" }))),
+            ("line track", line(json!({ "track": "credits" }))),
+            ("line mask", line(json!({ "mask": {} }))),
+            (
+                "line inset shadow",
+                line(json!({ "shadow": { "inset": true } })),
+            ),
+            (
+                "line adjust",
+                line(json!({ "transform": { "invert": 1.0 } })),
+            ),
+            ("char ruby", char(json!({ "ruby": "a" }))),
+            ("char singer", char(json!({ "singer": "lead" }))),
+            ("char pitch", char(json!({ "pitch": 60 }))),
+            (
+                "char inset shadow",
+                char(json!({ "shadow": { "inset": true } })),
+            ),
+            (
+                "char adjust",
+                char(json!({ "transform": { "brightness": 1.2 } })),
+            ),
+        ];
+
+        let doc: KLyricDocumentV2 = serde_json::from_value(base.clone()).unwrap();
+        assert_eq!(required_version(&doc), "2.0");
+        for (name, fields) in cases {
+            let doc: KLyricDocumentV2 = serde_json::from_value(with(base.clone(), fields))
+                .unwrap_or_else(|e| panic!("{}: {}", name, e));
+            assert_eq!(required_version(&doc), "2.2", "{}", name);
+        }
+    }
+}
@@ -1,21 +1,28 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 use super::effect::Effect;
+use super::library::{Import, Imported};
 use super::line::Line;
+use super::overlay::Overlay;
 use super::project::Project;
+use super::section::Section;
+use super::singer::Singer;
 use super::style::Style;
-use super::theme::Theme;
+use super::theme::{Background, SectionTheme, Theme};
+use super::track::Track;
+use crate::presets::PresetDefinition;
 
 /// Root KLyric v2.0 document structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct KLyricDocumentV2 {
     /// JSON Schema reference
     #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
     pub schema: Option<String>,
 
-    /// Format version (must be "2.0")
+    /// Format version (see `migrate::CURRENT_VERSION`)
     pub version: String,
 
     /// Project metadata
@@ -25,6 +32,10 @@ pub struct KLyricDocumentV2 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub theme: Option<Theme>,
 
+    /// Style/effect libraries, resolved relative to the document
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<Import>,
+
     /// Named style definitions
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub styles: HashMap<String, Style>,
@@ -33,8 +44,37 @@ pub struct KLyricDocumentV2 {
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub effects: HashMap<String, Effect>,
 
+    /// Singers (parts) that lines and chars can be attributed to
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub singers: HashMap<String, Singer>,
+
+    /// Text layers that lines can belong to, composited by `zIndex`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tracks: HashMap<String, Track>,
+
+    /// Named particle preset definitions, usable as effect `preset` names
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub particle_presets: HashMap<String, PresetDefinition>,
+
+    /// Song structure (intro, verse, chorus, ...) by time range
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<Section>,
+
+    /// Text, images and shapes shown over the lyrics for a time range
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overlays: Vec<Overlay>,
+
     /// Lyric lines with timing and characters
     pub lines: Vec<Line>,
+
+    /// Definitions loaded from `imports` (see `StyleResolver::load_imports`)
+    #[serde(skip)]
+    pub imported: Imported,
+
+    /// Directory of the file the document was loaded from, which relative
+    /// asset paths resolve against (see `load_document`)
+    #[serde(skip)]
+    pub base_dir: Option<PathBuf>,
 }
 
 impl KLyricDocumentV2 {
@@ -43,13 +83,14 @@ impl KLyricDocumentV2 {
         serde_json::from_str(json)
     }
 
-    /// Serialize to JSON
+    /// JSON Schema of the document format, derived from the model types
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(KLyricDocumentV2).to_value()
+    }
+
+    /// Serialize to JSON, stamped with the oldest format version that can read it
     pub fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
-        if pretty {
-            serde_json::to_string_pretty(self)
-        } else {
-            serde_json::to_string(self)
-        }
+        crate::migrate::write_document(self, pretty)
     }
 
     /// Get the line that should be displayed at a given time
@@ -59,47 +100,188 @@ impl KLyricDocumentV2 {
             .find(|line| time >= line.start && time <= line.end)
     }
 
-    /// Resolve a style by name, handling inheritance
-    pub fn resolve_style(&self, name: &str) -> Style {
-        let mut resolved = Style::default();
+    /// Lines to display at a given time, in compositing order: per track,
+    /// the first active line plus the first active line of every other
+    /// singer
+    pub fn get_active_lines(&self, time: f64) -> Vec<&Line> {
+        self.active_lines(time, |_| true)
+    }
+
+    /// `get_active_lines` without the tracks hidden by mute and solo, for
+    /// preview
+    pub fn preview_active_lines(&self, time: f64) -> Vec<&Line> {
+        self.active_lines(time, |line| self.track_visible(line.track.as_deref()))
+    }
 
-        if let Some(style) = self.styles.get(name) {
-            // Handle inheritance
-            if let Some(ref extends) = style.extends {
-                resolved = self.resolve_style(extends);
+    fn active_lines(&self, time: f64, visible: impl Fn(&Line) -> bool) -> Vec<&Line> {
+        let mut active: Vec<&Line> = Vec::new();
+        for line in self
+            .lines
+            .iter()
+            .filter(|line| time >= line.start && time <= line.end)
+            .filter(|line| visible(line))
+        {
+            let mut track = active.iter().filter(|l| l.track == line.track);
+            let first = track.clone().next().is_none();
+            if first || (line.singer.is_some() && track.all(|l| l.singer != line.singer)) {
+                active.push(line);
             }
-            // Merge current style
-            merge_style(&mut resolved, style);
         }
+        active.sort_by_key(|line| self.track_of(line).map_or(0, |track| track.z_index));
+        active
+    }
 
-        resolved
+    /// Overlays on screen at a given time, with their index in `overlays`
+    pub fn active_overlays(&self, time: f64) -> impl Iterator<Item = (usize, &Overlay)> {
+        self.overlays
+            .iter()
+            .enumerate()
+            .filter(move |(_, overlay)| overlay.contains(time))
+    }
+
+    /// Track a line belongs to, if it names a known one
+    pub fn track_of(&self, line: &Line) -> Option<&Track> {
+        self.tracks.get(line.track.as_deref()?)
+    }
+
+    /// Whether preview shows lines of a track: not muted, and soloed when
+    /// any track is. `None` (or an unknown id) is the implicit main track, which
+    /// can't be muted or soloed.
+    pub fn track_visible(&self, id: Option<&str>) -> bool {
+        let soloed = self.tracks.values().any(|track| track.solo);
+        match id.and_then(|id| self.tracks.get(id)) {
+            Some(track) => !track.muted && (track.solo || !soloed),
+            None => !soloed,
+        }
     }
-}
 
-/// Merge style properties (source overrides target)
-fn merge_style(target: &mut Style, source: &Style) {
-    if source.font.is_some() {
-        target.font = source.font.clone();
+    /// Style name for a line: its own, its singer's, its track's, its
+    /// section's default, the theme default, or `base`
+    pub fn line_style<'a>(&'a self, line: &'a Line) -> &'a str {
+        line.style
+            .as_deref()
+            .or_else(|| {
+                let singer = self.singers.get(line.singer.as_deref()?)?;
+                singer.style.as_deref()
+            })
+            .or_else(|| self.track_of(line)?.style.as_deref())
+            .or_else(|| self.section_theme(line.start)?.default_style.as_deref())
+            .or_else(|| self.theme.as_ref()?.default_style.as_deref())
+            .unwrap_or("base")
     }
-    if source.colors.is_some() {
-        target.colors = source.colors.clone();
+
+    /// Section playing at a given time; the latest to start when they overlap.
+    /// A line belongs to the section playing at its start.
+    pub fn section_at(&self, time: f64) -> Option<&Section> {
+        self.sections
+            .iter()
+            .filter(|section| section.contains(time))
+            .max_by(|a, b| a.start.total_cmp(&b.start))
+    }
+
+    /// Theme overrides of the section playing at a given time
+    pub fn section_theme(&self, time: f64) -> Option<&SectionTheme> {
+        let section = self.section_at(time)?;
+        self.theme.as_ref()?.sections.get(&section.name)
+    }
+
+    /// Background at a given time, taking section overrides into account
+    pub fn background_at(&self, time: f64) -> Option<&Background> {
+        self.section_theme(time)
+            .and_then(|theme| theme.background.as_ref())
+            .or_else(|| self.theme.as_ref()?.background.as_ref())
+    }
+
+    /// Resolve an asset path (an overlay image, the project audio) against
+    /// `base_dir`
+    pub fn asset_path(&self, path: &str) -> PathBuf {
+        match &self.base_dir {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        }
     }
-    if source.stroke.is_some() {
-        target.stroke = source.stroke.clone();
+
+    /// Look up a style, falling back to imported ones
+    pub fn style(&self, name: &str) -> Option<&Style> {
+        self.styles
+            .get(name)
+            .or_else(|| self.imported.styles.get(name))
     }
-    if source.shadow.is_some() {
-        target.shadow = source.shadow.clone();
+
+    /// Look up an effect, falling back to imported ones
+    pub fn effect(&self, name: &str) -> Option<&Effect> {
+        self.effects
+            .get(name)
+            .or_else(|| self.imported.effects.get(name))
     }
-    if source.glow.is_some() {
-        target.glow = source.glow.clone();
+
+    /// Follow a style's `extends` chain.
+    ///
+    /// A style that extends its own name builds on the definition it
+    /// shadows, so a document `title` can extend the imported `title`. The
+    /// walk stops at the first definition it has already visited.
+    pub fn style_chain<'a>(&'a self, name: &'a str) -> StyleChain<'a> {
+        let mut chain = StyleChain {
+            links: Vec::new(),
+            cycle: None,
+        };
+        // Skipping the document's own definition when it is the one shadowing
+        let lookup =
+            |name: &'a str, shadowing: bool| match self.styles.get(name).filter(|_| !shadowing) {
+                Some(style) => Some((name, style, false)),
+                None => Some((name, self.imported.styles.get(name)?, true)),
+            };
+        let mut visited = HashSet::new();
+        let mut next = lookup(name, false);
+        while let Some((name, style, imported)) = next {
+            if !visited.insert((name, imported)) {
+                chain.cycle = chain
+                    .links
+                    .iter()
+                    .position(|link| link.name == name && link.imported == imported);
+                break;
+            }
+            chain.links.push(StyleLink {
+                name,
+                style,
+                imported,
+            });
+            next = style
+                .extends
+                .as_deref()
+                .and_then(|parent| lookup(parent, parent == name));
+        }
+        chain
     }
-    if let Some(effects) = &source.effects {
-        // Effects override or append?
-        // Usually style inheritance overrides completely if present
-        target.effects = Some(effects.clone());
+
+    /// Resolve a style by name, handling inheritance (see `Style::inherit`)
+    pub fn resolve_style(&self, name: &str) -> Style {
+        let mut resolved = Style::default();
+        for link in self.style_chain(name).links.iter().rev() {
+            resolved.inherit(link.style);
+        }
+        resolved
     }
 }
 
+/// A style's `extends` chain, see `KLyricDocumentV2::style_chain`
+#[derive(Debug)]
+pub struct StyleChain<'a> {
+    /// The style itself first, then each parent in turn
+    pub links: Vec<StyleLink<'a>>,
+    /// Index into `links` of the definition the chain loops back to
+    pub cycle: Option<usize>,
+}
+
+/// One definition in a `StyleChain`
+#[derive(Debug)]
+pub struct StyleLink<'a> {
+    pub name: &'a str,
+    pub style: &'a Style,
+    /// Whether the definition comes from `imports`
+    pub imported: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,7 +329,15 @@ mod tests {
             theme: None,
             styles: HashMap::new(),
             effects: HashMap::new(),
+            particle_presets: HashMap::new(),
             lines: vec![],
+            imports: Vec::new(),
+            sections: Vec::new(),
+            singers: HashMap::new(),
+            tracks: HashMap::new(),
+            overlays: Vec::new(),
+            imported: Default::default(),
+            base_dir: None,
         };
 
         let json = doc.to_json(false).unwrap();
@@ -177,7 +367,15 @@ mod tests {
             theme: None,
             styles: HashMap::new(),
             effects: HashMap::new(),
+            particle_presets: HashMap::new(),
             lines: vec![],
+            imports: Vec::new(),
+            sections: Vec::new(),
+            singers: HashMap::new(),
+            tracks: HashMap::new(),
+            overlays: Vec::new(),
+            imported: Default::default(),
+            base_dir: None,
         };
 
         let line1 = Line {
@@ -202,6 +400,118 @@ mod tests {
         assert!(doc.get_active_line(7.0).is_none());
     }
 
+    #[test]
+    fn test_singers() {
+        let json = r##"{
+            "version": "2.2",
+            "project": { "title": "t", "duration": 10, "resolution": { "width": 1920, "height": 1080 } },
+            "singers": {
+                "a": { "style": "lead", "color": "#FF0000", "placement": "left" },
+                "b": {}
+            },
+            "lines": [
+                { "start": 1, "end": 4, "singer": "a", "chars": [] },
+                { "start": 2, "end": 5, "singer": "a", "chars": [] },
+                { "start": 3, "end": 6, "singer": "b", "style": "duet", "chars": [] },
+                { "start": 3, "end": 6, "chars": [] }
+            ]
+        }"##;
+        let doc = KLyricDocumentV2::from_json(json).unwrap();
+        let a = &doc.singers["a"];
+        assert_eq!(a.placement, Some(crate::model::Placement::Left));
+        assert_eq!(doc.line_style(&doc.lines[0]), "lead");
+        assert_eq!(doc.line_style(&doc.lines[2]), "duet");
+        assert_eq!(doc.line_style(&doc.lines[3]), "base");
+
+        // One line per singer
+        let active = doc.get_active_lines(3.5);
+        assert_eq!(active.len(), 2);
+        assert!(std::ptr::eq(active[0], &doc.lines[0]));
+        assert!(std::ptr::eq(active[1], &doc.lines[2]));
+        assert_eq!(doc.get_active_lines(5.5).len(), 1);
+        assert!(doc.get_active_lines(7.0).is_empty());
+    }
+
+    #[test]
+    fn test_tracks() {
+        let json = r#"{
+            "version": "2.2",
+            "project": { "title": "t", "duration": 10, "resolution": { "width": 1920, "height": 1080 } },
+            "tracks": {
+                "title": { "style": "card", "zIndex": 1 },
+                "backing": { "zIndex": -1, "placement": "top" }
+            },
+            "lines": [
+                { "start": 0, "end": 5, "track": "title", "chars": [] },
+                { "start": 1, "end": 4, "chars": [] },
+                { "start": 2, "end": 5, "chars": [] },
+                { "start": 1, "end": 4, "track": "backing", "chars": [] }
+            ]
+        }"#;
+        let mut doc = KLyricDocumentV2::from_json(json).unwrap();
+        assert_eq!(doc.line_style(&doc.lines[0]), "card");
+        assert_eq!(doc.line_style(&doc.lines[3]), "base");
+
+        // One line per track, bottom track first
+        let active = doc.get_active_lines(3.0);
+        assert_eq!(active.len(), 3);
+        assert!(std::ptr::eq(active[0], &doc.lines[3]));
+        assert!(std::ptr::eq(active[1], &doc.lines[1]));
+        assert!(std::ptr::eq(active[2], &doc.lines[0]));
+
+        doc.tracks.get_mut("title").unwrap().muted = true;
+        assert_eq!(doc.preview_active_lines(3.0).len(), 2);
+
+        // Soloing hides the main track too
+        doc.tracks.get_mut("backing").unwrap().solo = true;
+        let active = doc.preview_active_lines(3.0);
+        assert_eq!(active.len(), 1);
+        assert!(std::ptr::eq(active[0], &doc.lines[3]));
+
+        // Only preview state: exports keep every track, and it isn't saved
+        assert_eq!(doc.get_active_lines(3.0).len(), 3);
+        let json = doc.to_json(false).unwrap();
+        assert!(!json.contains("muted") && !json.contains("solo"));
+    }
+
+    #[test]
+    fn test_sections() {
+        let json = r##"{
+            "version": "2.2",
+            "project": { "title": "t", "duration": 30, "resolution": { "width": 1920, "height": 1080 } },
+            "theme": {
+                "background": { "color": "#000000" },
+                "defaultStyle": "plain",
+                "sections": { "chorus": { "background": { "color": "#FF0000" }, "defaultStyle": "loud" } }
+            },
+            "sections": [
+                { "name": "verse", "start": 0, "end": 10 },
+                { "name": "chorus", "start": 10, "end": 20 },
+                { "name": "chorus", "start": 18, "end": 25 }
+            ],
+            "lines": [
+                { "start": 2, "end": 4, "chars": [] },
+                { "start": 12, "end": 14, "chars": [] }
+            ]
+        }"##;
+        let doc = KLyricDocumentV2::from_json(json).unwrap();
+        assert_eq!(doc.section_at(5.0).unwrap().name, "verse");
+        // Overlaps go to the latest start
+        assert_eq!(doc.section_at(19.0).unwrap().start, 18.0);
+        assert!(doc.section_at(26.0).is_none());
+
+        assert_eq!(
+            doc.background_at(5.0).unwrap().color.as_deref(),
+            Some("#000000")
+        );
+        assert_eq!(
+            doc.background_at(15.0).unwrap().color.as_deref(),
+            Some("#FF0000")
+        );
+        assert_eq!(doc.line_style(&doc.lines[0]), "plain");
+        assert_eq!(doc.line_style(&doc.lines[1]), "loud");
+    }
+
     #[test]
     fn test_resolve_style() {
         let mut doc = KLyricDocumentV2 {
@@ -221,7 +531,15 @@ mod tests {
             theme: None,
             styles: HashMap::new(),
             effects: HashMap::new(),
+            particle_presets: HashMap::new(),
             lines: vec![],
+            imports: Vec::new(),
+            sections: Vec::new(),
+            singers: HashMap::new(),
+            tracks: HashMap::new(),
+            overlays: Vec::new(),
+            imported: Default::default(),
+            base_dir: None,
         };
 
         let mut base_style = Style::default();
@@ -247,7 +565,8 @@ mod tests {
         doc.styles.insert("inherited".to_string(), inherited_style);
 
         let resolved = doc.resolve_style("inherited");
-        assert_eq!(resolved.font.as_ref().unwrap().family.as_deref(), None); // Overridden logic: merge_style completely replaces font if target has font. Wait, `merge_style` says `if source.font.is_some() { target.font = source.font.clone(); }`. So the target's font is replaced entirely by the source font, fields are NOT merged recursively. Thus family will be None, and size will be 36.0.
+        // Fonts merge field by field: the parent's family survives the child's size
+        assert_eq!(resolved.font.as_ref().unwrap().family.as_deref(), Some("Arial"));
         assert_eq!(resolved.font.as_ref().unwrap().size, Some(36.0));
     }
 }
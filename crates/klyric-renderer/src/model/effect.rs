@@ -2,7 +2,7 @@ use crate::particle::ParticleConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Effect {
     /// Effect type
@@ -67,7 +67,7 @@ fn default_iterations() -> u32 {
     1
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum EffectType {
     #[default]
@@ -81,7 +81,7 @@ pub enum EffectType {
     Custom,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum EffectTrigger {
     #[default]
@@ -92,7 +92,7 @@ pub enum EffectTrigger {
     Always,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum KaraokeMode {
     Mask,
@@ -101,7 +101,7 @@ pub enum KaraokeMode {
     Reveal,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
     Ltr,
@@ -110,14 +110,14 @@ pub enum Direction {
     Btt,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(untagged)]
 pub enum AnimatedValue {
     Range { from: f64, to: f64 },
     Expression(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Keyframe {
     /// Position in animation (0-1)
@@ -159,6 +159,26 @@ pub struct Keyframe {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub glitch_offset: Option<f32>,
 
+    /// Hue shift in degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hue_shift: Option<f32>,
+
+    /// Brightness factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<f32>,
+
+    /// Contrast factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contrast: Option<f32>,
+
+    /// Saturation factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saturation: Option<f32>,
+
+    /// Color inversion amount (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invert: Option<f32>,
+
     /// Color at this keyframe
     #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
@@ -168,7 +188,7 @@ pub struct Keyframe {
     pub easing: Option<Easing>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum Easing {
     #[default]
@@ -2,7 +2,7 @@ use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
     /// X position (pixels or percentage string)
@@ -97,7 +97,23 @@ impl<'de> Deserialize<'de> for PositionValue {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash)]
+impl schemars::JsonSchema for PositionValue {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "PositionValue".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Pixels as a number, or a percentage of the canvas as a string like \"50%\"",
+            "oneOf": [
+                { "type": "number" },
+                { "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?%$" }
+            ]
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash, schemars::JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum Anchor {
     TopLeft,
@@ -112,7 +128,7 @@ pub enum Anchor {
     BottomRight,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Transform {
     /// X offset in pixels
@@ -162,6 +178,22 @@ pub struct Transform {
     /// Hue shift in degrees
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hue_shift: Option<f32>,
+
+    /// Brightness factor (1 = unchanged, 0 = black)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<f32>,
+
+    /// Contrast factor (1 = unchanged, 0 = flat gray)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contrast: Option<f32>,
+
+    /// Saturation factor (1 = unchanged, 0 = grayscale)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saturation: Option<f32>,
+
+    /// Color inversion amount (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invert: Option<f32>,
 }
 
 pub fn default_scale() -> f32 {
@@ -211,9 +243,21 @@ impl Transform {
     pub fn hue_shift_val(&self) -> f32 {
         self.hue_shift.unwrap_or(0.0)
     }
+    pub fn brightness_val(&self) -> f32 {
+        self.brightness.unwrap_or(1.0)
+    }
+    pub fn contrast_val(&self) -> f32 {
+        self.contrast.unwrap_or(1.0)
+    }
+    pub fn saturation_val(&self) -> f32 {
+        self.saturation.unwrap_or(1.0)
+    }
+    pub fn invert_val(&self) -> f32 {
+        self.invert.unwrap_or(0.0)
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Layout {
     /// Text layout mode
@@ -241,7 +285,7 @@ pub struct Layout {
     pub max_width: Option<f32>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LayoutMode {
     #[default]
@@ -250,7 +294,7 @@ pub enum LayoutMode {
     Path,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Align {
     Left,
@@ -259,7 +303,7 @@ pub enum Align {
     Right,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, Hash, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum Justify {
     Top,
@@ -285,6 +329,10 @@ pub struct RenderTransform {
     pub blur: f32,
     pub glitch_offset: f32,
     pub hue_shift: f32,
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    pub invert: f32,
 }
 
 impl Default for RenderTransform {
@@ -302,6 +350,10 @@ impl Default for RenderTransform {
             blur: 0.0,
             glitch_offset: 0.0,
             hue_shift: 0.0,
+            brightness: 1.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            invert: 0.0,
         }
     }
 }
@@ -324,6 +376,10 @@ impl RenderTransform {
             blur: line.blur_val() + char_t.blur_val(),
             glitch_offset: line.glitch_offset_val() + char_t.glitch_offset_val(),
             hue_shift: line.hue_shift_val() + char_t.hue_shift_val(),
+            brightness: line.brightness_val() * char_t.brightness_val(),
+            contrast: line.contrast_val() * char_t.contrast_val(),
+            saturation: line.saturation_val() * char_t.saturation_val(),
+            invert: line.invert_val() + char_t.invert_val(),
         }
     }
 
@@ -345,6 +401,10 @@ impl RenderTransform {
             blur: self.blur + t.blur_val(),
             glitch_offset: self.glitch_offset + t.glitch_offset_val(),
             hue_shift: self.hue_shift + t.hue_shift_val(),
+            brightness: self.brightness * t.brightness_val(),
+            contrast: self.contrast * t.contrast_val(),
+            saturation: self.saturation * t.saturation_val(),
+            invert: self.invert + t.invert_val(),
         }
     }
 
@@ -386,6 +446,18 @@ impl RenderTransform {
         if let Some(v) = delta.hue_shift {
             self.hue_shift = v;
         }
+        if let Some(v) = delta.brightness {
+            self.brightness = v;
+        }
+        if let Some(v) = delta.contrast {
+            self.contrast = v;
+        }
+        if let Some(v) = delta.saturation {
+            self.saturation = v;
+        }
+        if let Some(v) = delta.invert {
+            self.invert = v;
+        }
     }
 
     /// Checks if the transform only involves translation (no rotation/scale).
@@ -411,6 +483,10 @@ impl RenderTransform {
         self.blur += other.blur;
         self.glitch_offset += other.glitch_offset;
         self.hue_shift += other.hue_shift;
+        self.brightness *= other.brightness;
+        self.contrast *= other.contrast;
+        self.saturation *= other.saturation;
+        self.invert += other.invert;
         // Anchors are not accumulated
     }
 
@@ -453,6 +529,94 @@ impl RenderTransform {
         if mask & (1 << 11) != 0 {
             self.hue_shift = other.hue_shift;
         }
+        if mask & (1 << 12) != 0 {
+            self.brightness = other.brightness;
+        }
+        if mask & (1 << 13) != 0 {
+            self.contrast = other.contrast;
+        }
+        if mask & (1 << 14) != 0 {
+            self.saturation = other.saturation;
+        }
+        if mask & (1 << 15) != 0 {
+            self.invert = other.invert;
+        }
+    }
+
+    /// Whether any color adjustment (hue, brightness, contrast, saturation,
+    /// invert) is set
+    #[inline(always)]
+    pub fn has_color_adjust(&self) -> bool {
+        self.hue_shift.abs() > 0.001
+            || (self.brightness - 1.0).abs() > 0.001
+            || (self.contrast - 1.0).abs() > 0.001
+            || (self.saturation - 1.0).abs() > 0.001
+            || self.invert > 0.001
+    }
+
+    /// Row-major 4x5 RGBA color matrix of the color adjustments, with
+    /// offsets in 0-1. Applied like the CSS filters of the same names, in the
+    /// order brightness, contrast, saturation, hue, invert.
+    pub fn color_matrix(&self) -> [f32; 20] {
+        // RGB part as a 3x3 matrix plus offset
+        type Affine = ([[f32; 3]; 3], [f32; 3]);
+        fn then((a, u): Affine, (m, mut t): Affine) -> Affine {
+            let mut out = [[0.0; 3]; 3];
+            for ((out_row, m_row), offset) in out.iter_mut().zip(&m).zip(&mut t) {
+                for (c, value) in out_row.iter_mut().enumerate() {
+                    *value = (0..3).map(|k| m_row[k] * a[k][c]).sum();
+                }
+                *offset += (0..3).map(|k| m_row[k] * u[k]).sum::<f32>();
+            }
+            (out, t)
+        }
+        fn diagonal(scale: f32, offset: f32) -> Affine {
+            let mut m = [[0.0; 3]; 3];
+            for (i, row) in m.iter_mut().enumerate() {
+                row[i] = scale;
+            }
+            (m, [offset; 3])
+        }
+
+        let s = self.saturation;
+        let saturate = [
+            [0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s],
+            [0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s],
+            [0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s],
+        ];
+        let (sin, cos) = self.hue_shift.to_radians().sin_cos();
+        let hue = [
+            [
+                0.213 + cos * 0.787 - sin * 0.213,
+                0.715 - cos * 0.715 - sin * 0.715,
+                0.072 - cos * 0.072 + sin * 0.928,
+            ],
+            [
+                0.213 - cos * 0.213 + sin * 0.143,
+                0.715 + cos * 0.285 + sin * 0.140,
+                0.072 - cos * 0.072 - sin * 0.283,
+            ],
+            [
+                0.213 - cos * 0.213 - sin * 0.787,
+                0.715 - cos * 0.715 + sin * 0.715,
+                0.072 + cos * 0.928 + sin * 0.072,
+            ],
+        ];
+        let invert = self.invert.clamp(0.0, 1.0);
+
+        let mut rgb = diagonal(self.brightness, 0.0);
+        rgb = then(rgb, diagonal(self.contrast, 0.5 - 0.5 * self.contrast));
+        rgb = then(rgb, (saturate, [0.0; 3]));
+        rgb = then(rgb, (hue, [0.0; 3]));
+        rgb = then(rgb, diagonal(1.0 - 2.0 * invert, invert));
+
+        let (m, t) = rgb;
+        [
+            m[0][0], m[0][1], m[0][2], 0.0, t[0], //
+            m[1][0], m[1][1], m[1][2], 0.0, t[1], //
+            m[2][0], m[2][1], m[2][2], 0.0, t[2], //
+            0.0, 0.0, 0.0, 1.0, 0.0,
+        ]
     }
 }
 
@@ -512,4 +676,46 @@ mod tests {
         let pct = PositionValue::Percentage(0.5);
         assert_eq!(serde_json::to_string(&pct).unwrap(), "\"50%\"");
     }
+
+    #[test]
+    fn test_color_matrix() {
+        // Apply the matrix to an opaque RGB color
+        fn apply(transform: &RenderTransform, rgb: [f32; 3]) -> [f32; 3] {
+            let m = transform.color_matrix();
+            let mut out = [0.0; 3];
+            for (r, value) in out.iter_mut().enumerate() {
+                let row = &m[r * 5..r * 5 + 5];
+                *value = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2] + row[3] + row[4];
+            }
+            out
+        }
+        fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+            a.iter().zip(b).all(|(a, b)| (a - b).abs() < 0.01)
+        }
+
+        let mut transform = RenderTransform::default();
+        assert!(!transform.has_color_adjust());
+        assert!(close(apply(&transform, [0.2, 0.5, 0.9]), [0.2, 0.5, 0.9]));
+
+        transform.saturation = 0.0;
+        let [r, g, b] = apply(&transform, [1.0, 0.0, 0.0]);
+        assert!(transform.has_color_adjust());
+        assert!((r - g).abs() < 1e-4 && (g - b).abs() < 1e-4);
+
+        transform.saturation = 1.0;
+        transform.invert = 1.0;
+        assert!(close(apply(&transform, [1.0, 1.0, 1.0]), [0.0, 0.0, 0.0]));
+
+        // A full turn of hue is the identity again; greys never change
+        transform.invert = 0.0;
+        transform.hue_shift = 360.0;
+        assert!(close(apply(&transform, [0.2, 0.5, 0.9]), [0.2, 0.5, 0.9]));
+        transform.hue_shift = 120.0;
+        assert!(close(apply(&transform, [0.5, 0.5, 0.5]), [0.5, 0.5, 0.5]));
+        assert!(!close(apply(&transform, [1.0, 0.0, 0.0]), [1.0, 0.0, 0.0]));
+
+        transform.hue_shift = 0.0;
+        transform.brightness = 0.5;
+        assert!(close(apply(&transform, [1.0, 0.5, 0.0]), [0.5, 0.25, 0.0]));
+    }
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::effect::Effect;
+use super::style::Style;
+
+/// Reference to an external style/effect library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum Import {
+    /// Path relative to the importing file, namespaced by its file stem
+    Path(String),
+    /// Path with an explicit namespace
+    Aliased {
+        path: String,
+        #[serde(rename = "as")]
+        namespace: String,
+    },
+}
+
+impl Import {
+    pub fn path(&self) -> &str {
+        match self {
+            Import::Path(path) | Import::Aliased { path, .. } => path,
+        }
+    }
+
+    /// Prefix for qualified names (`house.title` for `house.klyric-styles`)
+    pub fn namespace(&self) -> String {
+        match self {
+            Import::Path(path) => {
+                let name = Path::new(path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or(path);
+                // `house.klyric-styles` -> `house`
+                name.split('.').next().unwrap_or(name).to_string()
+            }
+            Import::Aliased { namespace, .. } => namespace.clone(),
+        }
+    }
+}
+
+/// A style library file (`*.klyric-styles`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleLibrary {
+    /// Libraries this one builds on, relative to this file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<Import>,
+
+    /// Named style definitions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub styles: HashMap<String, Style>,
+
+    /// Named effect definitions
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub effects: HashMap<String, Effect>,
+}
+
+/// Definitions merged in from a document's imports, kept apart from its own
+/// so that saving the document does not inline the libraries
+#[derive(Debug, Clone, Default)]
+pub struct Imported {
+    /// Styles by plain and qualified (`namespace.name`) name
+    pub styles: HashMap<String, Style>,
+
+    /// Effects by plain and qualified name
+    pub effects: HashMap<String, Effect>,
+
+    /// Every library file that was loaded
+    pub sources: Vec<PathBuf>,
+}
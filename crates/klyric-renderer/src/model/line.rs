@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use super::document::KLyricDocumentV2;
+use super::effect::EffectType;
 use super::layout::{Layout, Position, Transform};
+use super::mask::Mask;
 use super::style::{Font, Shadow, Stroke};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Line {
     /// Unique identifier
@@ -20,10 +23,18 @@ pub struct Line {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
 
-    /// Style name to apply
+    /// Style name to apply; the singer's or track's style when unset
     #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<String>,
 
+    /// Singer of this line (key into the document's `singers`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub singer: Option<String>,
+
+    /// Track of this line (key into the document's `tracks`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<String>,
+
     /// Font override for this line
     #[serde(skip_serializing_if = "Option::is_none")]
     pub font: Option<Font>,
@@ -36,6 +47,10 @@ pub struct Line {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub shadow: Option<Shadow>,
 
+    /// Mask override for this line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask: Option<Mask>,
+
     /// Effect names to apply
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub effects: Vec<String>,
@@ -54,9 +69,80 @@ pub struct Line {
 
     /// Characters with individual timing
     pub chars: Vec<Char>,
+
+    /// Secondary tracks (translations, romanization) stacked under the line
+    /// in order. They show for the whole line and are not highlighted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub translations: Vec<Translation>,
+}
+
+/// Secondary text shown under a line, timed with it
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Translation {
+    /// Text to display
+    pub text: String,
+
+    /// Track name, usually a language tag such as `en`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+
+    /// Style name to apply; the line's style when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+}
+
+impl Line {
+    /// Line rendering `translation` in place of this line's text.
+    ///
+    /// Every char is timed to the line start, so the text shows in its
+    /// style's `complete` color for the whole line. Position, transform and
+    /// layout follow this line, and so do its transition and keyframe
+    /// effects; particles and other glyph effects stay with the original.
+    pub fn translation_line(&self, translation: &Translation, doc: &KLyricDocumentV2) -> Line {
+        let moves_line = |name: &&String| {
+            let effect_type = match doc.effect(name) {
+                Some(effect) => effect.effect_type.clone(),
+                None => match crate::presets::transitions::get_transition(name) {
+                    Some(effect) => effect.effect_type,
+                    None => return false,
+                },
+            };
+            matches!(effect_type, EffectType::Transition | EffectType::Keyframe)
+        };
+        Line {
+            text: Some(translation.text.clone()),
+            style: translation.style.clone().or_else(|| self.style.clone()),
+            singer: self.singer.clone(),
+            track: self.track.clone(),
+            effects: self.effects.iter().filter(moves_line).cloned().collect(),
+            position: self.position.clone(),
+            transform: self.transform.clone(),
+            layout: self.layout,
+            chars: translation
+                .text
+                .chars()
+                .map(|c| Char {
+                    char: c.to_string(),
+                    start: self.start,
+                    end: self.start,
+                    style: None,
+                    font: None,
+                    stroke: None,
+                    shadow: None,
+                    effects: Vec::new(),
+                    transform: None,
+                    pitch: None,
+                    ruby: None,
+                    singer: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Char {
     /// The character(s) to display
@@ -91,6 +177,20 @@ pub struct Char {
     /// Character-specific transform
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transform: Option<Transform>,
+
+    /// Sung note as a MIDI note number (60 = middle C), from karaoke imports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pitch: Option<i32>,
+
+    /// Ruby text (furigana, pinyin) shown above this char and highlighted
+    /// with it. Use a multi-character `char` for ruby spanning a whole word.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruby: Option<String>,
+
+    /// Singer of this char when it differs from the line's, highlighting
+    /// it in that singer's color
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub singer: Option<String>,
 }
 
 #[cfg(test)]
@@ -120,6 +220,8 @@ mod tests {
         assert!(line.position.is_none());
         assert!(line.transform.is_none());
         assert!(line.layout.is_none());
+        assert!(line.translations.is_empty());
+        assert!(line.singer.is_none());
     }
 
     #[test]
@@ -141,6 +243,8 @@ mod tests {
         assert!(ch.shadow.is_none());
         assert!(ch.effects.is_empty());
         assert!(ch.transform.is_none());
+        assert!(ch.ruby.is_none());
+        assert!(ch.singer.is_none());
     }
 
     #[test]
@@ -161,4 +265,45 @@ mod tests {
         assert_eq!(line.chars[0].char, "H");
         assert_eq!(line.chars[1].char, "i");
     }
+
+    #[test]
+    fn test_translation_line() {
+        let doc: KLyricDocumentV2 = serde_json::from_str(
+            r#"{
+                "version": "2.0",
+                "project": { "title": "t", "duration": 10, "resolution": { "width": 1920, "height": 1080 } },
+                "effects": {
+                    "bob": { "type": "keyframe" },
+                    "emit": { "type": "particle" }
+                },
+                "lines": []
+            }"#,
+        )
+        .unwrap();
+        let json = r#"{
+            "start": 1.0,
+            "end": 3.0,
+            "style": "base",
+            "effects": ["fade", "bob", "emit", "missing"],
+            "chars": [{ "char": "你", "start": 1.0, "end": 2.0 }],
+            "translations": [
+                { "text": "Hi", "lang": "en" },
+                { "text": "Ni", "style": "romaji" }
+            ]
+        }"#;
+
+        let line: Line = serde_json::from_str(json).unwrap();
+        assert_eq!(line.translations[0].lang.as_deref(), Some("en"));
+
+        let en = line.translation_line(&line.translations[0], &doc);
+        assert_eq!(en.style.as_deref(), Some("base"));
+        // The emitter stays with the original line
+        assert_eq!(en.effects, vec!["fade".to_string(), "bob".to_string()]);
+        assert_eq!(en.chars.len(), 2);
+        assert!(en.chars.iter().all(|c| c.start == 1.0 && c.end == 1.0));
+        assert!(en.translations.is_empty());
+
+        let romaji = line.translation_line(&line.translations[1], &doc);
+        assert_eq!(romaji.style.as_deref(), Some("romaji"));
+    }
 }
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use super::modifiers::ValueDriver;
+
+/// Region a line's text shows through, and what the text is filled with.
+///
+/// The shape is placed in fractions of the line's text bounds, so
+/// `{ "x": 0, "y": 0, "width": 1, "height": 1 }` covers the whole line.
+/// Each value is a driver: `Linear` runs over the line's duration, the
+/// others take the seconds since the line started.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Mask {
+    /// Clip shape; without one the whole line shows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shape: Option<MaskShape>,
+
+    /// Left edge of the shape (default 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x: Option<ValueDriver>,
+
+    /// Top edge of the shape (default 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<ValueDriver>,
+
+    /// Width of the shape (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<ValueDriver>,
+
+    /// Height of the shape (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<ValueDriver>,
+
+    /// Corner radius of a `rect` in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f32>,
+
+    /// Show the text outside the shape instead of inside it
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub invert: bool,
+
+    /// What the visible text is painted with
+    #[serde(default, skip_serializing_if = "MaskFill::is_color")]
+    pub fill: MaskFill,
+
+    /// Image for the `background` fill, relative to the document's file.
+    /// Defaults to the background's `image`; a background `video` is never
+    /// used, so give a still of it here.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub src: Option<String>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MaskShape {
+    #[default]
+    Rect,
+    Ellipse,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum MaskFill {
+    /// The text's own colors
+    #[default]
+    Color,
+    /// The background image, seen through the text. Only images are
+    /// supported, not video.
+    Background,
+}
+
+impl MaskFill {
+    pub fn is_color(&self) -> bool {
+        *self == MaskFill::Color
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_deserialization() {
+        let json = r#"{
+            "shape": "rect",
+            "x": { "mode": "Linear", "start": -1, "end": 0, "ease": "QuadOut" },
+            "radius": 8,
+            "invert": true
+        }"#;
+        let mask: Mask = serde_json::from_str(json).unwrap();
+        assert_eq!(mask.shape, Some(MaskShape::Rect));
+        assert!(matches!(mask.x, Some(ValueDriver::Linear { .. })));
+        assert!(mask.width.is_none());
+        assert!(mask.invert);
+        assert_eq!(mask.fill, MaskFill::Color);
+
+        let mask: Mask = serde_json::from_str(r#"{ "fill": "background" }"#).unwrap();
+        assert!(mask.shape.is_none());
+        assert_eq!(mask.fill, MaskFill::Background);
+        let json = serde_json::to_value(&mask).unwrap();
+        assert_eq!(json, serde_json::json!({ "fill": "background" }));
+    }
+}
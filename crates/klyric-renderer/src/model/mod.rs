@@ -1,17 +1,29 @@
 pub mod document;
 pub mod effect;
 pub mod layout;
+pub mod library;
 pub mod line;
+pub mod mask;
 pub mod modifiers;
+pub mod overlay;
 pub mod project;
+pub mod section;
+pub mod singer;
 pub mod style;
 pub mod theme;
+pub mod track;
 
 pub use document::*;
 pub use effect::*;
 pub use layout::*;
+pub use library::*;
 pub use line::*;
+pub use mask::*;
 pub use modifiers::*;
+pub use overlay::*;
 pub use project::*;
+pub use section::*;
+pub use singer::*;
 pub use style::*;
 pub use theme::*;
+pub use track::*;
@@ -1,6 +1,7 @@
+use crate::audio::AudioBand;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(tag = "type", content = "params")]
 pub enum Modifier {
     // --- Transform Modifiers ---
@@ -13,6 +14,7 @@ pub enum Modifier {
     Color(ColorParams),
     Fade(FadeParams),
     Blur(f32),
+    Adjust(AdjustParams),
 
     // --- Deformers ---
     Wave(WaveParams),
@@ -27,71 +29,92 @@ pub enum Modifier {
     Emit(EmitParams),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct MoveParams {
     pub x: ValueDriver,
     pub y: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct ScaleParams {
     pub x: ValueDriver,
     pub y: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct RotateParams {
     pub angle: ValueDriver,
     pub pivot_x: f32,
     pub pivot_y: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct ColorParams {
     // Simplified for now, can be expanded
     pub fill: Option<String>,
     pub stroke: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct FadeParams {
     pub value: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Color adjustments on top of the char's own: hue and invert are added,
+/// the factors multiplied
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AdjustParams {
+    /// Hue shift in degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hue_shift: Option<ValueDriver>,
+    /// Brightness factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<ValueDriver>,
+    /// Contrast factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contrast: Option<ValueDriver>,
+    /// Saturation factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saturation: Option<ValueDriver>,
+    /// Color inversion amount (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invert: Option<ValueDriver>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct WaveParams {
     pub freq: ValueDriver,
     pub amp: ValueDriver,
     pub speed: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct JitterParams {
     pub amount: ValueDriver,
     pub speed: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct PerspectParams {
     pub depth: ValueDriver,
     pub rotate_x: ValueDriver,
     pub rotate_y: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct AppearParams {
     pub mode: AppearMode,
     pub progress: ValueDriver,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum AppearMode {
     Typewriter,
     Fade,
     Random,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct EmitParams {
     pub preset: String, // Reference to particle preset
     pub rate: ValueDriver,
@@ -99,7 +122,7 @@ pub struct EmitParams {
 
 // --- Value Drivers ---
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(tag = "mode")]
 pub enum ValueDriver {
     Fixed {
@@ -125,6 +148,12 @@ pub enum ValueDriver {
         values: Vec<f32>,
         interval: f32,
     },
+    /// `base + amp * level`, where level is the analyzed audio band (0.0 - 1.0)
+    Audio {
+        band: AudioBand,
+        base: f32,
+        amp: f32,
+    },
     // Defaults for easy JSON
     Default,
 }
@@ -135,7 +164,7 @@ impl Default for ValueDriver {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum DriverEasing {
     Linear,
     QuadIn,
@@ -157,13 +186,13 @@ pub enum DriverEasing {
 
 // --- Selectors (Targeting) ---
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct EffectLayer {
     pub selector: Selector,
     pub modifiers: Vec<Modifier>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(tag = "mode", content = "args")]
 pub enum Selector {
     All,
@@ -172,9 +201,11 @@ pub enum Selector {
     TimeRange { start: f32, end: f32 },
     Text { contains: String },
     Tag(String),
+    // Lines of the named song section (see `KLyricDocumentV2::sections`)
+    Section(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum ScopeType {
     Document,
     Line,
@@ -213,6 +244,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_adjust_deserialization() {
+        let json = r#"{
+            "type": "Adjust",
+            "params": {
+                "hue_shift": { "mode": "Linear", "start": 0.0, "end": 360.0, "ease": "Linear" },
+                "saturation": { "mode": "Fixed", "val": 1.5 }
+            }
+        }"#;
+
+        let modifier: Modifier = serde_json::from_str(json).unwrap();
+        match modifier {
+            Modifier::Adjust(params) => {
+                assert!(matches!(params.hue_shift, Some(ValueDriver::Linear { .. })));
+                assert!(
+                    matches!(params.saturation, Some(ValueDriver::Fixed { val }) if val == 1.5)
+                );
+                assert!(params.brightness.is_none());
+                assert!(params.invert.is_none());
+            }
+            _ => panic!("Expected Adjust"),
+        }
+    }
+
     #[test]
     fn test_value_driver_deserialization() {
         let json_linear = r#"{
@@ -263,4 +318,22 @@ mod tests {
             _ => panic!("Expected Pattern"),
         }
     }
+
+    #[test]
+    fn test_audio_driver() {
+        use crate::audio::AudioLevels;
+        use crate::effects::drivers::DriverManager;
+
+        let json = r#"{ "mode": "Audio", "band": "bass", "base": 1.0, "amp": 0.5 }"#;
+        let driver: ValueDriver = serde_json::from_str(json).unwrap();
+
+        let audio = AudioLevels {
+            bass: 0.8,
+            ..Default::default()
+        };
+        let val = DriverManager::evaluate_with_audio(&driver, 0.0, &audio);
+        assert!((val - 1.4).abs() < 1e-6);
+        // No analysis loaded: the band reads as silence
+        assert_eq!(DriverManager::evaluate(&driver, 0.0), 1.0);
+    }
 }
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use super::layout::{Position, Transform};
+use super::line::{Char, Line};
+
+/// A static element on screen for a time range (title card, credit,
+/// watermark), drawn over the lyrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Overlay {
+    /// Overlay type
+    #[serde(rename = "type", default)]
+    pub kind: OverlayKind,
+
+    /// Start time in seconds
+    pub start: f64,
+
+    /// End time in seconds
+    pub end: f64,
+
+    /// Text of a `text` overlay
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// Image or SVG path of an `image` overlay, relative to the document's
+    /// file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub src: Option<String>,
+
+    /// Width in pixels. Images default to their own size, keeping the
+    /// aspect ratio when only one side is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f32>,
+
+    /// Height in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<f32>,
+
+    /// Corner radius of a `rect`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f32>,
+
+    /// Style name to apply. Text uses its font and `complete` colors;
+    /// shapes are filled with the `complete` fill and drawn with its stroke.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+
+    /// Effect names to apply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<String>,
+
+    /// Position of the overlay's center
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+
+    /// Overlay transform
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayKind {
+    #[default]
+    Text,
+    Image,
+    Rect,
+    Ellipse,
+}
+
+impl Overlay {
+    pub fn contains(&self, time: f64) -> bool {
+        time >= self.start && time <= self.end
+    }
+
+    /// The overlay as a line, so it goes through the line style and effect
+    /// pipeline. Text is timed to the start and shows in its `complete`
+    /// colors; other kinds have no chars.
+    pub fn to_line(&self) -> Line {
+        let text = match self.kind {
+            OverlayKind::Text => self.text.as_deref().unwrap_or_default(),
+            _ => "",
+        };
+        Line {
+            start: self.start,
+            end: self.end,
+            text: Some(text.to_string()),
+            style: self.style.clone(),
+            effects: self.effects.clone(),
+            position: self.position.clone(),
+            transform: self.transform.clone(),
+            chars: text
+                .chars()
+                .map(|c| Char {
+                    char: c.to_string(),
+                    start: self.start,
+                    end: self.start,
+                    style: None,
+                    font: None,
+                    stroke: None,
+                    shadow: None,
+                    effects: Vec::new(),
+                    transform: None,
+                    pitch: None,
+                    ruby: None,
+                    singer: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_deserialization() {
+        let json = r#"[
+            { "start": 0, "end": 3, "text": "Title", "style": "card", "effects": ["fade"] },
+            { "type": "image", "start": 0, "end": 60, "src": "logo.png", "width": 120 },
+            { "type": "rect", "start": 1, "end": 2, "width": 400, "height": 80, "radius": 12 }
+        ]"#;
+        let overlays: Vec<Overlay> = serde_json::from_str(json).unwrap();
+        assert_eq!(overlays[0].kind, OverlayKind::Text);
+        assert_eq!(overlays[1].kind, OverlayKind::Image);
+        assert_eq!(overlays[1].width, Some(120.0));
+        assert_eq!(overlays[2].kind, OverlayKind::Rect);
+        assert!(overlays[2].contains(1.5));
+        assert!(!overlays[2].contains(2.5));
+
+        let line = overlays[0].to_line();
+        assert_eq!(line.chars.len(), 5);
+        assert_eq!(line.chars[4].start, 0.0);
+        assert_eq!(line.style.as_deref(), Some("card"));
+        assert_eq!(line.effects, vec!["fade".to_string()]);
+        assert!(overlays[1].to_line().chars.is_empty());
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// A named part of the song (intro, verse, chorus, ...) over a time range
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Section {
+    /// Section name. Repeated parts share one (every chorus is `chorus`),
+    /// which is what `Selector::Section` and theme overrides match.
+    pub name: String,
+
+    /// Start time in seconds
+    pub start: f64,
+
+    /// End time in seconds
+    pub end: f64,
+}
+
+impl Section {
+    pub fn contains(&self, time: f64) -> bool {
+        time >= self.start && time <= self.end
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// A singer (or part) that lines and chars can be attributed to
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Singer {
+    /// Style for this singer's lines that don't name one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+
+    /// Highlight color, replacing the style's active fill
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// Screen region for this singer's lines. Unset, lines keep their own
+    /// position unless another singer is on screen at the same time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<Placement>,
+}
+
+/// Screen region a singer's lines are moved to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Placement {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Center,
+}
+
+impl Placement {
+    /// Position as fractions of the frame `(width, height)`. `None` keeps
+    /// the line's own coordinate on that axis.
+    pub fn region(self) -> (Option<f32>, Option<f32>) {
+        match self {
+            Placement::Left => (Some(0.25), None),
+            Placement::Right => (Some(0.75), None),
+            Placement::Top => (None, Some(0.3)),
+            Placement::Bottom => (None, Some(0.7)),
+            Placement::Center => (Some(0.5), Some(0.5)),
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Style {
     /// Parent style to inherit from
@@ -23,10 +23,23 @@ pub struct Style {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub shadow: Option<Shadow>,
 
+    /// Extra outlines drawn under `stroke`, first at the bottom
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strokes: Option<Vec<Stroke>>,
+
+    /// Extra shadows drawn under `shadow` (or over the fill when `inset`),
+    /// first at the bottom
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shadows: Option<Vec<Shadow>>,
+
     /// Glow effect settings
     #[serde(skip_serializing_if = "Option::is_none")]
     pub glow: Option<Glow>,
 
+    /// Clip region and fill of the text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask: Option<Mask>,
+
     /// Global transform
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transform: Option<Transform>,
@@ -38,12 +51,176 @@ pub struct Style {
     /// Modifier layers (New System)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub layers: Option<Vec<EffectLayer>>,
+
+    /// Size and spacing of ruby text above chars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruby: Option<RubyStyle>,
+
+    /// Inherited fields to clear before this style is applied, by JSON path
+    /// (`"shadow"`, `"font.family"`, `"colors.active.stroke"`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reset: Vec<String>,
+
+    /// List fields (`effects`, `layers`, `strokes`, `shadows`) that extend the
+    /// inherited list instead of replacing it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub append: Vec<String>,
 }
 
 use super::layout::Transform;
+use super::mask::Mask;
 use super::modifiers::EffectLayer;
+use super::theme::Gradient;
+
+/// List fields that `Style::append` can name
+pub const APPENDABLE_FIELDS: &[&str] = &["effects", "layers", "strokes", "shadows"];
+
+/// Field-level overlay used by style inheritance
+pub trait Merge {
+    /// Overwrite the fields that `over` sets, keeping the rest
+    fn merge(&mut self, over: &Self);
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Merge an optional sub-struct field by field, adopting it if unset
+pub fn merge_option<T: Merge + Clone>(target: &mut Option<T>, over: &Option<T>) {
+    match (target.as_mut(), over) {
+        (Some(target), Some(over)) => target.merge(over),
+        (None, Some(over)) => *target = Some(over.clone()),
+        _ => {}
+    }
+}
+
+/// Replace a list, or extend it when `append` is set
+fn merge_list<T: Clone>(target: &mut Option<Vec<T>>, over: &Option<Vec<T>>, append: bool) {
+    match (target.as_mut(), over) {
+        (Some(target), Some(over)) if append => target.extend(over.iter().cloned()),
+        (_, Some(over)) => *target = Some(over.clone()),
+        _ => {}
+    }
+}
+
+/// `Merge` for structs of plain `Option` fields. The destructuring pattern
+/// fails to compile if a field is added to the struct but not listed here.
+macro_rules! impl_merge {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl Merge for $ty {
+            fn merge(&mut self, over: &Self) {
+                let $ty { $($field),* } = over;
+                $(
+                    if $field.is_some() {
+                        self.$field = $field.clone();
+                    }
+                )*
+            }
+        }
+    };
+}
+
+impl_merge!(Font { family, size, weight, style, letter_spacing });
+impl_merge!(Stroke { width, color });
+impl_merge!(Shadow { color, x, y, blur, inset });
+impl_merge!(Glow { color, blur, intensity });
+impl_merge!(RubyStyle { scale, gap });
+impl_merge!(Transform {
+    x,
+    y,
+    rotation,
+    scale,
+    scale_x,
+    scale_y,
+    opacity,
+    anchor_x,
+    anchor_y,
+    blur,
+    glitch_offset,
+    hue_shift,
+    brightness,
+    contrast,
+    saturation,
+    invert,
+});
+
+impl Merge for FillStroke {
+    fn merge(&mut self, over: &Self) {
+        // A fill of any kind replaces the inherited one, so a solid color
+        // isn't hidden under an inherited gradient
+        if over.fill.is_some() || over.gradient.is_some() || over.pattern.is_some() {
+            self.fill = over.fill.clone();
+            self.gradient = over.gradient.clone();
+            self.pattern = over.pattern.clone();
+        }
+        if over.stroke.is_some() {
+            self.stroke = over.stroke.clone();
+        }
+    }
+}
+
+impl Merge for StateColors {
+    fn merge(&mut self, over: &Self) {
+        merge_option(&mut self.inactive, &over.inactive);
+        merge_option(&mut self.active, &over.active);
+        merge_option(&mut self.complete, &over.complete);
+    }
+}
+
+impl Style {
+    /// Apply `child` on top of this (inherited) style: clear its `reset`
+    /// paths, merge sub-structs field by field, and replace or extend the
+    /// `effects`/`layers`/`strokes`/`shadows` lists. `extends`, `reset` and
+    /// `append` describe the child itself and are not carried over.
+    pub fn inherit(&mut self, child: &Style) {
+        for path in &child.reset {
+            self.reset_field(path);
+        }
+        merge_option(&mut self.font, &child.font);
+        merge_option(&mut self.colors, &child.colors);
+        merge_option(&mut self.stroke, &child.stroke);
+        merge_option(&mut self.shadow, &child.shadow);
+        merge_option(&mut self.glow, &child.glow);
+        // A mask's shape and drivers only make sense together
+        if child.mask.is_some() {
+            self.mask = child.mask.clone();
+        }
+        merge_option(&mut self.transform, &child.transform);
+        merge_option(&mut self.ruby, &child.ruby);
+        merge_list(&mut self.effects, &child.effects, child.appends("effects"));
+        merge_list(&mut self.layers, &child.layers, child.appends("layers"));
+        merge_list(&mut self.strokes, &child.strokes, child.appends("strokes"));
+        merge_list(&mut self.shadows, &child.shadows, child.appends("shadows"));
+    }
+
+    /// Whether `field` is listed in `append`
+    pub fn appends(&self, field: &str) -> bool {
+        self.append.iter().any(|f| f == field)
+    }
+
+    /// Clear the field at a dotted JSON path (`"font.family"`) back to its
+    /// default. Returns false if nothing was set there.
+    pub fn reset_field(&mut self, path: &str) -> bool {
+        let Ok(mut value) = serde_json::to_value(&*self) else {
+            return false;
+        };
+        let (parent, field) = path.rsplit_once('.').unwrap_or(("", path));
+        let mut object = value.as_object_mut();
+        for segment in parent.split('.').filter(|s| !s.is_empty()) {
+            object = object
+                .and_then(|o| o.get_mut(segment))
+                .and_then(|v| v.as_object_mut());
+        }
+        if object.and_then(|o| o.remove(field)).is_none() {
+            return false;
+        }
+        match serde_json::from_value(value) {
+            Ok(style) => {
+                *self = style;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Font {
     /// Font family (comma-separated for fallbacks)
@@ -95,7 +272,7 @@ impl Font {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum FontStyle {
     #[default]
@@ -104,7 +281,7 @@ pub enum FontStyle {
     Oblique,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StateColors {
     /// Colors before character is highlighted
@@ -130,6 +307,14 @@ pub struct FillStroke {
     /// Stroke color
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stroke: Option<String>,
+
+    /// Gradient replacing the solid `fill`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gradient: Option<TextGradient>,
+
+    /// Image pattern replacing the solid `fill` and `gradient`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<Pattern>,
 }
 
 // Custom deserializer to handle both string and object formats
@@ -158,6 +343,8 @@ impl<'de> serde::Deserialize<'de> for FillStroke {
                 Ok(FillStroke {
                     fill: Some(value.to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 })
             }
 
@@ -166,20 +353,22 @@ impl<'de> serde::Deserialize<'de> for FillStroke {
             where
                 M: MapAccess<'de>,
             {
-                let mut fill: Option<String> = None;
-                let mut stroke: Option<String> = None;
-
-                while let Some(key) = map.next_key::<&str>()? {
-                    match key {
-                        "fill" => fill = map.next_value()?,
-                        "stroke" => stroke = map.next_value()?,
+                let mut fill_stroke = FillStroke::default();
+
+                // Owned keys, so this also works from `serde_json::Value`
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "fill" => fill_stroke.fill = map.next_value()?,
+                        "stroke" => fill_stroke.stroke = map.next_value()?,
+                        "gradient" => fill_stroke.gradient = map.next_value()?,
+                        "pattern" => fill_stroke.pattern = map.next_value()?,
                         _ => {
                             let _ = map.next_value::<serde::de::IgnoredAny>()?;
                         }
                     }
                 }
 
-                Ok(FillStroke { fill, stroke })
+                Ok(fill_stroke)
             }
         }
 
@@ -187,7 +376,82 @@ impl<'de> serde::Deserialize<'de> for FillStroke {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+impl schemars::JsonSchema for FillStroke {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "FillStroke".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let gradient = generator.subschema_for::<TextGradient>();
+        let pattern = generator.subschema_for::<Pattern>();
+        schemars::json_schema!({
+            "description": "A fill color string, or an object with fill/stroke colors and an optional gradient or pattern fill",
+            "oneOf": [
+                { "type": "string" },
+                {
+                    "type": "object",
+                    "properties": {
+                        "fill": { "type": "string" },
+                        "stroke": { "type": "string" },
+                        "gradient": gradient,
+                        "pattern": pattern
+                    }
+                }
+            ]
+        })
+    }
+}
+
+/// Gradient across the text: a background gradient plus the area it's laid
+/// out over
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TextGradient {
+    #[serde(flatten)]
+    pub gradient: Gradient,
+
+    /// Whether the gradient runs across the whole line or restarts at each
+    /// glyph
+    #[serde(default, skip_serializing_if = "FillSpan::is_line")]
+    pub span: FillSpan,
+}
+
+/// Image tiled across the text
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Pattern {
+    /// Image path, relative to the document's file
+    pub src: String,
+
+    /// Tile scale (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+
+    /// Whether the tiles run across the whole line or restart at each glyph
+    #[serde(default, skip_serializing_if = "FillSpan::is_line")]
+    pub span: FillSpan,
+}
+
+/// Area a text gradient or pattern is laid out over
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FillSpan {
+    /// The line's text bounds, so the fill runs across it
+    #[default]
+    Line,
+    /// Each glyph's own bounds
+    Glyph,
+}
+
+impl FillSpan {
+    pub fn is_line(&self) -> bool {
+        *self == FillSpan::Line
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Stroke {
     /// Stroke width in pixels
@@ -211,6 +475,7 @@ impl Stroke {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::theme::GradientType;
 
     #[test]
     fn test_style_deserialization_defaults() {
@@ -264,6 +529,13 @@ mod tests {
         assert_eq!(glow.color_or_default(), "");
     }
 
+    #[test]
+    fn test_ruby_style_defaults() {
+        let ruby = RubyStyle::default();
+        assert_eq!(ruby.scale_or_default(), 0.5);
+        assert_eq!(ruby.gap_or_default(), 4.0);
+    }
+
     #[test]
     fn test_fill_stroke_custom_deserialization_string() {
         let json = r#""#FFFFFF""#;
@@ -282,10 +554,71 @@ mod tests {
         let fs: FillStroke = serde_json::from_str(json).unwrap();
         assert_eq!(fs.fill.as_deref(), Some("#FF0000"));
         assert_eq!(fs.stroke.as_deref(), Some("#00FF00"));
+
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let fs: FillStroke = serde_json::from_value(value).unwrap();
+        assert_eq!(fs.stroke.as_deref(), Some("#00FF00"));
+    }
+
+    #[test]
+    fn test_fill_stroke_gradient_and_pattern() {
+        let json = r##"{
+            "fill": "#FFD700",
+            "gradient": { "type": "sweep", "colors": ["#FF0000", "#00FF00", "#0000FF"], "span": "glyph" }
+        }"##;
+        let fs: FillStroke = serde_json::from_str(json).unwrap();
+        let gradient = fs.gradient.as_ref().unwrap();
+        assert!(matches!(gradient.gradient.gradient_type, GradientType::Sweep));
+        assert_eq!(gradient.span, FillSpan::Glyph);
+        assert_eq!(gradient.gradient.angle, 180.0);
+        // Written back flat, as it was read
+        let written = serde_json::to_value(gradient).unwrap();
+        assert_eq!(written["type"], "sweep");
+        assert_eq!(written["span"], "glyph");
+
+        let json = r#"{ "pattern": { "src": "foil.png", "scale": 0.5 } }"#;
+        let pattern: FillStroke = serde_json::from_str(json).unwrap();
+        assert_eq!(pattern.pattern.as_ref().unwrap().span, FillSpan::Line);
+
+        // A fill of any kind replaces the inherited one; the stroke stays
+        let mut merged = FillStroke {
+            stroke: Some("#000000".to_string()),
+            ..fs
+        };
+        merged.merge(&pattern);
+        assert!(merged.fill.is_none());
+        assert!(merged.gradient.is_none());
+        assert_eq!(merged.pattern.unwrap().src, "foil.png");
+        assert_eq!(merged.stroke.as_deref(), Some("#000000"));
+    }
+
+    #[test]
+    fn test_layered_strokes_and_shadows() {
+        let json = r##"{
+            "strokes": [{ "width": 10, "color": "#000000" }, { "width": 4, "color": "#FFFFFF" }],
+            "shadows": [{ "color": "#00000080", "x": 0, "y": 0, "blur": 6, "inset": true }]
+        }"##;
+        let mut style: Style = serde_json::from_str(json).unwrap();
+        let strokes = style.strokes.as_ref().unwrap();
+        assert_eq!(strokes[1].width_or_default(), 4.0);
+        assert_eq!(style.shadows.as_ref().unwrap()[0].inset, Some(true));
+
+        // Lists replace the inherited ones unless appended
+        let json = r##"{
+            "strokes": [{ "width": 14, "color": "#FF0080" }],
+            "shadows": [{ "color": "#000000", "x": 4, "y": 4 }],
+            "append": ["strokes"]
+        }"##;
+        let child: Style = serde_json::from_str(json).unwrap();
+        style.inherit(&child);
+        assert_eq!(style.strokes.as_ref().unwrap().len(), 3);
+        let shadows = style.shadows.as_ref().unwrap();
+        assert_eq!(shadows.len(), 1);
+        assert!(shadows[0].inset.is_none());
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Shadow {
     /// Shadow color
@@ -303,6 +636,10 @@ pub struct Shadow {
     /// Blur radius in pixels
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blur: Option<f32>,
+
+    /// Inner shadow, cast inside the glyphs over the fill
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inset: Option<bool>,
 }
 
 pub fn default_shadow_offset() -> f32 {
@@ -327,7 +664,7 @@ impl Shadow {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Glow {
     /// Glow color
@@ -361,3 +698,31 @@ impl Glow {
         self.color.clone().unwrap_or_default()
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RubyStyle {
+    /// Ruby font size relative to the base text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+
+    /// Space between the ruby and the top of the base text, in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gap: Option<f32>,
+}
+
+pub fn default_ruby_scale() -> f32 {
+    0.5
+}
+pub fn default_ruby_gap() -> f32 {
+    4.0
+}
+
+impl RubyStyle {
+    pub fn scale_or_default(&self) -> f32 {
+        self.scale.unwrap_or_else(default_ruby_scale)
+    }
+    pub fn gap_or_default(&self) -> f32 {
+        self.gap.unwrap_or_else(default_ruby_gap)
+    }
+}
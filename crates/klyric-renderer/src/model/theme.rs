@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Theme {
     /// Background configuration
@@ -10,9 +11,30 @@ pub struct Theme {
     /// Name of default style for all lines
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_style: Option<String>,
+
+    /// Overrides while a section plays, by section name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sections: HashMap<String, SectionTheme>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Theme settings replaced for the duration of a section
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SectionTheme {
+    /// Background while the section plays
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<Background>,
+
+    /// Style for the section's lines that don't name one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_style: Option<String>,
+
+    /// Effect names added to every line of the section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Background {
     /// Background type
@@ -44,7 +66,7 @@ fn default_opacity() -> f32 {
     1.0
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum BackgroundType {
     #[default]
@@ -54,7 +76,7 @@ pub enum BackgroundType {
     Video,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Gradient {
     /// Gradient type
@@ -64,7 +86,8 @@ pub struct Gradient {
     /// Array of colors in gradient
     pub colors: Vec<String>,
 
-    /// Angle in degrees for linear gradients
+    /// Angle in degrees: the direction of a linear gradient and where a
+    /// sweep starts (0 is up, clockwise)
     #[serde(default = "default_gradient_angle")]
     pub angle: f32,
 
@@ -77,12 +100,14 @@ fn default_gradient_angle() -> f32 {
     180.0
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum GradientType {
     #[default]
     Linear,
     Radial,
+    /// Colors around the center, like a color wheel
+    Sweep,
 }
 
 #[cfg(test)]
@@ -95,6 +120,25 @@ mod tests {
         let theme: Theme = serde_json::from_str(json).unwrap();
         assert!(theme.background.is_none());
         assert!(theme.default_style.is_none());
+        assert!(theme.sections.is_empty());
+    }
+
+    #[test]
+    fn test_section_theme() {
+        let json = r##"{
+            "background": { "color": "#000000" },
+            "sections": {
+                "chorus": {
+                    "background": { "type": "gradient", "gradient": { "colors": ["#FF0000", "#0000FF"] } },
+                    "effects": ["confetti"]
+                }
+            }
+        }"##;
+        let theme: Theme = serde_json::from_str(json).unwrap();
+        let chorus = &theme.sections["chorus"];
+        assert!(chorus.background.as_ref().unwrap().gradient.is_some());
+        assert_eq!(chorus.effects, vec!["confetti".to_string()]);
+        assert!(chorus.default_style.is_none());
     }
 
     #[test]
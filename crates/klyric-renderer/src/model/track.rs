@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use super::singer::Placement;
+
+/// An independent text layer (main lyrics, backing vocals, title card, ...)
+/// that lines belong to
+#[derive(Debug, Clone, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Track {
+    /// Style for this track's lines that name none, after their singer's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+
+    /// Screen region for this track's lines; a singer's placement wins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<Placement>,
+
+    /// Compositing order; higher tracks are drawn on top. Lines without a
+    /// track are at 0.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub z_index: i32,
+
+    /// Hidden in preview. Editor state, never saved; exports include every
+    /// track.
+    #[serde(skip)]
+    pub muted: bool,
+
+    /// When any track is soloed, preview shows only soloed tracks. Editor
+    /// state, never saved.
+    #[serde(skip)]
+    pub solo: bool,
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
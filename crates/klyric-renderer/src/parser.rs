@@ -1,22 +1,59 @@
+use super::migrate::{migrate, MigrationReport, CURRENT_VERSION};
 use super::model::KLyricDocumentV2;
+use super::style::StyleResolver;
+use super::validate::{validate_json, Severity};
 use anyhow::{Context, Result};
+use std::path::Path;
 
-/// Parse a KLyric v2.0 document from a JSON string
+/// Parse a KLyric document from a JSON string, upgrading older format versions
 pub fn parse_document(json: &str) -> Result<KLyricDocumentV2> {
-    let doc: KLyricDocumentV2 =
-        serde_json::from_str(json).context("Failed to parse KLyric v2.0 JSON")?;
-
-    // Validate version
-    if doc.version != "2.0" {
-        return Err(anyhow::anyhow!(
-            "Unsupported KLyric version: {}",
-            doc.version
-        ));
+    let (doc, report) = parse_document_with_report(json)?;
+    for change in &report.changes {
+        log::info!("Migrated document from {}: {}", report.from_version, change);
     }
+    Ok(doc)
+}
 
+/// Read a document file and load its `imports` relative to it. Asset
+/// paths resolve relative to it too (see `KLyricDocumentV2::base_dir`).
+pub fn load_document(path: &Path) -> Result<KLyricDocumentV2> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut doc = parse_document(&json)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    if !doc.imports.is_empty() {
+        StyleResolver::load_imports(&mut doc, dir)?;
+    }
+    doc.base_dir = Some(dir.to_path_buf());
     Ok(doc)
 }
 
+/// Like `parse_document`, also returning what the migration changed
+pub fn parse_document_with_report(json: &str) -> Result<(KLyricDocumentV2, MigrationReport)> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).context("Failed to parse KLyric JSON")?;
+    let (value, report) = migrate(value)?;
+
+    let doc: KLyricDocumentV2 = match serde_json::from_value(value) {
+        Ok(doc) => doc,
+        Err(e) => {
+            // Report every malformed section with its JSON path, not just the first
+            let details: Vec<String> = validate_json(json)
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .map(|d| d.to_string())
+                .collect();
+            return Err(e).context(format!(
+                "Failed to parse KLyric v{} JSON: {}",
+                CURRENT_VERSION,
+                details.join("; ")
+            ));
+        }
+    };
+
+    Ok((doc, report))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,4 +68,31 @@ mod tests {
             .to_string()
             .contains("Unsupported KLyric version"));
     }
+
+    #[test]
+    fn test_parser_reports_paths() {
+        let json = r#"{"version": "2.0", "lines": [{"start": 0, "end": "x", "chars": []}], "project": {"title":"", "duration":0, "resolution":{"width":0,"height":0}}}"#;
+        let err = parse_document(json).unwrap_err().to_string();
+        assert!(err.contains("$.lines[0].end: invalid type"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_document_base_dir() {
+        let dir = std::env::temp_dir().join(format!("klyric-parser-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("song.klyric");
+        std::fs::write(
+            &path,
+            r#"{"version": "2.0", "lines": [], "project": {"title":"", "duration":0, "resolution":{"width":0,"height":0}}}"#,
+        )
+        .unwrap();
+
+        let doc = load_document(&path).unwrap();
+        assert_eq!(doc.base_dir.as_deref(), Some(dir.as_path()));
+        assert_eq!(doc.asset_path("art/logo.svg"), dir.join("art/logo.svg"));
+
+        let doc = parse_document(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(doc.asset_path("logo.png"), Path::new("logo.png"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,250 @@
+//! Particle budget, level-of-detail settings and statistics
+
+use std::collections::HashMap;
+
+use super::emitter::ParticleEmitter;
+
+/// Default cap on live particles across all emitters
+pub const DEFAULT_MAX_PARTICLES: usize = 20_000;
+
+/// Particle level-of-detail settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleQuality {
+    /// Emission count scale used for interactive preview (0.0 - 1.0)
+    pub preview_scale: f32,
+    /// Emission count scale used for export (0.0 - 1.0)
+    pub export_scale: f32,
+    /// Maximum live particles across all emitters (None = unlimited)
+    pub max_particles: Option<usize>,
+}
+
+impl Default for ParticleQuality {
+    fn default() -> Self {
+        Self {
+            preview_scale: 0.5,
+            export_scale: 1.0,
+            max_particles: Some(DEFAULT_MAX_PARTICLES),
+        }
+    }
+}
+
+/// Live particle statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParticleStats {
+    /// Total emitters (including ones only waiting for particles to die)
+    pub emitters: usize,
+    /// Emitters still spawning
+    pub active_emitters: usize,
+    /// Live particles
+    pub particles: usize,
+    /// Particles removed by the budget during the last update
+    pub culled: usize,
+}
+
+impl ParticleStats {
+    /// Collect statistics from a set of emitters
+    pub fn collect(emitters: &HashMap<u64, ParticleEmitter>, culled: usize) -> Self {
+        Self {
+            emitters: emitters.len(),
+            active_emitters: emitters.values().filter(|e| e.active).count(),
+            particles: emitters.values().map(|e| e.particles.len()).sum(),
+            culled,
+        }
+    }
+}
+
+/// Remove particles until at most `max_particles` remain.
+///
+/// Emitters are culled lowest `priority` first. Within a priority level the
+/// excess is shared proportionally between emitters, and each emitter drops
+/// its oldest particles (closest to the end of their life) first.
+/// Ties are broken by emitter key so culling is deterministic.
+///
+/// Returns the number of particles removed.
+pub fn cull_to_budget(emitters: &mut HashMap<u64, ParticleEmitter>, max_particles: usize) -> usize {
+    let total: usize = emitters.values().map(|e| e.particles.len()).sum();
+    if total <= max_particles {
+        return 0;
+    }
+    let mut excess = total - max_particles;
+    let mut removed = 0;
+
+    let mut order: Vec<(i32, u64)> = emitters
+        .iter()
+        .filter(|(_, e)| !e.particles.is_empty())
+        .map(|(k, e)| (e.config.priority, *k))
+        .collect();
+    order.sort_unstable();
+
+    let mut i = 0;
+    while i < order.len() && excess > 0 {
+        // Group emitters sharing the same priority
+        let priority = order[i].0;
+        let mut j = i;
+        while j < order.len() && order[j].0 == priority {
+            j += 1;
+        }
+        let group = &order[i..j];
+
+        let group_total: usize = group.iter().map(|(_, k)| emitters[k].particles.len()).sum();
+        let group_excess = excess.min(group_total);
+        let mut group_removed = 0;
+
+        for (_, key) in group {
+            if group_removed >= group_excess {
+                break;
+            }
+            let emitter = emitters.get_mut(key).expect("emitter key from map");
+            let len = emitter.particles.len();
+            let share = (group_excess * len).div_ceil(group_total);
+            let n = share.min(len).min(group_excess - group_removed);
+            drop_oldest(emitter, n);
+            group_removed += n;
+        }
+
+        excess -= group_removed;
+        removed += group_removed;
+        i = j;
+    }
+
+    removed
+}
+
+/// Remove the `n` particles closest to the end of their lifetime.
+///
+/// Survivors keep their relative order so draw order doesn't change.
+fn drop_oldest(emitter: &mut ParticleEmitter, n: usize) {
+    if n == 0 {
+        return;
+    }
+    if n >= emitter.particles.len() {
+        emitter.particles.clear();
+        return;
+    }
+    let ages: Vec<f32> = emitter
+        .particles
+        .iter()
+        .map(|p| {
+            if p.max_life > 0.0 {
+                p.life / p.max_life
+            } else {
+                1.0
+            }
+        })
+        .collect();
+
+    // Partition indices so the `n` oldest come first
+    let mut order: Vec<usize> = (0..ages.len()).collect();
+    order.select_nth_unstable_by(n - 1, |&a, &b| ages[b].total_cmp(&ages[a]));
+    let mut dropped = vec![false; ages.len()];
+    for &idx in &order[..n] {
+        dropped[idx] = true;
+    }
+
+    let mut idx = 0;
+    emitter.particles.retain(|_| {
+        let keep = !dropped[idx];
+        idx += 1;
+        keep
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::particle::{ParticleConfig, RangeValue, SpawnPattern};
+
+    fn emitter(count: u32, priority: i32, seed: u64) -> ParticleEmitter {
+        let config = ParticleConfig {
+            count,
+            priority,
+            lifetime: RangeValue::Range(1.0, 2.0),
+            ..Default::default()
+        };
+        let mut e = ParticleEmitter::new(config, SpawnPattern::Point { x: 0.0, y: 0.0 }, seed);
+        e.burst();
+        e
+    }
+
+    #[test]
+    fn test_no_cull_under_budget() {
+        let mut emitters = HashMap::new();
+        emitters.insert(1, emitter(10, 0, 1));
+        assert_eq!(cull_to_budget(&mut emitters, 100), 0);
+        assert_eq!(emitters[&1].particles.len(), 10);
+    }
+
+    #[test]
+    fn test_cull_low_priority_first() {
+        let mut emitters = HashMap::new();
+        emitters.insert(1, emitter(50, 0, 1));
+        emitters.insert(2, emitter(50, 10, 2));
+
+        let removed = cull_to_budget(&mut emitters, 70);
+        assert_eq!(removed, 30);
+        assert_eq!(emitters[&1].particles.len(), 20);
+        assert_eq!(emitters[&2].particles.len(), 50);
+
+        // Budget below the high-priority count eats into it only after the low one is empty
+        let removed = cull_to_budget(&mut emitters, 40);
+        assert_eq!(removed, 30);
+        assert_eq!(emitters[&1].particles.len(), 0);
+        assert_eq!(emitters[&2].particles.len(), 40);
+    }
+
+    #[test]
+    fn test_cull_shares_within_priority() {
+        let mut emitters = HashMap::new();
+        emitters.insert(1, emitter(40, 0, 1));
+        emitters.insert(2, emitter(40, 0, 2));
+
+        let removed = cull_to_budget(&mut emitters, 40);
+        assert_eq!(removed, 40);
+        assert_eq!(emitters[&1].particles.len(), 20);
+        assert_eq!(emitters[&2].particles.len(), 20);
+    }
+
+    #[test]
+    fn test_cull_drops_oldest() {
+        let mut e = emitter(3, 0, 1);
+        for p in &mut e.particles {
+            p.max_life = 1.0;
+        }
+        e.particles[0].life = 0.9;
+        e.particles[1].life = 0.1;
+        e.particles[2].life = 0.5;
+        drop_oldest(&mut e, 2);
+        assert_eq!(e.particles.len(), 1);
+        assert_eq!(e.particles[0].life, 0.1);
+    }
+
+    #[test]
+    fn test_cull_keeps_survivor_order() {
+        let mut e = emitter(6, 0, 1);
+        for (i, p) in e.particles.iter_mut().enumerate() {
+            p.max_life = 1.0;
+            p.x = i as f32;
+        }
+        for (p, life) in e.particles.iter_mut().zip([0.2, 0.8, 0.1, 0.9, 0.3, 0.7]) {
+            p.life = life;
+        }
+        drop_oldest(&mut e, 3);
+        let xs: Vec<f32> = e.particles.iter().map(|p| p.x).collect();
+        assert_eq!(xs, vec![0.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut emitters = HashMap::new();
+        emitters.insert(1, emitter(5, 0, 1));
+        let mut stopped = emitter(3, 0, 2);
+        stopped.stop();
+        emitters.insert(2, stopped);
+
+        let stats = ParticleStats::collect(&emitters, 4);
+        assert_eq!(stats.emitters, 2);
+        assert_eq!(stats.active_emitters, 1);
+        assert_eq!(stats.particles, 8);
+        assert_eq!(stats.culled, 4);
+    }
+}
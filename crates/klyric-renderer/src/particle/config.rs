@@ -2,12 +2,15 @@
 
 use serde::{Deserialize, Serialize};
 
+use std::sync::Arc;
+
 use super::physics::ParticlePhysics;
 use super::rng::Rng;
-use super::types::{BlendMode, ParticleShape};
+use super::shape::{ShapeSampling, SpawnShape};
+use super::types::{BlendMode, ParticleLayer, ParticleLayerFilter, ParticleShape};
 
 /// Range of values for randomization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(untagged)]
 pub enum RangeValue {
     Single(f32),
@@ -45,6 +48,9 @@ impl ParticleConfig {
         self.shape = other.shape.clone();
         self.physics = other.physics.clone();
         self.blend_mode = other.blend_mode;
+        self.priority = other.priority;
+        self.layer = other.layer;
+        self.filter = other.filter;
     }
 }
 
@@ -55,7 +61,7 @@ impl Default for RangeValue {
 }
 
 /// Configuration for particle emission
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ParticleConfig {
     /// Number of particles per emission burst
@@ -109,6 +115,18 @@ pub struct ParticleConfig {
     /// Blend mode for rendering
     #[serde(default)]
     pub blend_mode: BlendMode,
+
+    /// Culling priority under the global particle budget (higher survives longer)
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Compositing layer relative to the text
+    #[serde(default)]
+    pub layer: ParticleLayer,
+
+    /// Optional blur/bloom pass over this emitter's particles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ParticleLayerFilter>,
 }
 
 fn default_count() -> u32 {
@@ -146,6 +164,9 @@ impl Default for ParticleConfig {
             shape: ParticleShape::Circle,
             physics: ParticlePhysics::default(),
             blend_mode: BlendMode::Normal,
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
         }
     }
 }
@@ -159,6 +180,34 @@ pub enum SpawnPattern {
     Line { x1: f32, y1: f32, x2: f32, y2: f32 },
     /// Rectangle area
     Rect { x: f32, y: f32, w: f32, h: f32 },
+    /// Filled circle
+    Circle { cx: f32, cy: f32, radius: f32 },
+    /// Annulus between two radii
+    Ring {
+        cx: f32,
+        cy: f32,
+        inner: f32,
+        outer: f32,
+    },
+    /// Custom shape in unit coordinates, mapped onto a box
+    Shape {
+        shape: Arc<SpawnShape>,
+        sampling: ShapeSampling,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
+    /// Glyph outline or fill, mapped onto the glyph box.
+    /// Samples the box itself until the glyph shape is attached.
+    Glyph {
+        shape: Option<Arc<SpawnShape>>,
+        sampling: ShapeSampling,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+    },
 }
 
 impl SpawnPattern {
@@ -170,8 +219,74 @@ impl SpawnPattern {
                 (x1 + (x2 - x1) * t, y1 + (y2 - y1) * t)
             }
             SpawnPattern::Rect { x, y, w, h } => (x + rng.next_f32() * w, y + rng.next_f32() * h),
+            SpawnPattern::Circle { cx, cy, radius } => sample_annulus(*cx, *cy, 0.0, *radius, rng),
+            SpawnPattern::Ring {
+                cx,
+                cy,
+                inner,
+                outer,
+            } => sample_annulus(*cx, *cy, *inner, *outer, rng),
+            SpawnPattern::Shape {
+                shape,
+                sampling,
+                x,
+                y,
+                w,
+                h,
+            } => {
+                let (u, v) = shape.sample(*sampling, rng);
+                (x + u * w, y + v * h)
+            }
+            SpawnPattern::Glyph {
+                shape,
+                sampling,
+                x,
+                y,
+                w,
+                h,
+            } => match shape {
+                Some(shape) if !shape.is_empty() => {
+                    let (u, v) = shape.sample(*sampling, rng);
+                    (x + u * w, y + v * h)
+                }
+                _ => match sampling {
+                    ShapeSampling::Fill => (x + rng.next_f32() * w, y + rng.next_f32() * h),
+                    ShapeSampling::Outline => sample_rect_outline(*x, *y, *w, *h, rng),
+                },
+            },
         }
     }
+
+    /// Whether this pattern is waiting for a glyph shape to be attached
+    pub fn needs_glyph_shape(&self) -> bool {
+        matches!(self, SpawnPattern::Glyph { shape: None, .. })
+    }
+}
+
+/// Uniformly sample a point in the annulus between `inner` and `outer`
+fn sample_annulus(cx: f32, cy: f32, inner: f32, outer: f32, rng: &mut Rng) -> (f32, f32) {
+    let angle = rng.angle();
+    let r2 = rng.range(inner * inner, outer * outer);
+    let r = r2.max(0.0).sqrt();
+    (cx + angle.cos() * r, cy + angle.sin() * r)
+}
+
+/// Uniformly sample a point on the perimeter of a rectangle
+fn sample_rect_outline(x: f32, y: f32, w: f32, h: f32, rng: &mut Rng) -> (f32, f32) {
+    let perimeter = 2.0 * (w + h);
+    if perimeter <= 0.0 {
+        return (x, y);
+    }
+    let d = rng.next_f32() * perimeter;
+    if d < w {
+        (x + d, y)
+    } else if d < w + h {
+        (x + w, y + d - w)
+    } else if d < 2.0 * w + h {
+        (x + w - (d - w - h), y + h)
+    } else {
+        (x, y + h - (d - 2.0 * w - h))
+    }
 }
 
 /// Apply expression overrides to particle configuration
@@ -269,6 +384,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spawn_pattern_ring() {
+        let pattern = SpawnPattern::Ring {
+            cx: 50.0,
+            cy: 50.0,
+            inner: 10.0,
+            outer: 20.0,
+        };
+        let mut rng = Rng::new(42);
+        for _ in 0..50 {
+            let (x, y) = pattern.sample(&mut rng);
+            let d = ((x - 50.0).powi(2) + (y - 50.0).powi(2)).sqrt();
+            assert!((9.99..=20.01).contains(&d), "distance {} outside ring", d);
+        }
+    }
+
+    #[test]
+    fn test_spawn_pattern_glyph_shape() {
+        // Triangle in unit space mapped onto a 100x100 box at (200, 300)
+        let shape = Arc::new(SpawnShape::from_svg_path("M0 1 L0.5 0 L1 1 Z").unwrap());
+        let mut pattern = SpawnPattern::Glyph {
+            shape: None,
+            sampling: ShapeSampling::Fill,
+            x: 200.0,
+            y: 300.0,
+            w: 100.0,
+            h: 100.0,
+        };
+        assert!(pattern.needs_glyph_shape());
+
+        if let SpawnPattern::Glyph { shape: s, .. } = &mut pattern {
+            *s = Some(shape.clone());
+        }
+        assert!(!pattern.needs_glyph_shape());
+
+        let mut rng = Rng::new(9);
+        for _ in 0..50 {
+            let (x, y) = pattern.sample(&mut rng);
+            assert!(shape.contains((x - 200.0) / 100.0, (y - 300.0) / 100.0));
+        }
+    }
+
     #[test]
     fn test_range_value_sample() {
         let mut rng = Rng::new(42);
@@ -2,6 +2,7 @@
 
 use super::config::{apply_particle_overrides, ParticleConfig, SpawnPattern};
 use super::rng::Rng;
+use super::shape::SpawnShape;
 use super::types::{parse_hex_color, Particle};
 use crate::expressions::FastEvaluationContext;
 use evalexpr::Node;
@@ -29,6 +30,8 @@ pub struct ParticleEmitter {
     pub elapsed: f32,
     /// If true, the emitter is managed by the frame loop (auto-deactivated if not touched)
     pub frame_driven: bool,
+    /// Level-of-detail scale applied to `config.count` (1.0 = full quality)
+    pub count_scale: f32,
 }
 
 impl ParticleEmitter {
@@ -44,12 +47,22 @@ impl ParticleEmitter {
             color_rgba,
             elapsed: 0.0,
             frame_driven: true,
+            count_scale: 1.0,
         }
     }
 
+    /// Particles per emission after applying the level-of-detail scale.
+    /// A non-zero count never scales below one particle.
+    pub fn scaled_count(&self) -> u32 {
+        if self.count_scale >= 1.0 || self.config.count == 0 {
+            return self.config.count;
+        }
+        ((self.config.count as f32 * self.count_scale.max(0.0)).round() as u32).max(1)
+    }
+
     /// Emit a burst of particles immediately
     pub fn burst(&mut self) {
-        for _ in 0..self.config.count {
+        for _ in 0..self.scaled_count() {
             self.spawn_particle();
         }
     }
@@ -107,7 +120,7 @@ impl ParticleEmitter {
             let spawn_interval = 1.0 / self.config.spawn_rate;
             while self.spawn_accumulator >= spawn_interval {
                 self.spawn_accumulator -= spawn_interval;
-                for _ in 0..self.config.count {
+                for _ in 0..self.scaled_count() {
                     self.spawn_particle();
                 }
             }
@@ -154,6 +167,41 @@ impl ParticleEmitter {
                 *y1 = y - 50.0;
                 *y2 = y - 50.0;
             }
+            SpawnPattern::Circle { cx, cy, .. } | SpawnPattern::Ring { cx, cy, .. } => {
+                *cx = x + w / 2.0;
+                *cy = y + h / 2.0;
+            }
+            SpawnPattern::Shape {
+                x: sx,
+                y: sy,
+                w: sw,
+                h: sh,
+                ..
+            }
+            | SpawnPattern::Glyph {
+                x: sx,
+                y: sy,
+                w: sw,
+                h: sh,
+                ..
+            } => {
+                *sx = x;
+                *sy = y;
+                *sw = w;
+                *sh = h;
+            }
+        }
+    }
+
+    /// Whether the spawn pattern is waiting for a glyph shape
+    pub fn needs_glyph_shape(&self) -> bool {
+        self.spawn_pattern.needs_glyph_shape()
+    }
+
+    /// Attach the glyph shape (unit coordinates over the glyph box) to a glyph pattern
+    pub fn set_glyph_shape(&mut self, glyph_shape: Arc<SpawnShape>) {
+        if let SpawnPattern::Glyph { shape, .. } = &mut self.spawn_pattern {
+            *shape = Some(glyph_shape);
         }
     }
 
@@ -209,6 +257,23 @@ mod tests {
         assert!(!emitter.particles.is_empty());
     }
 
+    #[test]
+    fn test_emitter_count_scale() {
+        let config = ParticleConfig {
+            count: 10,
+            ..Default::default()
+        };
+
+        let mut emitter = ParticleEmitter::new(config, SpawnPattern::Point { x: 0.0, y: 0.0 }, 42);
+        emitter.count_scale = 0.5;
+        emitter.burst();
+        assert_eq!(emitter.particles.len(), 5);
+
+        // Never drops a non-empty emission to zero
+        emitter.count_scale = 0.01;
+        assert_eq!(emitter.scaled_count(), 1);
+    }
+
     #[test]
     fn test_emitter_lifecycle() {
         let config = ParticleConfig {
@@ -5,20 +5,29 @@
 //!
 //! # Module Structure
 //! - `types` - Core Particle struct and color utilities
+//! - `budget` - Global particle budget, level-of-detail settings and stats
 //! - `physics` - Physics simulation parameters
 //! - `config` - Configuration, spawn patterns, and range values
 //! - `emitter` - ParticleEmitter for spawning/managing particles
+//! - `shape` - Flattened outlines for glyph and custom-shape spawning
 //! - `rng` - Deterministic random number generator
 
+pub mod budget;
 pub mod config;
 pub mod emitter;
 pub mod physics;
 pub mod rng;
+pub mod shape;
 pub mod types;
 
 // Re-exports for convenience
+pub use budget::{ParticleQuality, ParticleStats};
 pub use config::{ParticleConfig, RangeValue, SpawnPattern};
 pub use emitter::ParticleEmitter;
 pub use physics::ParticlePhysics;
 pub use rng::Rng;
-pub use types::{color_to_rgba, parse_hex_color, BlendMode, Particle, ParticleShape};
+pub use shape::{ShapeSampling, SpawnShape};
+pub use types::{
+    color_to_rgba, parse_hex_color, BlendMode, Particle, ParticleLayer, ParticleLayerFilter,
+    ParticleShape,
+};
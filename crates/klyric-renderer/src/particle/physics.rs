@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 
 /// Physics parameters for particle simulation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ParticlePhysics {
     /// Gravity acceleration (pixels/s², positive = down)
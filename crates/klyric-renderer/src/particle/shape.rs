@@ -0,0 +1,629 @@
+//! Spawn shapes - flattened outlines used to place particles on arbitrary shapes
+//!
+//! Shapes are stored in unit coordinates (`0..1` on both axes) and mapped
+//! onto a target box when sampled, so the same shape follows a glyph as it
+//! moves or scales.
+
+use anyhow::{anyhow, bail, Result};
+
+use super::rng::Rng;
+
+/// Number of line segments used to approximate each curve
+const CURVE_SEGMENTS: usize = 8;
+
+/// Maximum rejection-sampling attempts before falling back to the outline
+const FILL_ATTEMPTS: usize = 32;
+
+/// How points are taken from a spawn shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShapeSampling {
+    /// Points along the outline
+    #[default]
+    Outline,
+    /// Points inside the filled area
+    Fill,
+}
+
+/// A flattened shape made of polygonal contours
+#[derive(Debug, Clone)]
+pub struct SpawnShape {
+    /// Contours as point lists (implicitly closed for fill tests)
+    contours: Vec<Vec<(f32, f32)>>,
+    /// Outline segments as [x1, y1, x2, y2]
+    segments: Vec<[f32; 4]>,
+    /// Cumulative outline length at the end of each segment
+    cumulative: Vec<f32>,
+    /// Bounding box (min_x, min_y, max_x, max_y)
+    bbox: (f32, f32, f32, f32),
+    /// Current pen position while building
+    cursor: (f32, f32),
+    /// Start of the current contour while building
+    contour_start: (f32, f32),
+}
+
+impl SpawnShape {
+    pub fn new() -> Self {
+        Self {
+            contours: Vec::new(),
+            segments: Vec::new(),
+            cumulative: Vec::new(),
+            bbox: (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            cursor: (0.0, 0.0),
+            contour_start: (0.0, 0.0),
+        }
+    }
+
+    /// Begin a new contour
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(vec![(x, y)]);
+        self.cursor = (x, y);
+        self.contour_start = (x, y);
+        self.extend_bbox(x, y);
+    }
+
+    /// Straight line from the current point
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        if self.contours.is_empty() {
+            self.move_to(self.cursor.0, self.cursor.1);
+        }
+        let (x0, y0) = self.cursor;
+        self.push_segment(x0, y0, x, y);
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push((x, y));
+        }
+        self.cursor = (x, y);
+        self.extend_bbox(x, y);
+    }
+
+    /// Quadratic Bézier from the current point
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        let (x0, y0) = self.cursor;
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f32 / CURVE_SEGMENTS as f32;
+            let mt = 1.0 - t;
+            let px = mt * mt * x0 + 2.0 * mt * t * cx + t * t * x;
+            let py = mt * mt * y0 + 2.0 * mt * t * cy + t * t * y;
+            self.line_to(px, py);
+        }
+    }
+
+    /// Cubic Bézier from the current point
+    pub fn cubic_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        let (x0, y0) = self.cursor;
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f32 / CURVE_SEGMENTS as f32;
+            let mt = 1.0 - t;
+            let a = mt * mt * mt;
+            let b = 3.0 * mt * mt * t;
+            let c = 3.0 * mt * t * t;
+            let d = t * t * t;
+            self.line_to(
+                a * x0 + b * c1x + c * c2x + d * x,
+                a * y0 + b * c1y + c * c2y + d * y,
+            );
+        }
+    }
+
+    /// Conic (rational quadratic) from the current point with weight `w`
+    pub fn conic_to(&mut self, cx: f32, cy: f32, x: f32, y: f32, w: f32) {
+        let (x0, y0) = self.cursor;
+        for i in 1..=CURVE_SEGMENTS {
+            let t = i as f32 / CURVE_SEGMENTS as f32;
+            let mt = 1.0 - t;
+            let a = mt * mt;
+            let b = 2.0 * w * mt * t;
+            let c = t * t;
+            let den = a + b + c;
+            self.line_to(
+                (a * x0 + b * cx + c * x) / den,
+                (a * y0 + b * cy + c * y) / den,
+            );
+        }
+    }
+
+    /// Elliptical arc from the current point, using SVG arc parameters
+    /// (radii, x-axis rotation in degrees, large-arc and sweep flags)
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc_to(
+        &mut self,
+        rx: f32,
+        ry: f32,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        x: f32,
+        y: f32,
+    ) {
+        let (x0, y0) = self.cursor;
+        if (x0, y0) == (x, y) {
+            return;
+        }
+        let mut rx = rx.abs();
+        let mut ry = ry.abs();
+        if rx <= f32::EPSILON || ry <= f32::EPSILON {
+            self.line_to(x, y);
+            return;
+        }
+
+        // Endpoint to center parameterization (SVG 1.1, appendix F.6.5)
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let dx = (x0 - x) / 2.0;
+        let dy = (y0 - y) / 2.0;
+        let x1 = cos * dx + sin * dy;
+        let y1 = -sin * dx + cos * dy;
+
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            let s = lambda.sqrt();
+            rx *= s;
+            ry *= s;
+        }
+
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = if den > 0.0 {
+            (num / den).max(0.0).sqrt()
+        } else {
+            0.0
+        };
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let cxp = coef * rx * y1 / ry;
+        let cyp = -coef * ry * x1 / rx;
+        let cx = cos * cxp - sin * cyp + (x0 + x) / 2.0;
+        let cy = sin * cxp + cos * cyp + (y0 + y) / 2.0;
+
+        let angle =
+            |ux: f32, uy: f32, vx: f32, vy: f32| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+        let ux = (x1 - cxp) / rx;
+        let uy = (y1 - cyp) / ry;
+        let vx = (-x1 - cxp) / rx;
+        let vy = (-y1 - cyp) / ry;
+        let start = angle(1.0, 0.0, ux, uy);
+        let mut delta = angle(ux, uy, vx, vy);
+        if !sweep && delta > 0.0 {
+            delta -= std::f32::consts::TAU;
+        } else if sweep && delta < 0.0 {
+            delta += std::f32::consts::TAU;
+        }
+
+        // Same density as the Bézier flattening: one curve per quarter turn
+        let quarters = (delta.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+        let steps = quarters * CURVE_SEGMENTS;
+        for i in 1..steps {
+            let theta = start + delta * i as f32 / steps as f32;
+            let ex = rx * theta.cos();
+            let ey = ry * theta.sin();
+            self.line_to(cos * ex - sin * ey + cx, sin * ex + cos * ey + cy);
+        }
+        self.line_to(x, y);
+    }
+
+    /// Close the current contour back to its starting point
+    pub fn close(&mut self) {
+        let (sx, sy) = self.contour_start;
+        if self.cursor != (sx, sy) {
+            let (x0, y0) = self.cursor;
+            self.push_segment(x0, y0, sx, sy);
+        }
+        self.cursor = (sx, sy);
+    }
+
+    /// Whether the shape has any outline to sample
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Total outline length (in shape units)
+    pub fn length(&self) -> f32 {
+        self.cumulative.last().copied().unwrap_or(0.0)
+    }
+
+    /// Rescale the shape so its bounding box spans the unit square
+    pub fn normalize(&mut self) {
+        let (min_x, min_y, max_x, max_y) = self.bbox;
+        let w = (max_x - min_x).max(f32::EPSILON);
+        let h = (max_y - min_y).max(f32::EPSILON);
+        self.transform(|x, y| ((x - min_x) / w, (y - min_y) / h));
+    }
+
+    /// Apply a point mapping to every coordinate of the shape
+    pub fn transform(&mut self, f: impl Fn(f32, f32) -> (f32, f32)) {
+        for contour in &mut self.contours {
+            for p in contour.iter_mut() {
+                *p = f(p.0, p.1);
+            }
+        }
+        let segments = std::mem::take(&mut self.segments);
+        self.cumulative.clear();
+        self.bbox = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for s in segments {
+            let (x1, y1) = f(s[0], s[1]);
+            let (x2, y2) = f(s[2], s[3]);
+            self.push_segment(x1, y1, x2, y2);
+            self.extend_bbox(x1, y1);
+            self.extend_bbox(x2, y2);
+        }
+    }
+
+    /// Sample a point in shape coordinates
+    pub fn sample(&self, sampling: ShapeSampling, rng: &mut Rng) -> (f32, f32) {
+        match sampling {
+            ShapeSampling::Outline => self.sample_outline(rng),
+            ShapeSampling::Fill => self.sample_fill(rng),
+        }
+    }
+
+    fn sample_outline(&self, rng: &mut Rng) -> (f32, f32) {
+        let total = self.length();
+        if total <= 0.0 {
+            return self
+                .segments
+                .first()
+                .map(|s| (s[0], s[1]))
+                .unwrap_or_default();
+        }
+
+        let target = rng.next_f32() * total;
+        let idx = self
+            .cumulative
+            .partition_point(|&c| c < target)
+            .min(self.segments.len() - 1);
+        let start = if idx == 0 {
+            0.0
+        } else {
+            self.cumulative[idx - 1]
+        };
+        let seg_len = self.cumulative[idx] - start;
+        let t = if seg_len > 0.0 {
+            (target - start) / seg_len
+        } else {
+            0.0
+        };
+        let s = &self.segments[idx];
+        (s[0] + (s[2] - s[0]) * t, s[1] + (s[3] - s[1]) * t)
+    }
+
+    fn sample_fill(&self, rng: &mut Rng) -> (f32, f32) {
+        let (min_x, min_y, max_x, max_y) = self.bbox;
+        if min_x > max_x || min_y > max_y {
+            return (0.0, 0.0);
+        }
+        for _ in 0..FILL_ATTEMPTS {
+            let x = rng.range(min_x, max_x);
+            let y = rng.range(min_y, max_y);
+            if self.contains(x, y) {
+                return (x, y);
+            }
+        }
+        // Very thin shapes: the outline is the best approximation
+        self.sample_outline(rng)
+    }
+
+    /// Point-in-shape test using the non-zero winding rule
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        let mut winding = 0i32;
+        for contour in &self.contours {
+            let n = contour.len();
+            if n < 3 {
+                continue;
+            }
+            for i in 0..n {
+                let (x1, y1) = contour[i];
+                let (x2, y2) = contour[(i + 1) % n];
+                if y1 <= y {
+                    if y2 > y && cross(x1, y1, x2, y2, x, y) > 0.0 {
+                        winding += 1;
+                    }
+                } else if y2 <= y && cross(x1, y1, x2, y2, x, y) < 0.0 {
+                    winding -= 1;
+                }
+            }
+        }
+        winding != 0
+    }
+
+    /// Parse SVG path data (`M`, `L`, `H`, `V`, `C`, `S`, `Q`, `T`, `Z`,
+    /// `A`, absolute and relative).
+    pub fn from_svg_path(d: &str) -> Result<Self> {
+        let mut shape = SpawnShape::new();
+        let tokens = tokenize_svg_path(d)?;
+        let mut i = 0;
+        let mut cmd = ' ';
+        // Last control point for smooth curves
+        let mut last_ctrl: Option<(f32, f32)> = None;
+        let mut last_cmd = ' ';
+
+        while i < tokens.len() {
+            if let SvgToken::Command(c) = tokens[i] {
+                cmd = c;
+                i += 1;
+                if cmd == 'Z' || cmd == 'z' {
+                    shape.close();
+                    last_ctrl = None;
+                    last_cmd = cmd;
+                    continue;
+                }
+            } else if cmd == ' ' {
+                bail!("SVG path must start with a command");
+            }
+
+            let relative = cmd.is_ascii_lowercase();
+            let (cx, cy) = shape.cursor;
+            let abs = |x: f32, y: f32| if relative { (cx + x, cy + y) } else { (x, y) };
+            let arity = match cmd.to_ascii_uppercase() {
+                'M' | 'L' | 'T' => 2,
+                'H' | 'V' => 1,
+                'S' | 'Q' => 4,
+                'C' => 6,
+                'A' => 7,
+                _ => bail!("Unsupported SVG path command '{}'", cmd),
+            };
+            let args = svg_numbers(&tokens, i, arity)?;
+            i += arity;
+
+            match cmd.to_ascii_uppercase() {
+                'M' => {
+                    let (x, y) = abs(args[0], args[1]);
+                    shape.move_to(x, y);
+                    // Subsequent pairs are implicit line-tos
+                    cmd = if relative { 'l' } else { 'L' };
+                    last_ctrl = None;
+                }
+                'L' => {
+                    let (x, y) = abs(args[0], args[1]);
+                    shape.line_to(x, y);
+                    last_ctrl = None;
+                }
+                'H' => {
+                    let x = if relative { cx + args[0] } else { args[0] };
+                    shape.line_to(x, cy);
+                    last_ctrl = None;
+                }
+                'V' => {
+                    let y = if relative { cy + args[0] } else { args[0] };
+                    shape.line_to(cx, y);
+                    last_ctrl = None;
+                }
+                'C' => {
+                    let (c1x, c1y) = abs(args[0], args[1]);
+                    let (c2x, c2y) = abs(args[2], args[3]);
+                    let (x, y) = abs(args[4], args[5]);
+                    shape.cubic_to(c1x, c1y, c2x, c2y, x, y);
+                    last_ctrl = Some((c2x, c2y));
+                }
+                'S' => {
+                    let (c1x, c1y) = match (last_ctrl, last_cmd.to_ascii_uppercase()) {
+                        (Some((lx, ly)), 'C' | 'S') => (2.0 * cx - lx, 2.0 * cy - ly),
+                        _ => (cx, cy),
+                    };
+                    let (c2x, c2y) = abs(args[0], args[1]);
+                    let (x, y) = abs(args[2], args[3]);
+                    shape.cubic_to(c1x, c1y, c2x, c2y, x, y);
+                    last_ctrl = Some((c2x, c2y));
+                }
+                'Q' => {
+                    let (qx, qy) = abs(args[0], args[1]);
+                    let (x, y) = abs(args[2], args[3]);
+                    shape.quad_to(qx, qy, x, y);
+                    last_ctrl = Some((qx, qy));
+                }
+                'T' => {
+                    let (qx, qy) = match (last_ctrl, last_cmd.to_ascii_uppercase()) {
+                        (Some((lx, ly)), 'Q' | 'T') => (2.0 * cx - lx, 2.0 * cy - ly),
+                        _ => (cx, cy),
+                    };
+                    let (x, y) = abs(args[0], args[1]);
+                    shape.quad_to(qx, qy, x, y);
+                    last_ctrl = Some((qx, qy));
+                }
+                'A' => {
+                    let (x, y) = abs(args[5], args[6]);
+                    shape.arc_to(
+                        args[0],
+                        args[1],
+                        args[2],
+                        args[3] != 0.0,
+                        args[4] != 0.0,
+                        x,
+                        y,
+                    );
+                    last_ctrl = None;
+                }
+                _ => unreachable!(),
+            }
+            last_cmd = cmd;
+        }
+
+        if shape.is_empty() {
+            return Err(anyhow!("SVG path has no drawable segments"));
+        }
+        Ok(shape)
+    }
+
+    fn push_segment(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        let len = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+        let total = self.length() + len;
+        self.segments.push([x1, y1, x2, y2]);
+        self.cumulative.push(total);
+    }
+
+    fn extend_bbox(&mut self, x: f32, y: f32) {
+        self.bbox.0 = self.bbox.0.min(x);
+        self.bbox.1 = self.bbox.1.min(y);
+        self.bbox.2 = self.bbox.2.max(x);
+        self.bbox.3 = self.bbox.3.max(y);
+    }
+}
+
+impl Default for SpawnShape {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn cross(x1: f32, y1: f32, x2: f32, y2: f32, px: f32, py: f32) -> f32 {
+    (x2 - x1) * (py - y1) - (px - x1) * (y2 - y1)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SvgToken {
+    Command(char),
+    Number(f32),
+}
+
+fn tokenize_svg_path(d: &str) -> Result<Vec<SvgToken>> {
+    let mut tokens = Vec::new();
+    let bytes = d.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            tokens.push(SvgToken::Command(c));
+            i += 1;
+        } else if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+            let start = i;
+            let mut seen_dot = false;
+            let mut seen_exp = false;
+            i += 1;
+            if c == '.' {
+                seen_dot = true;
+            }
+            while i < bytes.len() {
+                let n = bytes[i] as char;
+                if n.is_ascii_digit() {
+                    i += 1;
+                } else if n == '.' && !seen_dot && !seen_exp {
+                    seen_dot = true;
+                    i += 1;
+                } else if (n == 'e' || n == 'E') && !seen_exp {
+                    seen_exp = true;
+                    i += 1;
+                    if i < bytes.len() && (bytes[i] == b'-' || bytes[i] == b'+') {
+                        i += 1;
+                    }
+                } else {
+                    break;
+                }
+            }
+            let value: f32 = d[start..i]
+                .parse()
+                .map_err(|_| anyhow!("Invalid number '{}' in SVG path", &d[start..i]))?;
+            tokens.push(SvgToken::Number(value));
+        } else if c.is_whitespace() || c == ',' {
+            i += 1;
+        } else {
+            bail!("Unexpected character '{}' in SVG path", c);
+        }
+    }
+    Ok(tokens)
+}
+
+fn svg_numbers(tokens: &[SvgToken], start: usize, count: usize) -> Result<Vec<f32>> {
+    (start..start + count)
+        .map(|idx| match tokens.get(idx) {
+            Some(SvgToken::Number(v)) => Ok(*v),
+            _ => Err(anyhow!("SVG path command is missing arguments")),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> SpawnShape {
+        SpawnShape::from_svg_path("M0 0 H10 V10 H0 Z").unwrap()
+    }
+
+    #[test]
+    fn test_svg_square_outline() {
+        let shape = square();
+        assert!((shape.length() - 40.0).abs() < 1e-4);
+
+        let mut rng = Rng::new(7);
+        for _ in 0..50 {
+            let (x, y) = shape.sample(ShapeSampling::Outline, &mut rng);
+            let on_edge = x.abs() < 1e-3
+                || (x - 10.0).abs() < 1e-3
+                || y.abs() < 1e-3
+                || (y - 10.0).abs() < 1e-3;
+            assert!(on_edge, "({}, {}) is not on the outline", x, y);
+        }
+    }
+
+    #[test]
+    fn test_fill_respects_holes() {
+        // Outer square clockwise, inner square counter-clockwise (a hole)
+        let shape =
+            SpawnShape::from_svg_path("M0 0 L10 0 L10 10 L0 10 Z M3 3 L3 7 L7 7 L7 3 Z").unwrap();
+        assert!(shape.contains(1.0, 1.0));
+        assert!(!shape.contains(5.0, 5.0));
+
+        let mut rng = Rng::new(3);
+        for _ in 0..50 {
+            let (x, y) = shape.sample(ShapeSampling::Fill, &mut rng);
+            let in_hole = x > 3.01 && x < 6.99 && y > 3.01 && y < 6.99;
+            assert!(!in_hole, "({}, {}) is inside the hole", x, y);
+        }
+    }
+
+    #[test]
+    fn test_relative_commands_and_curves() {
+        let shape = SpawnShape::from_svg_path("m1,1 l2,0 q1,1 0,2 c-1,0 -2,-1 -2,-2 z").unwrap();
+        assert!(!shape.is_empty());
+        assert!(shape.contains(2.0, 2.0));
+    }
+
+    #[test]
+    fn test_arc_follows_circle() {
+        // Two half-circle arcs of radius 5 around (5, 0)
+        let shape = SpawnShape::from_svg_path("M0 0 A5 5 0 0 1 10 0 A5 5 0 0 1 0 0 Z").unwrap();
+        assert!(shape.contains(5.0, 0.0));
+        assert!(shape.contains(5.0, 4.5));
+        assert!(shape.contains(5.0, -4.5));
+        assert!(!shape.contains(9.0, 4.0));
+
+        let mut rng = Rng::new(11);
+        for _ in 0..50 {
+            let (x, y) = shape.sample(ShapeSampling::Outline, &mut rng);
+            let r = ((x - 5.0).powi(2) + y.powi(2)).sqrt();
+            assert!((r - 5.0).abs() < 0.1, "({}, {}) is off the circle", x, y);
+        }
+    }
+
+    #[test]
+    fn test_conic_quarter_circle() {
+        let mut shape = SpawnShape::new();
+        shape.move_to(1.0, 0.0);
+        shape.conic_to(1.0, 1.0, 0.0, 1.0, std::f32::consts::FRAC_1_SQRT_2);
+
+        let mut rng = Rng::new(5);
+        for _ in 0..50 {
+            let (x, y) = shape.sample(ShapeSampling::Outline, &mut rng);
+            let r = (x * x + y * y).sqrt();
+            assert!((r - 1.0).abs() < 0.01, "({}, {}) is off the circle", x, y);
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut shape = SpawnShape::from_svg_path("M10 20 L30 20 L30 60 Z").unwrap();
+        shape.normalize();
+        let mut rng = Rng::new(1);
+        for _ in 0..20 {
+            let (x, y) = shape.sample(ShapeSampling::Outline, &mut rng);
+            assert!((-1e-4..=1.0001).contains(&x));
+            assert!((-1e-4..=1.0001).contains(&y));
+        }
+    }
+
+    #[test]
+    fn test_invalid_svg_path() {
+        assert!(SpawnShape::from_svg_path("").is_err());
+        assert!(SpawnShape::from_svg_path("10 10").is_err());
+        assert!(SpawnShape::from_svg_path("M0 0 L").is_err());
+    }
+}
@@ -83,7 +83,7 @@ impl Particle {
 }
 
 /// Shape of a particle for rendering
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, schemars::JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ParticleShape {
     #[default]
@@ -96,13 +96,59 @@ pub enum ParticleShape {
 }
 
 /// Blend mode for particle rendering
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, schemars::JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum BlendMode {
     #[default]
     Normal,
     Additive,
     Multiply,
+    Screen,
+    Overlay,
+    SoftLight,
+}
+
+/// Where particles are composited relative to the text
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ParticleLayer {
+    /// Drawn behind the text (but above the background)
+    Behind,
+    /// Drawn on top of the text
+    #[default]
+    Front,
+    /// Drawn on top of the text, clipped to the text's coverage
+    Masked,
+}
+
+/// Blur/bloom pass applied to an emitter's particles as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticleLayerFilter {
+    /// Gaussian blur sigma in pixels
+    #[serde(default = "default_filter_blur")]
+    pub blur: f32,
+    /// Bloom strength (0.0 - 1.0). At 0 the particles themselves are blurred;
+    /// above 0 they stay sharp and a blurred glow is added on top.
+    #[serde(default)]
+    pub bloom: f32,
+}
+
+fn default_filter_blur() -> f32 {
+    6.0
+}
+
+impl Default for ParticleLayerFilter {
+    fn default() -> Self {
+        Self {
+            blur: default_filter_blur(),
+            bloom: 0.0,
+        }
+    }
 }
 
 /// Extract RGBA components from u32 color
@@ -140,4 +186,16 @@ mod tests {
         let (r, g, b, a) = color_to_rgba(0xFF00FF80);
         assert_eq!((r, g, b, a), (255, 0, 255, 128));
     }
+
+    #[test]
+    fn test_layer_and_blend_serde() {
+        let mode: BlendMode = serde_json::from_str("\"softlight\"").unwrap();
+        assert_eq!(mode, BlendMode::SoftLight);
+        let layer: ParticleLayer = serde_json::from_str("\"masked\"").unwrap();
+        assert_eq!(layer, ParticleLayer::Masked);
+
+        let filter: ParticleLayerFilter = serde_json::from_str(r#"{"bloom": 0.5}"#).unwrap();
+        assert_eq!(filter.blur, 6.0);
+        assert_eq!(filter.bloom, 0.5);
+    }
 }
@@ -0,0 +1,210 @@
+//! Data-driven particle preset definitions
+//!
+//! A `PresetDefinition` describes a particle preset as plain data: the
+//! emitter configuration plus a spawn pattern expressed relative to the
+//! character bounds. Definitions can be embedded in a document
+//! (`particlePresets`) or loaded from JSON preset packs on disk.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::traits::ParticlePreset;
+use super::types::CharBounds;
+use crate::particle::{ParticleConfig, ParticleEmitter, ShapeSampling, SpawnPattern, SpawnShape};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Spawn pattern relative to the character bounds.
+///
+/// Coordinates are normalized: `(0, 0)` is the top-left corner of the
+/// character and `(1, 1)` the bottom-right.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SpawnSpec {
+    /// Single point at the character center
+    #[default]
+    Center,
+    /// Random points inside the character box
+    Fill,
+    /// Horizontal line above the character, `offset` pixels above the top edge
+    Above {
+        #[serde(default)]
+        offset: f32,
+    },
+    /// Single point at a normalized position
+    Point { x: f32, y: f32 },
+    /// Line between two normalized positions
+    Line { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// Rectangle in normalized coordinates
+    Rect { x: f32, y: f32, w: f32, h: f32 },
+    /// Along the glyph outline
+    GlyphOutline,
+    /// Inside the filled glyph shape
+    GlyphFill,
+    /// Filled circle around the character center (radius in pixels)
+    Circle { radius: f32 },
+    /// Ring around the character center (radii in pixels)
+    Ring { inner: f32, outer: f32 },
+    /// SVG path data in normalized coordinates
+    SvgPath {
+        d: String,
+        /// Sample inside the shape instead of along its outline
+        #[serde(default)]
+        fill: bool,
+    },
+}
+
+impl SpawnSpec {
+    /// Resolve this spec into an absolute spawn pattern for the given bounds
+    pub fn to_pattern(&self, bounds: &CharBounds) -> SpawnPattern {
+        let px = |nx: f32| bounds.x + nx * bounds.width;
+        let py = |ny: f32| bounds.y + ny * bounds.height;
+
+        match self {
+            SpawnSpec::Center => bounds.spawn_center(),
+            SpawnSpec::Fill => bounds.spawn_fill(),
+            SpawnSpec::Above { offset } => bounds.spawn_above(*offset),
+            SpawnSpec::Point { x, y } => SpawnPattern::Point {
+                x: px(*x),
+                y: py(*y),
+            },
+            SpawnSpec::Line { x1, y1, x2, y2 } => SpawnPattern::Line {
+                x1: px(*x1),
+                y1: py(*y1),
+                x2: px(*x2),
+                y2: py(*y2),
+            },
+            SpawnSpec::Rect { x, y, w, h } => SpawnPattern::Rect {
+                x: px(*x),
+                y: py(*y),
+                w: w * bounds.width,
+                h: h * bounds.height,
+            },
+            SpawnSpec::GlyphOutline => bounds.spawn_glyph_outline(),
+            SpawnSpec::GlyphFill => bounds.spawn_glyph_fill(),
+            SpawnSpec::Circle { radius } => SpawnPattern::Circle {
+                cx: px(0.5),
+                cy: py(0.5),
+                radius: *radius,
+            },
+            SpawnSpec::Ring { inner, outer } => bounds.spawn_ring(*inner, *outer),
+            SpawnSpec::SvgPath { d, fill } => match svg_spawn_shape(d) {
+                Ok(shape) => SpawnPattern::Shape {
+                    shape,
+                    sampling: if *fill {
+                        ShapeSampling::Fill
+                    } else {
+                        ShapeSampling::Outline
+                    },
+                    x: bounds.x,
+                    y: bounds.y,
+                    w: bounds.width,
+                    h: bounds.height,
+                },
+                Err(e) => {
+                    log::warn!("Invalid SVG spawn path '{}': {}", d, e);
+                    bounds.spawn_fill()
+                }
+            },
+        }
+    }
+}
+
+/// Parsed SVG spawn shapes keyed by path data.
+///
+/// Emitters are created per character, so each distinct path is parsed once
+/// and the flattened shape shared between them.
+fn svg_spawn_shape(d: &str) -> Result<Arc<SpawnShape>, String> {
+    type ShapeCache = HashMap<String, Result<Arc<SpawnShape>, String>>;
+    static CACHE: OnceLock<Mutex<ShapeCache>> = OnceLock::new();
+
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    cache
+        .entry(d.to_string())
+        .or_insert_with(|| {
+            SpawnShape::from_svg_path(d)
+                .map(Arc::new)
+                .map_err(|e| e.to_string())
+        })
+        .clone()
+}
+
+/// A named particle preset described entirely in data
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetDefinition {
+    /// Emitter configuration
+    pub config: ParticleConfig,
+
+    /// Where particles spawn relative to the character
+    #[serde(default)]
+    pub spawn: SpawnSpec,
+}
+
+impl PresetDefinition {
+    /// Parse a single preset definition from JSON
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize to JSON
+    pub fn to_json(&self, pretty: bool) -> Result<String, serde_json::Error> {
+        if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        }
+    }
+}
+
+impl ParticlePreset for PresetDefinition {
+    fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
+        ParticleEmitter::new(self.config.clone(), self.spawn.to_pattern(bounds), seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(self.clone())
+    }
+}
+
+/// Parse a preset pack: a JSON object mapping preset names to definitions
+pub fn parse_preset_pack(json: &str) -> Result<HashMap<String, PresetDefinition>> {
+    serde_json::from_str(json).context("Invalid particle preset pack")
+}
+
+/// Load every `*.json` preset pack in a directory.
+///
+/// Files are read in name order so later files deterministically override
+/// earlier ones. A missing directory yields an empty set.
+pub fn load_preset_directory(dir: &Path) -> Result<HashMap<String, PresetDefinition>> {
+    let mut presets = HashMap::new();
+    if !dir.is_dir() {
+        return Ok(presets);
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read preset directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .map(|e| e.eq_ignore_ascii_case("json"))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+
+    for path in files {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read preset file {}", path.display()))?;
+        let pack = parse_preset_pack(&content)
+            .with_context(|| format!("Failed to parse preset file {}", path.display()))?;
+        presets.extend(pack);
+    }
+
+    Ok(presets)
+}
@@ -1,14 +1,20 @@
+use super::definition::{load_preset_directory, PresetDefinition};
 use super::particles::*;
 use super::traits::ParticlePreset;
 use super::types::{CharBounds, EffectPreset};
 use crate::particle::ParticleEmitter;
+use anyhow::Result;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Factory for creating preset particle effects
 ///
 /// Maintains a registry of available presets allowing for extension.
+/// Document-scoped presets shadow registered ones of the same name.
 pub struct PresetFactory {
     registry: HashMap<String, Box<dyn ParticlePreset>>,
+    /// Presets defined by the currently loaded document
+    document_presets: HashMap<String, PresetDefinition>,
 }
 
 impl PresetFactory {
@@ -16,6 +22,7 @@ impl PresetFactory {
     pub fn new() -> Self {
         let mut factory = Self {
             registry: HashMap::new(),
+            document_presets: HashMap::new(),
         };
 
         // Register default presets
@@ -36,6 +43,58 @@ impl PresetFactory {
         self.registry.insert(name.to_lowercase(), preset);
     }
 
+    /// Register a data-driven preset definition
+    pub fn register_definition(&mut self, name: &str, definition: PresetDefinition) {
+        self.register(name, Box::new(definition));
+    }
+
+    /// Register a set of data-driven preset definitions
+    pub fn register_definitions(&mut self, definitions: &HashMap<String, PresetDefinition>) {
+        for (name, definition) in definitions {
+            self.register_definition(name, definition.clone());
+        }
+    }
+
+    /// Load all preset packs (`*.json`) from a directory into the registry.
+    ///
+    /// Returns the number of presets registered.
+    pub fn load_directory(&mut self, dir: &Path) -> Result<usize> {
+        let definitions = load_preset_directory(dir)?;
+        self.register_definitions(&definitions);
+        Ok(definitions.len())
+    }
+
+    /// Replace the document-scoped presets
+    pub fn set_document_presets(&mut self, definitions: &HashMap<String, PresetDefinition>) {
+        self.document_presets.clear();
+        for (name, definition) in definitions {
+            self.document_presets
+                .insert(name.to_lowercase(), definition.clone());
+        }
+    }
+
+    /// Get the data definition of a preset, if it has one
+    pub fn definition(&self, name: &str) -> Option<PresetDefinition> {
+        let key = name.to_lowercase();
+        self.document_presets
+            .get(&key)
+            .cloned()
+            .or_else(|| self.registry.get(&key).and_then(|p| p.definition()))
+    }
+
+    /// Names of all available presets (registered and document-scoped), sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .registry
+            .keys()
+            .chain(self.document_presets.keys())
+            .cloned()
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
     /// Create a particle emitter for the given preset enum (legacy support)
     pub fn create_from_enum(
         &self,
@@ -62,8 +121,12 @@ impl PresetFactory {
 
     /// Create a particle emitter by name
     pub fn create(&self, name: &str, bounds: &CharBounds, seed: u64) -> Option<ParticleEmitter> {
+        let key = name.to_lowercase();
+        if let Some(definition) = self.document_presets.get(&key) {
+            return Some(definition.create_emitter(bounds, seed));
+        }
         self.registry
-            .get(&name.to_lowercase())
+            .get(&key)
             .map(|p| p.create_emitter(bounds, seed))
     }
 }
@@ -1,9 +1,11 @@
+pub mod definition;
 pub mod factory;
 pub mod particles;
 pub mod traits;
 pub mod transitions;
 pub mod types;
 
+pub use definition::{PresetDefinition, SpawnSpec};
 pub use factory::PresetFactory;
 pub use traits::ParticlePreset;
 pub use types::{CharBounds, EffectPreset};
@@ -56,4 +58,101 @@ mod tests {
         disintegrate.burst();
         assert_eq!(disintegrate.particles.len(), 30);
     }
+
+    #[test]
+    fn test_builtin_definitions_round_trip() {
+        let factory = PresetFactory::new();
+        for name in [
+            "rain",
+            "sparkle",
+            "hearts",
+            "confetti",
+            "disintegrate",
+            "fire",
+            "glow",
+        ] {
+            let def = factory
+                .definition(name)
+                .expect("built-in should have a definition");
+            let json = def.to_json(false).unwrap();
+            let parsed = PresetDefinition::from_json(&json).unwrap();
+            assert_eq!(parsed.spawn, def.spawn);
+            assert_eq!(parsed.config.count, def.config.count);
+            assert_eq!(parsed.config.color, def.config.color);
+        }
+    }
+
+    #[test]
+    fn test_json_preset_registration() {
+        let json = r##"{
+            "snow": {
+                "config": { "count": 7, "spawnRate": 0, "color": "#FFFFFF" },
+                "spawn": { "type": "line", "x1": 0, "y1": 0, "x2": 1, "y2": 0 }
+            }
+        }"##;
+        let pack = definition::parse_preset_pack(json).unwrap();
+
+        let mut factory = PresetFactory::new();
+        factory.register_definitions(&pack);
+
+        let bounds = CharBounds {
+            x: 10.0,
+            y: 20.0,
+            width: 30.0,
+            height: 40.0,
+        };
+        let mut emitter = factory.create("Snow", &bounds, 1).unwrap();
+        emitter.burst();
+        assert_eq!(emitter.particles.len(), 7);
+        for p in &emitter.particles {
+            assert!(p.x >= 10.0 && p.x <= 40.0);
+            assert_eq!(p.y, 20.0);
+        }
+    }
+
+    #[test]
+    fn test_document_presets_shadow_registry() {
+        let mut factory = PresetFactory::new();
+        let mut doc_presets = std::collections::HashMap::new();
+        let mut def = factory.definition("sparkle").unwrap();
+        def.config.count = 3;
+        doc_presets.insert("sparkle".to_string(), def);
+        factory.set_document_presets(&doc_presets);
+
+        let bounds = CharBounds {
+            x: 0.0,
+            y: 0.0,
+            width: 10.0,
+            height: 10.0,
+        };
+        let mut emitter = factory.create("sparkle", &bounds, 1).unwrap();
+        emitter.burst();
+        assert_eq!(emitter.particles.len(), 3);
+
+        factory.set_document_presets(&std::collections::HashMap::new());
+        let mut emitter = factory.create("sparkle", &bounds, 1).unwrap();
+        emitter.burst();
+        assert_eq!(emitter.particles.len(), 12);
+    }
+
+    #[test]
+    fn test_svg_spawn_shape_parsed_once() {
+        let spec = SpawnSpec::SvgPath {
+            d: "M0 0.5 A0.5 0.5 0 0 1 1 0.5 Z".to_string(),
+            fill: false,
+        };
+        let bounds = CharBounds {
+            x: 0.0,
+            y: 0.0,
+            width: 10.0,
+            height: 10.0,
+        };
+        let shape = |pattern: crate::particle::SpawnPattern| match pattern {
+            crate::particle::SpawnPattern::Shape { shape, .. } => shape,
+            other => panic!("expected a shape pattern, got {:?}", other),
+        };
+        let a = shape(spec.to_pattern(&bounds));
+        let b = shape(spec.to_pattern(&bounds));
+        assert!(std::sync::Arc::ptr_eq(&a, &b));
+    }
 }
@@ -1,14 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticlePhysics, ParticleShape,
+    RangeValue,
 };
 
 /// 🎊 Confetti effect - colorful explosion
 pub struct ConfettiPreset;
 impl ParticlePreset for ConfettiPreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the confetti preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 20,
             spawn_rate: 0.0,
             lifetime: RangeValue::Range(1.0, 2.5),
@@ -27,7 +40,10 @@ impl ParticlePreset for ConfettiPreset {
                 drag: 1.0,
             },
             blend_mode: BlendMode::Normal,
-        };
-        ParticleEmitter::new(config, bounds.spawn_center(), seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
+        },
+        spawn: SpawnSpec::Center,
     }
 }
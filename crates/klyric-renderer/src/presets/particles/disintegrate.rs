@@ -1,14 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticlePhysics, ParticleShape,
+    RangeValue,
 };
 
 /// 💥 Disintegrate effect - character explodes into particles
 pub struct DisintegratePreset;
 impl ParticlePreset for DisintegratePreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the disintegrate preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 30,
             spawn_rate: 0.0,
             lifetime: RangeValue::Range(0.5, 1.2),
@@ -27,7 +40,10 @@ impl ParticlePreset for DisintegratePreset {
                 drag: 3.0,
             },
             blend_mode: BlendMode::Normal,
-        };
-        ParticleEmitter::new(config, bounds.spawn_fill(), seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
+        },
+        spawn: SpawnSpec::GlyphFill,
     }
 }
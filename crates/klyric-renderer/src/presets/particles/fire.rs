@@ -1,15 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
-    SpawnPattern,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticleLayerFilter,
+    ParticlePhysics, ParticleShape, RangeValue,
 };
 
 /// 🔥 Fire effect - flames rising
 pub struct FirePreset;
 impl ParticlePreset for FirePreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the fire preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 5,
             spawn_rate: 15.0,
             lifetime: RangeValue::Range(0.3, 0.8),
@@ -28,15 +40,18 @@ impl ParticlePreset for FirePreset {
                 drag: 2.0,
             },
             blend_mode: BlendMode::Additive,
-        };
-
-        let spawn = SpawnPattern::Line {
-            x1: bounds.x,
-            y1: bounds.y + bounds.height,
-            x2: bounds.x + bounds.width,
-            y2: bounds.y + bounds.height,
-        };
-
-        ParticleEmitter::new(config, spawn, seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: Some(ParticleLayerFilter {
+                blur: 8.0,
+                bloom: 0.6,
+            }),
+        },
+        spawn: SpawnSpec::Line {
+            x1: 0.0,
+            y1: 1.0,
+            x2: 1.0,
+            y2: 1.0,
+        },
     }
 }
@@ -1,14 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticlePhysics, ParticleShape,
+    RangeValue,
 };
 
 /// ✨ Glow pulse - subtle pulsing aura
 pub struct GlowPulsePreset;
 impl ParticlePreset for GlowPulsePreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the glow preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 3,
             spawn_rate: 5.0,
             lifetime: RangeValue::Range(0.4, 0.8),
@@ -27,7 +40,10 @@ impl ParticlePreset for GlowPulsePreset {
                 drag: 0.0,
             },
             blend_mode: BlendMode::Additive,
-        };
-        ParticleEmitter::new(config, bounds.spawn_center(), seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
+        },
+        spawn: SpawnSpec::Center,
     }
 }
@@ -1,14 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticlePhysics, ParticleShape,
+    RangeValue,
 };
 
 /// 💕 Hearts effect - floating hearts
 pub struct HeartsPreset;
 impl ParticlePreset for HeartsPreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the hearts preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 5,
             spawn_rate: 2.0,
             lifetime: RangeValue::Range(1.0, 2.0),
@@ -27,7 +40,10 @@ impl ParticlePreset for HeartsPreset {
                 drag: 0.5,
             },
             blend_mode: BlendMode::Normal,
-        };
-        ParticleEmitter::new(config, bounds.spawn_center(), seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
+        },
+        spawn: SpawnSpec::Center,
     }
 }
@@ -1,14 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticlePhysics, ParticleShape,
+    RangeValue,
 };
 
 /// 🌧️ Rain effect - droplets falling from above
 pub struct RainPreset;
 impl ParticlePreset for RainPreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the rain preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 3,
             spawn_rate: 8.0,
             lifetime: RangeValue::Range(0.8, 1.5),
@@ -27,7 +40,10 @@ impl ParticlePreset for RainPreset {
                 drag: 0.0,
             },
             blend_mode: BlendMode::Normal,
-        };
-        ParticleEmitter::new(config, bounds.spawn_above(50.0), seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
+        },
+        spawn: SpawnSpec::Above { offset: 50.0 },
     }
 }
@@ -1,14 +1,27 @@
+use super::super::definition::{PresetDefinition, SpawnSpec};
 use super::super::traits::ParticlePreset;
 use super::super::types::CharBounds;
 use crate::particle::{
-    BlendMode, ParticleConfig, ParticleEmitter, ParticlePhysics, ParticleShape, RangeValue,
+    BlendMode, ParticleConfig, ParticleEmitter, ParticleLayer, ParticleLayerFilter,
+    ParticlePhysics, ParticleShape, RangeValue,
 };
 
-/// ✨ Sparkle effect - glitter burst at center
+/// ✨ Sparkle effect - glitter burst along the glyph outline
 pub struct SparklePreset;
 impl ParticlePreset for SparklePreset {
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter {
-        let config = ParticleConfig {
+        definition().create_emitter(bounds, seed)
+    }
+
+    fn definition(&self) -> Option<PresetDefinition> {
+        Some(definition())
+    }
+}
+
+/// Data definition of the sparkle preset
+pub fn definition() -> PresetDefinition {
+    PresetDefinition {
+        config: ParticleConfig {
             count: 12,
             spawn_rate: 0.0,
             lifetime: RangeValue::Range(0.3, 0.8),
@@ -27,7 +40,13 @@ impl ParticlePreset for SparklePreset {
                 drag: 2.0,
             },
             blend_mode: BlendMode::Additive,
-        };
-        ParticleEmitter::new(config, bounds.spawn_center(), seed)
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: Some(ParticleLayerFilter {
+                blur: 4.0,
+                bloom: 0.8,
+            }),
+        },
+        spawn: SpawnSpec::GlyphOutline,
     }
 }
@@ -1,3 +1,4 @@
+use super::definition::PresetDefinition;
 use super::types::CharBounds;
 use crate::particle::ParticleEmitter;
 
@@ -8,4 +9,9 @@ use crate::particle::ParticleEmitter;
 pub trait ParticlePreset: Send + Sync {
     /// Create a new emitter instance for this preset
     fn create_emitter(&self, bounds: &CharBounds, seed: u64) -> ParticleEmitter;
+
+    /// Data form of this preset, if it can be expressed as a `PresetDefinition`
+    fn definition(&self) -> Option<PresetDefinition> {
+        None
+    }
 }
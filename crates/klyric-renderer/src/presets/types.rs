@@ -1,4 +1,4 @@
-use crate::particle::SpawnPattern;
+use crate::particle::{ShapeSampling, SpawnPattern};
 
 /// Effect preset identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -67,4 +67,35 @@ impl CharBounds {
             h: self.height,
         }
     }
+
+    /// Create spawn pattern on the glyph outline (box outline until the glyph shape is attached)
+    pub fn spawn_glyph_outline(&self) -> SpawnPattern {
+        self.spawn_glyph(ShapeSampling::Outline)
+    }
+
+    /// Create spawn pattern inside the glyph fill (box fill until the glyph shape is attached)
+    pub fn spawn_glyph_fill(&self) -> SpawnPattern {
+        self.spawn_glyph(ShapeSampling::Fill)
+    }
+
+    fn spawn_glyph(&self, sampling: ShapeSampling) -> SpawnPattern {
+        SpawnPattern::Glyph {
+            shape: None,
+            sampling,
+            x: self.x,
+            y: self.y,
+            w: self.width,
+            h: self.height,
+        }
+    }
+
+    /// Create a ring spawn pattern around the character center
+    pub fn spawn_ring(&self, inner: f32, outer: f32) -> SpawnPattern {
+        SpawnPattern::Ring {
+            cx: self.x + self.width / 2.0,
+            cy: self.y + self.height / 2.0,
+            inner,
+            outer,
+        }
+    }
 }
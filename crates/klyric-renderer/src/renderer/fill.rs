@@ -0,0 +1,154 @@
+//! Gradient and pattern fills for text and shapes
+
+use skia_safe::{
+    gradient_shader, Color, Image, Matrix, Point, Rect, SamplingOptions, Shader, TileMode,
+};
+
+use crate::model::{FillSpan, FillStroke, Gradient, GradientType};
+
+use super::utils::parse_color;
+
+/// A state's gradient or pattern fill, resolved once per style
+#[derive(Debug, Clone)]
+pub enum TextFill {
+    Gradient {
+        gradient_type: GradientType,
+        colors: Vec<Color>,
+        stops: Option<Vec<f32>>,
+        angle: f32,
+        span: FillSpan,
+    },
+    Pattern {
+        image: Image,
+        scale: f32,
+        span: FillSpan,
+    },
+}
+
+impl TextFill {
+    /// The pattern or gradient of `fill_stroke`, loading pattern images with
+    /// `image`. `None` for solid fills and gradients with fewer than two
+    /// valid colors.
+    pub fn resolve(
+        fill_stroke: &FillStroke,
+        image: impl FnOnce(&str) -> Option<Image>,
+    ) -> Option<Self> {
+        if let Some(pattern) = &fill_stroke.pattern {
+            return Some(TextFill::Pattern {
+                image: image(&pattern.src)?,
+                scale: pattern.scale.unwrap_or(1.0),
+                span: pattern.span,
+            });
+        }
+        let text_gradient = fill_stroke.gradient.as_ref()?;
+        Self::gradient(&text_gradient.gradient, text_gradient.span)
+    }
+
+    /// A gradient fill laid out over `span`; `None` with fewer than two
+    /// valid colors
+    pub fn gradient(gradient: &Gradient, span: FillSpan) -> Option<Self> {
+        let colors: Vec<Color> = gradient
+            .colors
+            .iter()
+            .filter_map(|c| parse_color(c))
+            .collect();
+        if colors.len() < 2 {
+            return None;
+        }
+        // Stops only apply when there is one per color
+        let stops = gradient
+            .stops
+            .clone()
+            .filter(|stops| stops.len() == colors.len());
+        Some(TextFill::Gradient {
+            gradient_type: gradient.gradient_type.clone(),
+            colors,
+            stops,
+            angle: gradient.angle,
+            span,
+        })
+    }
+
+    pub fn span(&self) -> FillSpan {
+        match self {
+            TextFill::Gradient { span, .. } | TextFill::Pattern { span, .. } => *span,
+        }
+    }
+
+    /// Shader laying the fill out over `rect`
+    pub fn shader(&self, rect: Rect) -> Option<Shader> {
+        match self {
+            TextFill::Gradient {
+                gradient_type,
+                colors,
+                stops,
+                angle,
+                ..
+            } => {
+                let stops = stops.as_deref();
+                let center = rect.center();
+                match gradient_type {
+                    GradientType::Linear => gradient_shader::linear(
+                        linear_points(*angle, rect),
+                        colors.as_slice(),
+                        stops,
+                        TileMode::Clamp,
+                        None,
+                        None,
+                    ),
+                    GradientType::Radial => gradient_shader::radial(
+                        center,
+                        rect.width().hypot(rect.height()) / 2.0,
+                        colors.as_slice(),
+                        stops,
+                        TileMode::Clamp,
+                        None,
+                        None,
+                    ),
+                    // Skia sweeps start at 3 o'clock
+                    GradientType::Sweep => gradient_shader::sweep(
+                        center,
+                        colors.as_slice(),
+                        stops,
+                        TileMode::Clamp,
+                        None,
+                        None,
+                        &Matrix::rotate_deg_pivot(angle - 90.0, center),
+                    ),
+                }
+            }
+            TextFill::Pattern { image, scale, .. } => image.to_shader(
+                (TileMode::Repeat, TileMode::Repeat),
+                SamplingOptions::default(),
+                &Matrix::scale_translate((*scale, *scale), (rect.left, rect.top)),
+            ),
+        }
+    }
+}
+
+/// Ends of a linear gradient at `angle` (CSS-style: 0 is up, 180 down),
+/// long enough that the end colors reach the corners of `rect`
+fn linear_points(angle: f32, rect: Rect) -> (Point, Point) {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let half = (rect.width() * sin.abs() + rect.height() * cos.abs()) / 2.0;
+    let offset = Point::new(sin * half, -cos * half);
+    let center = rect.center();
+    (center - offset, center + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_points() {
+        let rect = Rect::from_xywh(0.0, 0.0, 200.0, 100.0);
+        let (start, end) = linear_points(180.0, rect);
+        assert!((start.x - 100.0).abs() < 1e-3 && start.y.abs() < 1e-3);
+        assert!((end.x - 100.0).abs() < 1e-3 && (end.y - 100.0).abs() < 1e-3);
+
+        let (start, end) = linear_points(90.0, rect);
+        assert!(start.x.abs() < 1e-3 && (start.y - 50.0).abs() < 1e-3);
+        assert!((end.x - 200.0).abs() < 1e-3 && (end.y - 50.0).abs() < 1e-3);
+    }
+}
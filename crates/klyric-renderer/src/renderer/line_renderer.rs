@@ -1,21 +1,32 @@
 use anyhow::Result;
-use skia_safe::{surfaces, BlendMode, BlurStyle, Canvas, Color, MaskFilter, Paint, PaintStyle};
+use skia_safe::canvas::SaveLayerRec;
+use skia_safe::image_filters::{self, CropRect};
+use skia_safe::{
+    color_filters, surfaces, svg, BlendMode, BlurStyle, Canvas, ClipOp, Color, ColorFilter, Image,
+    MaskFilter, Paint, PaintStyle, RRect, Rect,
+};
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+use crate::audio::AudioLevels;
+use crate::effects::drivers::DriverManager;
 use crate::effects::{CompiledRenderOp, EffectEngine, TriggerContext};
 use crate::expressions::{EvaluationContext, FastEvaluationContext};
 use crate::layout::{GlyphInfo, LayoutEngine};
 use crate::model::{
-    Easing, EffectType, KLyricDocumentV2, Line, PositionValue, RenderTransform, Style, Transform,
+    Easing, Effect, EffectType, FillSpan, KLyricDocumentV2, KaraokeMode, Line, Mask, MaskFill,
+    MaskShape, Overlay, OverlayKind, PositionValue, RenderTransform, Style, Transform, ValueDriver,
 };
+use crate::particle::SpawnShape;
 use crate::presets::CharBounds;
 use crate::text::TextRenderer;
 
-use super::particle_system::ParticleRenderSystem;
+use super::fill::TextFill;
+use super::particle_system::{glyph_spawn_shape, ParticleRenderSystem};
 use super::utils::parse_color;
 use super::CategorizedLineEffects;
-use super::ResolvedStyleColors;
+use super::{ResolvedShadow, ResolvedStyleColors};
 
 pub struct RenderPaints {
     pub main_paint: Paint,
@@ -24,13 +35,25 @@ pub struct RenderPaints {
     pub r_paint: Paint,
     pub g_paint: Paint,
     pub b_paint: Paint,
+    /// Layered and inner shadows
+    pub layer_paint: Paint,
+    /// Cuts the offset glyph out of an inner shadow
+    pub inset_paint: Paint,
     pub cached_blur_filter: Option<(f32, MaskFilter)>,
+    /// [Bolt Optimization] Last blur filter of the single shadow, keyed by
+    /// sigma
+    pub cached_shadow_filter: Option<(f32, MaskFilter)>,
     pub current_paint_blur: f32,
     pub current_shadow_blur: f32,
     pub current_stroke_blur: f32,
     pub current_r_blur: f32,
     pub current_g_blur: f32,
     pub current_b_blur: f32,
+    /// [Bolt Optimization] Last color-adjust filter, keyed by hue, brightness,
+    /// contrast, saturation and invert
+    pub cached_color_filter: Option<([f32; 5], ColorFilter)>,
+    /// Whether the glyph paints carry a color filter
+    pub color_adjusted: bool,
 }
 
 impl RenderPaints {
@@ -63,6 +86,13 @@ impl RenderPaints {
         b_paint.set_anti_alias(true);
         b_paint.set_style(PaintStyle::Fill);
 
+        let mut layer_paint = Paint::default();
+        layer_paint.set_anti_alias(true);
+
+        let mut inset_paint = Paint::default();
+        inset_paint.set_anti_alias(true);
+        inset_paint.set_blend_mode(BlendMode::DstOut);
+
         Self {
             main_paint,
             shadow_paint,
@@ -70,13 +100,81 @@ impl RenderPaints {
             r_paint,
             g_paint,
             b_paint,
+            layer_paint,
+            inset_paint,
             cached_blur_filter: None,
+            cached_shadow_filter: None,
             current_paint_blur: 0.0,
             current_shadow_blur: 0.0,
             current_stroke_blur: 0.0,
             current_r_blur: 0.0,
             current_g_blur: 0.0,
             current_b_blur: 0.0,
+            cached_color_filter: None,
+            color_adjusted: false,
+        }
+    }
+}
+
+/// Decoded overlay image: a bitmap, or an SVG drawn as vectors
+#[derive(Debug, Clone)]
+pub enum OverlayAsset {
+    Raster(Image),
+    Vector(svg::Dom),
+}
+
+impl OverlayAsset {
+    /// Natural size in pixels; unknown for an SVG without a fixed size or
+    /// `viewBox`
+    pub fn size(&self) -> Option<(f32, f32)> {
+        match self {
+            OverlayAsset::Raster(image) => Some((image.width() as f32, image.height() as f32)),
+            OverlayAsset::Vector(dom) => {
+                let root = dom.root();
+                let size = root.intrinsic_size();
+                if size.width > 0.0 && size.height > 0.0 {
+                    return Some((size.width, size.height));
+                }
+                root.view_box()
+                    .map(|view_box| (view_box.width(), view_box.height()))
+                    .filter(|(w, h)| *w > 0.0 && *h > 0.0)
+            }
+        }
+    }
+
+    /// Draw stretched over `rect`. The paint's opacity, blur and blend mode
+    /// apply to the artwork as a whole.
+    pub fn draw(&self, canvas: &Canvas, rect: Rect, paint: &Paint) {
+        match self {
+            OverlayAsset::Raster(image) => {
+                canvas.draw_image_rect(image, None, rect, paint);
+            }
+            OverlayAsset::Vector(dom) => {
+                canvas.save_layer(&SaveLayerRec::default().paint(paint));
+                canvas.translate((rect.left, rect.top));
+                let mut dom = dom.clone();
+                match self.size() {
+                    Some((w, h)) => {
+                        canvas.scale((rect.width() / w, rect.height() / h));
+                    }
+                    None => dom.set_container_size((rect.width(), rect.height())),
+                }
+                dom.render(canvas);
+                canvas.restore();
+            }
+        }
+    }
+
+    /// Bitmap of the asset at its natural size, for use as a pattern tile
+    pub fn to_image(&self) -> Option<Image> {
+        match self {
+            OverlayAsset::Raster(image) => Some(image.clone()),
+            OverlayAsset::Vector(_) => {
+                let (w, h) = self.size()?;
+                let mut surface = surfaces::raster_n32_premul((w.ceil() as i32, h.ceil() as i32))?;
+                self.draw(surface.canvas(), Rect::from_wh(w, h), &Paint::default());
+                Some(surface.image_snapshot())
+            }
         }
     }
 }
@@ -99,6 +197,9 @@ pub struct LineRenderScratch {
     /// Key: (typeface_id, font_size_bits, glyph_id, progress_bits)
     /// Value: Option<Path>. Stores the result of measure.segment() for the current line's progress.
     pub segment_cache: HashMap<(u32, u64, u16, u64), Option<skia_safe::Path>>,
+    /// Cache for flattened glyph outlines used by glyph spawn patterns.
+    /// Key: (typeface_id, font_size_bits, glyph_id)
+    pub glyph_shape_cache: HashMap<(u32, u64, u16), Arc<SpawnShape>>,
 }
 
 impl LineRenderScratch {
@@ -113,6 +214,7 @@ impl LineRenderScratch {
             active_hoisted_mask: 0,
             path_measure_cache: HashMap::new(),
             segment_cache: HashMap::new(),
+            glyph_shape_cache: HashMap::new(),
         }
     }
 }
@@ -155,6 +257,13 @@ pub struct LineRenderer<'a> {
     pub width: u32,
     pub height: u32,
     pub paints: &'a mut RenderPaints,
+    /// Audio levels at `time`
+    pub audio: AudioLevels,
+    /// Singer region as fractions of the frame, replacing the line's
+    /// position on each axis that is set (see `Placement::region`)
+    pub region: (Option<f32>, Option<f32>),
+    /// Image the text is painted with under a `background` mask fill
+    pub fill: Option<OverlayAsset>,
 }
 
 impl<'a> LineRenderer<'a> {
@@ -182,12 +291,26 @@ impl<'a> LineRenderer<'a> {
         // Pre-resolve colors to avoid parsing per-glyph
         // [Bolt Optimization] Use cached colors passed from Renderer
         let inactive_color = colors.inactive;
-        let active_color = colors.active;
         let complete_color = colors.complete;
+        // The line's singer color replaces the style's active fill
+        let line_singer_color = line
+            .singer
+            .as_deref()
+            .and_then(|id| singer_color(self.doc, id));
+        let line_active_color = line_singer_color.unwrap_or(colors.active);
+        let line_active_fill = colors
+            .active_fill
+            .as_ref()
+            .filter(|_| line_singer_color.is_none());
+        // Line-spanning fills are laid out over the whole line's text
+        let line_bounds = text_bounds(glyphs);
 
         // Hoist Line Transform
         let line_transform = line.transform.clone().unwrap_or_default();
 
+        // Ruby glyphs trail the base text and are not counted as chars
+        let glyph_count = glyphs.partition_point(|g| g.ruby.is_none());
+
         // [Bolt Optimization] Paint objects are now reused from `self.paints` (see RenderPaints)
 
         // [Bolt Optimization] Hoist effect compilation and context creation
@@ -196,13 +319,19 @@ impl<'a> LineRenderer<'a> {
         // Since effect.delay is scalar and we use line.start, progress is invariant for the line.
         // Per-character variations (staggering) are handled via expressions (evaluated per-char)
         // or specialized ops (like TypewriterLimit) which are preserved in compiled ops.
+        let section = self
+            .doc
+            .section_at(line.start)
+            .map(|section| section.name.as_str());
         let line_ctx = TriggerContext {
             start_time: line.start,
             end_time: line.end,
             current_time: self.time,
             active: true,
             char_index: None,
-            char_count: Some(glyphs.len()),
+            char_count: Some(glyph_count),
+            audio: self.audio,
+            section,
         };
 
         // [Bolt Optimization] Use scratch buffers for active effects to avoid per-frame allocation
@@ -272,6 +401,12 @@ impl<'a> LineRenderer<'a> {
             }
         }
 
+        // Karaoke wipe: the sung color sweeps across each char while it is active
+        let karaoke_wipe = effects.transform_effects.iter().any(|resolved| {
+            resolved.effect.effect_type == EffectType::Karaoke
+                && matches!(resolved.effect.mode, Some(KaraokeMode::Wipe))
+        });
+
         // Reusable context for expression evaluation
         let eval_ctx = EvaluationContext {
             t: self.time,
@@ -279,9 +414,10 @@ impl<'a> LineRenderer<'a> {
             width: self.width as f64,
             height: self.height as f64,
             index: None,
-            count: Some(glyphs.len()),
+            count: Some(glyph_count),
             char_width: None,
             char_height: None,
+            audio: self.audio,
         };
         let mut fast_ctx = FastEvaluationContext::new(&eval_ctx);
 
@@ -332,7 +468,9 @@ impl<'a> LineRenderer<'a> {
                 current_time: self.time,
                 active: true,
                 char_index: Some(0), // Dummy index to satisfy Scope(Char)
-                char_count: Some(glyphs.len()),
+                char_count: Some(glyph_count),
+                audio: self.audio,
+                section,
             };
             EffectEngine::compute_global_layer_transform(
                 self.time,
@@ -344,6 +482,36 @@ impl<'a> LineRenderer<'a> {
             RenderTransform::default()
         };
 
+        // Mask: clip to its shape, and draw into a layer the fill image is
+        // painted into
+        let mask = line.mask.as_ref().or(style.mask.as_ref());
+        let restore_count = self.canvas.save_count();
+        let fill = mask
+            .filter(|mask| mask.fill == MaskFill::Background)
+            .and(self.fill.as_ref());
+        if let Some(mask) = mask {
+            self.canvas.save();
+            if let Some(shape) = mask.shape {
+                let rect = self.mask_rect(mask, line, glyphs, (base_x, base_y));
+                let op = if mask.invert {
+                    ClipOp::Difference
+                } else {
+                    ClipOp::Intersect
+                };
+                let rrect = match shape {
+                    MaskShape::Rect => {
+                        let radius = mask.radius.unwrap_or(0.0);
+                        RRect::new_rect_xy(rect, radius, radius)
+                    }
+                    MaskShape::Ellipse => RRect::new_oval(rect),
+                };
+                self.canvas.clip_rrect(rrect, op, true);
+            }
+            if fill.is_some() {
+                self.canvas.save_layer(&SaveLayerRec::default());
+            }
+        }
+
         // Loop:
         for glyph in glyphs.iter() {
             let char_absolute_x = base_x + glyph.x;
@@ -369,19 +537,52 @@ impl<'a> LineRenderer<'a> {
                     let cx = w / 2.0;
                     let cy = h / 2.0;
 
+                    // Highlight timing: the char's, or a ruby glyph's share of it
+                    let timing = char_data.map(|c| match glyph.ruby {
+                        Some((from, to)) => {
+                            let duration = c.end - c.start;
+                            (
+                                c.start + duration * from as f64,
+                                c.start + duration * to as f64,
+                            )
+                        }
+                        None => (c.start, c.end),
+                    });
+
                     // Resolve color
-                    let is_active = char_data
-                        .map(|c| self.time >= c.start && self.time <= c.end)
+                    let is_active = timing
+                        .map(|(start, end)| self.time >= start && self.time <= end)
                         .unwrap_or(false);
-                    let is_past = char_data.map(|c| self.time > c.end).unwrap_or(false);
+                    let is_past = timing.map(|(_, end)| self.time > end).unwrap_or(false);
 
-                    let text_color = if is_past {
-                        complete_color
+                    let (text_color, text_fill) = if is_past {
+                        (complete_color, colors.complete_fill.as_ref())
                     } else if is_active {
-                        active_color
+                        match char_data
+                            .and_then(|c| c.singer.as_deref())
+                            .and_then(|id| singer_color(self.doc, id))
+                        {
+                            Some(color) => (color, None),
+                            None => (line_active_color, line_active_fill),
+                        }
                     } else {
-                        inactive_color
+                        (inactive_color, colors.inactive_fill.as_ref())
                     };
+                    // Shaders are in the glyph's own coordinates
+                    let fill_shader = |fill: Option<&TextFill>| {
+                        fill.and_then(|fill| match fill.span() {
+                            FillSpan::Glyph => fill.shader(bounds),
+                            FillSpan::Line => {
+                                fill.shader(line_bounds.with_offset((-glyph.x, -glyph.y)))
+                            }
+                        })
+                    };
+
+                    // Portion of the char already swept by a karaoke wipe
+                    let wipe_progress = timing
+                        .filter(|_| karaoke_wipe && is_active)
+                        .map(|(start, end)| ((self.time - start) / (end - start)) as f32)
+                        .filter(|p| *p > 0.0 && *p < 1.0);
 
                     // Compute Transform (Base + Effects)
                     // [Bolt Optimization] Use RenderTransform and compiled ops
@@ -433,7 +634,9 @@ impl<'a> LineRenderer<'a> {
                         current_time: self.time,
                         active: true,
                         char_index: Some(glyph.char_index),
-                        char_count: Some(glyphs.len()),
+                        char_count: Some(glyph_count),
+                        audio: self.audio,
+                        section,
                     };
 
                     // --- 4. MODIFIER LAYERS (New System) ---
@@ -462,6 +665,7 @@ impl<'a> LineRenderer<'a> {
                     // Setup Paint
                     // [Bolt Optimization] Manual reuse without reset()
                     self.paints.main_paint.set_color(text_color);
+                    self.paints.main_paint.set_shader(fill_shader(text_fill));
 
                     let final_opacity =
                         final_transform.opacity * (1.0 - disintegration_progress as f32);
@@ -495,6 +699,7 @@ impl<'a> LineRenderer<'a> {
                         blur,
                         &self.paints.cached_blur_filter,
                     );
+                    apply_color_adjust(&mut self.paints, &final_transform);
 
                     // --- DRAWING ---
                     // Calculate position context
@@ -606,6 +811,16 @@ impl<'a> LineRenderer<'a> {
                     let path = path_to_draw; // Shadow original path with the one to draw
 
                     // --- 1. SHADOW ---
+                    for shadow in colors.shadows.iter().filter(|s| !s.inset) {
+                        let paint = &mut self.paints.layer_paint;
+                        paint.set_color(shadow.color);
+                        paint.set_alpha_f(shadow.color.a() as f32 / 255.0 * final_opacity);
+                        paint.set_mask_filter(shadow_filter(shadow, final_transform.blur));
+                        self.canvas.translate((shadow.x, shadow.y));
+                        self.canvas.draw_path(path, &self.paints.layer_paint);
+                        self.canvas.translate((-shadow.x, -shadow.y));
+                    }
+
                     let (active_shadow, active_shadow_color) =
                         if let Some(c_shadow) = char_data.and_then(|c| c.shadow.as_ref()) {
                             // [Bolt Optimization] Use pre-parsed color from glyph info
@@ -613,20 +828,50 @@ impl<'a> LineRenderer<'a> {
                         } else {
                             (fallback_shadow, fallback_shadow_color)
                         };
+                    // An inset shadow is drawn over the fill instead
+                    let (active_shadow, inset_shadow) =
+                        match active_shadow.filter(|s| s.inset == Some(true)) {
+                            Some(shadow) => (None, Some(shadow)),
+                            None => (active_shadow, None),
+                        };
 
                     if let (Some(shadow), Some(shadow_color)) = (active_shadow, active_shadow_color)
                     {
-                        // [Bolt Optimization] Reuse shadow paint
+                        // [Bolt Optimization] Reuse shadow paint. Like a layered
+                        // shadow, it keeps its color's alpha and is blurred by its
+                        // own `blur` plus the transform's.
                         self.paints.shadow_paint.set_color(shadow_color);
-                        self.paints.shadow_paint.set_alpha_f(final_opacity);
-
-                        // [Bolt Optimization] Apply blur to shadow with state tracking
-                        apply_paint_blur(
-                            &mut self.paints.shadow_paint,
-                            &mut self.paints.current_shadow_blur,
-                            final_transform.blur,
-                            &self.paints.cached_blur_filter,
-                        );
+                        self.paints
+                            .shadow_paint
+                            .set_alpha_f(shadow_color.a() as f32 / 255.0 * final_opacity);
+
+                        let shadow_blur = shadow.blur_or_default();
+                        if shadow_blur > 0.0 {
+                            let sigma = shadow_blur + final_transform.blur;
+                            let cached =
+                                self.paints.cached_shadow_filter.as_ref().is_some_and(
+                                    |(last_sigma, _)| (last_sigma - sigma).abs() < 0.001,
+                                );
+                            if !cached {
+                                self.paints.cached_shadow_filter =
+                                    MaskFilter::blur(BlurStyle::Normal, sigma, false)
+                                        .map(|filter| (sigma, filter));
+                            }
+                            let filter = self.paints.cached_shadow_filter.as_ref();
+                            self.paints
+                                .shadow_paint
+                                .set_mask_filter(filter.map(|(_, filter)| filter.clone()));
+                            // Not the transform's filter, so the fast path resets it
+                            self.paints.current_shadow_blur = f32::INFINITY;
+                        } else {
+                            // [Bolt Optimization] Apply blur to shadow with state tracking
+                            apply_paint_blur(
+                                &mut self.paints.shadow_paint,
+                                &mut self.paints.current_shadow_blur,
+                                final_transform.blur,
+                                &self.paints.cached_blur_filter,
+                            );
+                        }
 
                         // [Bolt Optimization] Use translate instead of save/restore
                         let sx = shadow.x_or_default();
@@ -637,6 +882,23 @@ impl<'a> LineRenderer<'a> {
                     }
 
                     // --- 2. STROKE ---
+                    if !colors.strokes.is_empty() {
+                        apply_paint_blur(
+                            &mut self.paints.stroke_paint,
+                            &mut self.paints.current_stroke_blur,
+                            final_transform.blur,
+                            &self.paints.cached_blur_filter,
+                        );
+                        for (color, width) in &colors.strokes {
+                            self.paints.stroke_paint.set_stroke_width(*width);
+                            self.paints.stroke_paint.set_color(*color);
+                            self.paints
+                                .stroke_paint
+                                .set_alpha_f(color.a() as f32 / 255.0 * final_opacity);
+                            self.canvas.draw_path(path, &self.paints.stroke_paint);
+                        }
+                    }
+
                     let (active_stroke, active_stroke_color) =
                         if let Some(c_stroke) = char_data.and_then(|c| c.stroke.as_ref()) {
                             // [Bolt Optimization] Use pre-parsed color from glyph info
@@ -653,7 +915,9 @@ impl<'a> LineRenderer<'a> {
                                 .stroke_paint
                                 .set_stroke_width(stroke.width_or_default());
                             self.paints.stroke_paint.set_color(stroke_color);
-                            self.paints.stroke_paint.set_alpha_f(final_opacity);
+                            self.paints
+                                .stroke_paint
+                                .set_alpha_f(stroke_color.a() as f32 / 255.0 * final_opacity);
 
                             // [Bolt Optimization] Apply blur to stroke with state tracking
                             apply_paint_blur(
@@ -721,18 +985,72 @@ impl<'a> LineRenderer<'a> {
                             self.canvas.translate((offset, offset));
                             self.canvas.draw_path(path, &self.paints.b_paint);
                             self.canvas.translate((-offset, -offset));
+                        } else if let Some(p) = wipe_progress {
+                            // Wipe: unsung color, then the active color clipped to the swept part
+                            self.paints.main_paint.set_color(inactive_color);
+                            self.paints
+                                .main_paint
+                                .set_shader(fill_shader(colors.inactive_fill.as_ref()));
+                            self.paints.main_paint.set_alpha_f(final_opacity);
+                            self.canvas.draw_path(path, &self.paints.main_paint);
+
+                            let pad = bounds.height();
+                            self.canvas.save();
+                            self.canvas.clip_rect(
+                                Rect::from_ltrb(
+                                    bounds.left - pad,
+                                    bounds.top - pad,
+                                    bounds.left + bounds.width() * p,
+                                    bounds.bottom + pad,
+                                ),
+                                None,
+                                Some(true),
+                            );
+                            self.paints.main_paint.set_color(text_color);
+                            self.paints.main_paint.set_shader(fill_shader(text_fill));
+                            self.paints.main_paint.set_alpha_f(final_opacity);
+                            self.canvas.draw_path(path, &self.paints.main_paint);
+                            self.canvas.restore();
                         } else {
                             // Normal Draw
                             self.canvas.draw_path(path, &self.paints.main_paint);
                         }
                     }
 
+                    // --- 4. INNER SHADOW ---
+                    if let (Some(shadow), Some(color)) = (inset_shadow, active_shadow_color) {
+                        let blur = shadow.blur_or_default() + final_transform.blur;
+                        self.draw_inner_shadow(
+                            path,
+                            bounds,
+                            color,
+                            (shadow.x_or_default(), shadow.y_or_default()),
+                            MaskFilter::blur(BlurStyle::Normal, blur, false),
+                            final_opacity,
+                        );
+                    }
+                    for shadow in colors.shadows.iter().filter(|s| s.inset) {
+                        self.draw_inner_shadow(
+                            path,
+                            bounds,
+                            shadow.color,
+                            (shadow.x, shadow.y),
+                            shadow_filter(shadow, final_transform.blur),
+                            final_opacity,
+                        );
+                    }
+
                     if is_simple_transform {
                         self.canvas.translate((-tx, -ty));
                     } else {
                         self.canvas.restore(); // Restore transform for next glyph/effects
                     }
 
+                    // Particles come from the base text only
+                    if glyph.ruby.is_some() {
+                        continue;
+                    }
+
                     // --- DISINTEGRATION EFFECT ---
                     // [Bolt Optimization] Iterate active effects only
                     for (idx, _progress, base_prefix) in &scratch.active_disintegrate_indices {
@@ -865,14 +1183,235 @@ impl<'a> LineRenderer<'a> {
                             bounds_rect,
                             seed,
                         );
+
+                        // Attach the glyph outline for glyph-shaped spawn patterns
+                        if self.particle_system.emitter_needs_glyph_shape(key) {
+                            if let Some(glyph_path) = glyph.path.as_ref() {
+                                let tf_id: u32 = typeface.unique_id().into();
+                                let shape_key = (tf_id, glyph.font_size.to_bits(), glyph.glyph_id);
+                                let shape = scratch
+                                    .glyph_shape_cache
+                                    .entry(shape_key)
+                                    .or_insert_with(|| {
+                                        Arc::new(glyph_spawn_shape(glyph_path, &bounds))
+                                    })
+                                    .clone();
+                                self.particle_system.set_emitter_glyph_shape(key, shape);
+                            }
+                        }
                     }
                 }
             }
         }
 
+        // The text's coverage takes the fill image, cropped to cover the frame
+        if let Some(fill) = fill {
+            let (fw, fh) = (self.width as f32, self.height as f32);
+            let rect = match fill.size() {
+                Some((w, h)) => {
+                    let scale = (fw / w).max(fh / h);
+                    let (w, h) = (w * scale, h * scale);
+                    Rect::from_xywh((fw - w) / 2.0, (fh - h) / 2.0, w, h)
+                }
+                None => Rect::from_wh(fw, fh),
+            };
+            let mut paint = Paint::default();
+            paint.set_anti_alias(true);
+            paint.set_blend_mode(BlendMode::SrcIn);
+            fill.draw(self.canvas, rect, &paint);
+        }
+        self.canvas.restore_to_count(restore_count);
+
         Ok(())
     }
 
+    /// Shade the inside of `path` along the edges facing away from `offset`,
+    /// as if the glyph were cut into the frame and lit from that side
+    fn draw_inner_shadow(
+        &mut self,
+        path: &skia_safe::Path,
+        bounds: Rect,
+        color: Color,
+        offset: (f32, f32),
+        mask_filter: Option<MaskFilter>,
+        opacity: f32,
+    ) {
+        let layer_paint = &mut self.paints.layer_paint;
+        layer_paint.set_color(color);
+        layer_paint.set_alpha_f(color.a() as f32 / 255.0 * opacity);
+        layer_paint.set_mask_filter(None);
+        self.paints.inset_paint.set_mask_filter(mask_filter);
+
+        self.canvas.save();
+        self.canvas.clip_path(path, ClipOp::Intersect, true);
+        self.canvas
+            .save_layer(&SaveLayerRec::default().bounds(&bounds));
+        self.canvas.draw_rect(bounds, &self.paints.layer_paint);
+        self.canvas.translate(offset);
+        self.canvas.draw_path(path, &self.paints.inset_paint);
+        self.canvas.restore();
+        self.canvas.restore();
+    }
+
+    /// The mask shape on screen, placed over the line's laid-out text
+    fn mask_rect(&self, mask: &Mask, line: &Line, glyphs: &[GlyphInfo], base: (f32, f32)) -> Rect {
+        let bounds = text_bounds(glyphs).with_offset(base);
+        let value = |driver: Option<&ValueDriver>, default: f32| {
+            driver.map_or(default, |driver| {
+                DriverManager::evaluate_span(driver, self.time, line.start, line.end, &self.audio)
+            })
+        };
+        Rect::from_xywh(
+            bounds.left + bounds.width() * value(mask.x.as_ref(), 0.0),
+            bounds.top + bounds.height() * value(mask.y.as_ref(), 0.0),
+            bounds.width() * value(mask.width.as_ref(), 1.0),
+            bounds.height() * value(mask.height.as_ref(), 1.0),
+        )
+    }
+
+    /// Draw an image or shape overlay centered on the position of `line`
+    /// (see `Overlay::to_line`). The line transform, transform effects and
+    /// the style's global layers apply to it as a whole.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_shape(
+        &mut self,
+        line: &Line,
+        overlay: &Overlay,
+        asset: Option<&OverlayAsset>,
+        style: &Style,
+        colors: &ResolvedStyleColors,
+        effects: &CategorizedLineEffects,
+        scratch: &mut LineRenderScratch,
+    ) {
+        let (x, y) = self.compute_line_position(line);
+        let section = self
+            .doc
+            .section_at(line.start)
+            .map(|section| section.name.as_str());
+        let ctx = TriggerContext {
+            start_time: line.start,
+            end_time: line.end,
+            current_time: self.time,
+            active: true,
+            char_index: None,
+            char_count: None,
+            audio: self.audio,
+            section,
+        };
+
+        let line_transform = line.transform.clone().unwrap_or_default();
+        let transform_effects: Vec<&Effect> = effects
+            .transform_effects
+            .iter()
+            .map(|resolved| &resolved.effect)
+            .collect();
+        let mut transform = EffectEngine::apply_to_render_transform(
+            self.time,
+            RenderTransform::new(&line_transform, &Transform::default()),
+            &transform_effects,
+            &ctx,
+        );
+        if let Some(layers) = style.layers.as_ref() {
+            let global_ctx = TriggerContext {
+                char_index: Some(0),
+                char_count: Some(1),
+                ..ctx
+            };
+            scratch.local_layer_indices.clear();
+            transform.combine(&EffectEngine::compute_global_layer_transform(
+                self.time,
+                layers,
+                &global_ctx,
+                &mut scratch.local_layer_indices,
+            ));
+        }
+
+        // Images default to their own size, keeping the aspect ratio
+        let natural = asset.and_then(OverlayAsset::size);
+        let (w, h) = match (natural, overlay.width, overlay.height) {
+            (_, Some(w), Some(h)) => (w, h),
+            (Some((nw, nh)), Some(w), None) => (w, nh * w / nw),
+            (Some((nw, nh)), None, Some(h)) => (nw * h / nh, h),
+            (Some(size), None, None) => size,
+            _ => return,
+        };
+        if w <= 0.0 || h <= 0.0 || transform.opacity <= 0.0 {
+            return;
+        }
+        let rect = Rect::from_xywh(-w / 2.0, -h / 2.0, w, h);
+
+        self.canvas.save();
+        self.canvas.translate((x + transform.x, y + transform.y));
+        let pivot_x = rect.left + w * transform.anchor_x;
+        let pivot_y = rect.top + h * transform.anchor_y;
+        self.canvas.translate((pivot_x, pivot_y));
+        self.canvas.rotate(transform.rotation, None);
+        self.canvas.scale((
+            transform.scale * transform.scale_x,
+            transform.scale * transform.scale_y,
+        ));
+        self.canvas.translate((-pivot_x, -pivot_y));
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        if transform.has_color_adjust() {
+            paint.set_color_filter(color_filters::matrix_row_major(
+                &transform.color_matrix(),
+                None,
+            ));
+        }
+        if transform.blur > 0.0 {
+            paint.set_image_filter(image_filters::blur(
+                (transform.blur, transform.blur),
+                None,
+                None,
+                CropRect::NO_CROP_RECT,
+            ));
+        }
+
+        let draw = |paint: &Paint| match overlay.kind {
+            OverlayKind::Rect => {
+                let radius = overlay.radius.unwrap_or(0.0);
+                self.canvas
+                    .draw_rrect(RRect::new_rect_xy(rect, radius, radius), paint);
+            }
+            OverlayKind::Ellipse => {
+                self.canvas.draw_oval(rect, paint);
+            }
+            OverlayKind::Image | OverlayKind::Text => {}
+        };
+
+        match (overlay.kind, asset) {
+            (OverlayKind::Image, Some(asset)) => {
+                paint.set_alpha_f(transform.opacity);
+                asset.draw(self.canvas, rect, &paint);
+            }
+            (OverlayKind::Image, None) => {}
+            _ => {
+                paint.set_color(colors.complete);
+                paint.set_shader(
+                    colors
+                        .complete_fill
+                        .as_ref()
+                        .and_then(|fill| fill.shader(rect)),
+                );
+                paint.set_alpha_f(colors.complete.a() as f32 / 255.0 * transform.opacity);
+                draw(&paint);
+                paint.set_shader(None);
+
+                let stroke_width = style.stroke.as_ref().map_or(0.0, |s| s.width_or_default());
+                if let (Some(color), true) = (colors.stroke, stroke_width > 0.0) {
+                    paint.set_style(PaintStyle::Stroke);
+                    paint.set_stroke_width(stroke_width);
+                    paint.set_color(color);
+                    paint.set_alpha_f(color.a() as f32 / 255.0 * transform.opacity);
+                    draw(&paint);
+                }
+            }
+        }
+        self.canvas.restore();
+    }
+
     fn compute_line_position(&self, line: &Line) -> (f32, f32) {
         let mut x = self.width as f32 / 2.0;
         let mut y = self.height as f32 / 2.0;
@@ -892,10 +1431,84 @@ impl<'a> LineRenderer<'a> {
             }
         }
 
+        let (region_x, region_y) = self.region;
+        if let Some(rx) = region_x {
+            x = rx * self.width as f32;
+        }
+        if let Some(ry) = region_y {
+            y = ry * self.height as f32;
+        }
+
         (x, y)
     }
 }
 
+/// Blur filter of a layered shadow, widened by the glyph's own blur
+fn shadow_filter(shadow: &ResolvedShadow, blur: f32) -> Option<MaskFilter> {
+    if blur > 0.0 {
+        MaskFilter::blur(BlurStyle::Normal, shadow.blur + blur, false)
+    } else {
+        shadow.mask_filter.clone()
+    }
+}
+
+/// Union of the glyphs' bounds, relative to the line position
+fn text_bounds(glyphs: &[GlyphInfo]) -> Rect {
+    glyphs
+        .iter()
+        .filter_map(|g| {
+            let bounds = g.bounds.or_else(|| g.path.as_ref().map(|p| *p.bounds()))?;
+            Some(bounds.with_offset((g.x, g.y)))
+        })
+        .reduce(Rect::join2)
+        .unwrap_or_default()
+}
+
+/// Highlight color of a singer, if it has one
+fn singer_color(doc: &KLyricDocumentV2, id: &str) -> Option<Color> {
+    doc.singers.get(id)?.color.as_deref().and_then(parse_color)
+}
+
+/// Set the color-matrix filter of the transform's hue, brightness, contrast,
+/// saturation and invert on the glyph's fill, glitch channel, stroke and
+/// shadow paints.
+/// Skips the paints entirely while no glyph is adjusted.
+fn apply_color_adjust(paints: &mut RenderPaints, transform: &RenderTransform) {
+    let filter = if transform.has_color_adjust() {
+        let key = [
+            transform.hue_shift,
+            transform.brightness,
+            transform.contrast,
+            transform.saturation,
+            transform.invert,
+        ];
+        match &paints.cached_color_filter {
+            Some((cached_key, filter)) if *cached_key == key => Some(filter.clone()),
+            _ => {
+                let filter = color_filters::matrix_row_major(&transform.color_matrix(), None);
+                paints.cached_color_filter = Some((key, filter.clone()));
+                Some(filter)
+            }
+        }
+    } else if paints.color_adjusted {
+        None
+    } else {
+        return;
+    };
+    paints.color_adjusted = filter.is_some();
+    for paint in [
+        &mut paints.main_paint,
+        &mut paints.stroke_paint,
+        &mut paints.shadow_paint,
+        &mut paints.layer_paint,
+        &mut paints.r_paint,
+        &mut paints.g_paint,
+        &mut paints.b_paint,
+    ] {
+        paint.set_color_filter(filter.clone());
+    }
+}
+
 /// Helper to apply blur mask filter to a paint object with state tracking.
 /// This prevents redundant ref-counting updates when blur sigma hasn't changed.
 fn apply_paint_blur(
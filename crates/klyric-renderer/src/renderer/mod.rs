@@ -1,21 +1,34 @@
+pub mod fill;
 pub mod line_renderer;
 pub mod particle_system;
 pub mod utils;
 
 use anyhow::Result;
-use skia_safe::{surfaces, AlphaType, Canvas, Color, ColorType, ImageInfo, Surface};
+use skia_safe::canvas::SaveLayerRec;
+use skia_safe::{
+    surfaces, svg, AlphaType, BlendMode as SkBlendMode, BlurStyle, Canvas, Color, ColorType, Data,
+    FontMgr, Image, ImageInfo, MaskFilter, Paint, Rect, Surface,
+};
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::SystemTime;
 
+use crate::audio::{AudioAnalysis, AudioLevels};
 use crate::effects::ResolvedEffect;
 use crate::expressions::ExpressionEvaluator;
 use crate::layout::{GlyphInfo, LayoutEngine};
-use crate::model::{AnimatedValue, Easing, Effect, EffectType, KLyricDocumentV2, Line, Style};
-use crate::presets::{CharBounds, EffectPreset};
+use crate::model::{
+    AnimatedValue, BackgroundType, Easing, Effect, EffectType, FillSpan, FillStroke,
+    KLyricDocumentV2, Line, MaskFill, Overlay, OverlayKind, Style,
+};
+use crate::particle::{ParticleLayer, ParticleQuality, ParticleStats};
+use crate::presets::{CharBounds, EffectPreset, PresetFactory};
 use crate::style::StyleResolver;
 use crate::text::TextRenderer;
 
-use self::line_renderer::LineRenderer;
+use self::fill::TextFill;
+use self::line_renderer::{LineRenderer, OverlayAsset};
 use self::particle_system::ParticleRenderSystem;
 use self::utils::parse_color;
 
@@ -31,9 +44,32 @@ pub struct ResolvedStyleColors {
     pub complete: Color,
     pub shadow: Option<Color>,
     pub stroke: Option<Color>,
+    /// Gradient or pattern fills replacing the solid colors per state
+    pub inactive_fill: Option<TextFill>,
+    pub active_fill: Option<TextFill>,
+    pub complete_fill: Option<TextFill>,
+    /// Layered `strokes` as (color, width), bottom first
+    pub strokes: Vec<(Color, f32)>,
+    /// Layered `shadows`, bottom first
+    pub shadows: Vec<ResolvedShadow>,
 }
 
-fn resolve_style_colors(style: &Style) -> ResolvedStyleColors {
+/// A layered shadow with its color parsed and blur filter built
+#[derive(Clone, Debug)]
+pub struct ResolvedShadow {
+    pub color: Color,
+    pub x: f32,
+    pub y: f32,
+    pub blur: f32,
+    pub mask_filter: Option<MaskFilter>,
+    pub inset: bool,
+}
+
+/// Resolve a style's colors, loading pattern images with `image`
+fn resolve_style_colors(
+    style: &Style,
+    mut image: impl FnMut(&str) -> Option<Image>,
+) -> ResolvedStyleColors {
     let inactive_hex = style
         .colors
         .as_ref()
@@ -70,12 +106,50 @@ fn resolve_style_colors(style: &Style) -> ResolvedStyleColors {
         .and_then(|s| s.color.as_deref())
         .and_then(parse_color);
 
+    let colors = style.colors.as_ref();
+    let mut fill = |state: Option<&FillStroke>| {
+        state.and_then(|fill_stroke| TextFill::resolve(fill_stroke, &mut image))
+    };
+    let inactive_fill = fill(colors.and_then(|c| c.inactive.as_ref()));
+    let active_fill = fill(colors.and_then(|c| c.active.as_ref()));
+    let complete_fill = fill(colors.and_then(|c| c.complete.as_ref()));
+
+    // Layers without a valid color or width draw nothing
+    let strokes = style
+        .strokes
+        .iter()
+        .flatten()
+        .filter_map(|s| Some((parse_color(s.color.as_deref()?)?, s.width_or_default())))
+        .filter(|(_, width)| *width > 0.0)
+        .collect();
+    let shadows = style
+        .shadows
+        .iter()
+        .flatten()
+        .filter_map(|s| {
+            let blur = s.blur_or_default();
+            Some(ResolvedShadow {
+                color: parse_color(s.color.as_deref()?)?,
+                x: s.x_or_default(),
+                y: s.y_or_default(),
+                blur,
+                mask_filter: MaskFilter::blur(BlurStyle::Normal, blur, false),
+                inset: s.inset.unwrap_or(false),
+            })
+        })
+        .collect();
+
     ResolvedStyleColors {
         inactive,
         active,
         complete,
         shadow,
         stroke,
+        inactive_fill,
+        active_fill,
+        complete_fill,
+        strokes,
+        shadows,
     }
 }
 
@@ -85,6 +159,17 @@ pub struct CategorizedLineEffects {
     pub particle_effects: Vec<(String, ResolvedEffect)>,
     pub disintegrate_effects: Vec<(String, ResolvedEffect)>,
     pub stroke_reveal_effects: Vec<Effect>,
+    /// Whether the particle effects create emitters drawing behind or
+    /// masked by the text
+    pub under_text: bool,
+}
+
+/// Whether frames are rendered for interactive preview or final export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    Preview,
+    #[default]
+    Export,
 }
 
 pub struct Renderer {
@@ -107,17 +192,32 @@ pub struct Renderer {
     line_hash_cache: HashMap<(usize, usize), u64>,
     /// Cache for pre-categorized effects per line: line_ptr -> CategorizedLineEffects
     line_effect_cache: HashMap<usize, CategorizedLineEffects>,
+    /// Lines rendering each line's translation tracks: line_ptr -> lines.
+    /// Shared so their pointers stay stable for the pointer-keyed caches.
+    translation_cache: HashMap<usize, Arc<[Line]>>,
+    /// Lines rendering the document's overlays (see `Overlay::to_line`),
+    /// shared for the same reason
+    overlay_lines: Option<Arc<[Line]>>,
+    /// Decoded overlay and mask fill images by resolved path
+    images: HashMap<PathBuf, CachedAsset>,
     /// Cache for resolved style colors: style_name -> ResolvedStyleColors
     style_color_cache: HashMap<String, ResolvedStyleColors>,
     /// Cached paint objects to avoid allocation per frame
     render_paints: line_renderer::RenderPaints,
     /// Scratch buffers for line rendering
     line_render_scratch: line_renderer::LineRenderScratch,
+    /// Preview or export rendering (selects the particle quality scale, and
+    /// whether track mute and solo apply)
+    render_mode: RenderMode,
+    /// Particle level-of-detail settings
+    particle_quality: ParticleQuality,
+    /// Precomputed analysis of `project.audio` for audio-reactive effects
+    audio_analysis: Option<Arc<AudioAnalysis>>,
 }
 
 impl Renderer {
     pub fn new(width: u32, height: u32) -> Self {
-        Self {
+        let mut renderer = Self {
             width,
             height,
             text_renderer: TextRenderer::new(),
@@ -129,16 +229,76 @@ impl Renderer {
             layout_cache: HashMap::new(),
             line_hash_cache: HashMap::new(),
             line_effect_cache: HashMap::new(),
+            translation_cache: HashMap::new(),
+            overlay_lines: None,
+            images: HashMap::new(),
             style_color_cache: HashMap::new(),
             render_paints: line_renderer::RenderPaints::new(),
             line_render_scratch: line_renderer::LineRenderScratch::new(),
-        }
+            render_mode: RenderMode::default(),
+            particle_quality: ParticleQuality::default(),
+            audio_analysis: None,
+        };
+        renderer.apply_particle_quality();
+        renderer
     }
 
     pub fn text_renderer_mut(&mut self) -> &mut TextRenderer {
         &mut self.text_renderer
     }
 
+    /// Switch between preview and export rendering
+    pub fn set_render_mode(&mut self, mode: RenderMode) {
+        self.render_mode = mode;
+        self.apply_particle_quality();
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    /// Set particle level-of-detail scales and the global particle budget
+    pub fn set_particle_quality(&mut self, quality: ParticleQuality) {
+        self.particle_quality = quality;
+        self.apply_particle_quality();
+    }
+
+    pub fn particle_quality(&self) -> ParticleQuality {
+        self.particle_quality
+    }
+
+    /// Live particle and emitter counts
+    pub fn particle_stats(&self) -> ParticleStats {
+        self.particle_system.stats()
+    }
+
+    fn apply_particle_quality(&mut self) {
+        let scale = match self.render_mode {
+            RenderMode::Preview => self.particle_quality.preview_scale,
+            RenderMode::Export => self.particle_quality.export_scale,
+        };
+        self.particle_system.set_count_scale(scale);
+        self.particle_system
+            .set_max_particles(self.particle_quality.max_particles);
+    }
+
+    /// Set the precomputed audio analysis used by audio-reactive drivers and
+    /// expressions. Without one, all audio levels read as silence.
+    pub fn set_audio_analysis(&mut self, analysis: Option<Arc<AudioAnalysis>>) {
+        self.audio_analysis = analysis;
+    }
+
+    pub fn audio_analysis(&self) -> Option<&Arc<AudioAnalysis>> {
+        self.audio_analysis.as_ref()
+    }
+
+    /// Load user particle preset packs (`*.json`) from a directory.
+    ///
+    /// Returns the number of presets registered.
+    pub fn load_preset_directory(&mut self, dir: &std::path::Path) -> Result<usize> {
+        self.particle_system.preset_factory.load_directory(dir)
+    }
+
     /// Render directly to an existing Canvas
     pub fn render_to_canvas(
         &mut self,
@@ -167,107 +327,409 @@ impl Renderer {
             self.line_hash_cache.clear();
             self.line_effect_cache.clear();
             self.style_color_cache.clear();
+            self.translation_cache.clear();
+            self.overlay_lines = None;
             self.line_render_scratch.path_measure_cache.clear();
+            self.line_render_scratch.glyph_shape_cache.clear();
+            self.particle_system
+                .preset_factory
+                .set_document_presets(&doc.particle_presets);
             self.last_doc_ptr = current_doc_ptr;
         }
 
         // 1. Draw Background
-        self.draw_background(canvas, doc);
+        self.draw_background(canvas, doc, time);
+
+        // Mute and solo are preview-only; exports draw every track
+        let active = match self.render_mode {
+            RenderMode::Preview => doc.preview_active_lines(time),
+            RenderMode::Export => doc.get_active_lines(time),
+        };
+        let regions = line_regions(doc, &active);
+
+        // Particles behind or masked by the text are composited against a
+        // separate text layer so they never touch the background.
+        let text_layer = self.needs_text_layer(doc, time, &regions);
+        let restore_count = canvas.save_count();
+        if text_layer {
+            canvas.save_layer(&SaveLayerRec::default());
+        }
 
         // Track which emitters are active this frame
         self.particle_system.reset_active_flags();
 
-        // 2. Find Active Lines and render
-        if let Some(line) = doc.get_active_line(time) {
-            // We need the line index to create unique keys
-            if let Some(line_idx) = doc.lines.iter().position(|l| std::ptr::eq(l, line)) {
-                // Resolve style (cached)
-                let style_name = line.style.as_deref().unwrap_or("base");
-                let style = if let Some(s) = self.style_cache.get(style_name) {
-                    s
-                } else {
-                    let s = StyleResolver::new(doc).resolve(style_name);
-                    self.style_cache.insert(style_name.to_string(), s);
-                    self.style_cache.get(style_name).unwrap()
-                };
-
-                // Layout (Cached via Content Hash)
-                // Optimization: Cache the hash calculation to avoid O(N) work every frame.
-                // Key by (line_ptr, style_ptr) to ensure correctness if line changes style association.
-                let line_ptr = line as *const _ as usize;
-                let style_ptr = style as *const _ as usize;
-                let hash_key = (line_ptr, style_ptr);
+        let audio = self
+            .audio_analysis
+            .as_ref()
+            .map(|a| a.levels_at(time))
+            .unwrap_or_default();
 
-                let layout_hash = if let Some(&hash) = self.line_hash_cache.get(&hash_key) {
-                    hash
-                } else {
-                    let hash = compute_layout_hash(line, style);
-                    self.line_hash_cache.insert(hash_key, hash);
-                    hash
-                };
-
-                if !self.layout_cache.contains_key(&layout_hash) {
-                    let g = LayoutEngine::layout_line(line, style, &mut self.text_renderer);
-                    self.layout_cache.insert(layout_hash, g);
-                }
-                let glyphs = self.layout_cache.get(&layout_hash).unwrap();
-
-                // Effects (Cached via Line Ptr)
-                if !self.line_effect_cache.contains_key(&line_ptr) {
-                    let effects = Self::resolve_line_effects(doc, line, style);
-                    self.line_effect_cache.insert(line_ptr, effects);
+        // 2. Render the active lines
+        for (line, region) in regions {
+            // We need the line index to create unique keys
+            let Some(line_idx) = doc.lines.iter().position(|l| std::ptr::eq(l, line)) else {
+                continue;
+            };
+            let mut bottom = match self
+                .render_line_layer(canvas, doc, time, audio, line, line_idx, region, None)
+            {
+                Ok(bottom) => bottom,
+                Err(e) => {
+                    canvas.restore_to_count(restore_count);
+                    return Err(e);
                 }
-                let effects = self.line_effect_cache.get(&line_ptr).unwrap();
+            };
 
-                // [Bolt Optimization] Resolve colors (cached)
-                if !self.style_color_cache.contains_key(style_name) {
-                    let colors = resolve_style_colors(style);
-                    self.style_color_cache
-                        .insert(style_name.to_string(), colors);
+            // Translation tracks stack under the line, each under the last
+            if !line.translations.is_empty() {
+                let line_ptr = line as *const _ as usize;
+                let translations = self
+                    .translation_cache
+                    .entry(line_ptr)
+                    .or_insert_with(|| {
+                        line.translations
+                            .iter()
+                            .map(|t| line.translation_line(t, doc))
+                            .collect()
+                    })
+                    .clone();
+                for (k, translation) in translations.iter().enumerate() {
+                    // Keep particle emitter keys apart from other lines
+                    let idx = line_idx + doc.lines.len() * (k + 1);
+                    match self.render_line_layer(
+                        canvas,
+                        doc,
+                        time,
+                        audio,
+                        translation,
+                        idx,
+                        region,
+                        Some(bottom),
+                    ) {
+                        Ok(b) => bottom = b,
+                        Err(e) => {
+                            canvas.restore_to_count(restore_count);
+                            return Err(e);
+                        }
+                    }
                 }
-                let style_colors = self.style_color_cache.get(style_name).unwrap();
-
-                let mut line_renderer = LineRenderer {
-                    canvas,
-                    doc,
-                    time,
-                    text_renderer: &mut self.text_renderer,
-                    particle_system: &mut self.particle_system,
-                    width: self.width,
-                    height: self.height,
-                    paints: &mut self.render_paints,
-                };
-
-                line_renderer.render_line(
-                    line,
-                    glyphs,
-                    line_idx,
-                    style,
-                    style_colors,
-                    effects,
-                    &mut self.line_render_scratch,
-                )?;
             }
         }
 
-        // 3. Update and render particles
+        // 3. Overlays, over the lyrics
+        if let Err(e) = self.render_overlays(canvas, doc, time, audio) {
+            canvas.restore_to_count(restore_count);
+            return Err(e);
+        }
+
+        // 4. Update and render particles
         self.particle_system.update(dt as f32);
-        self.particle_system.render(canvas);
+
+        if text_layer {
+            let mut layer_paint = Paint::default();
+
+            // Masked: only where the text has coverage
+            layer_paint.set_blend_mode(SkBlendMode::SrcATop);
+            canvas.save_layer(&SaveLayerRec::default().paint(&layer_paint));
+            self.particle_system
+                .render_layer(canvas, ParticleLayer::Masked);
+            canvas.restore();
+
+            // Behind: underneath the text already in the layer
+            layer_paint.set_blend_mode(SkBlendMode::DstOver);
+            canvas.save_layer(&SaveLayerRec::default().paint(&layer_paint));
+            self.particle_system
+                .render_layer(canvas, ParticleLayer::Behind);
+            canvas.restore();
+        }
+        canvas.restore_to_count(restore_count);
+
+        self.particle_system
+            .render_layer(canvas, ParticleLayer::Front);
 
         Ok(())
     }
 
+    /// Lay out and draw one line, moved to `region` (see
+    /// `LineRenderer::region`). With `below`, the text is stacked under that
+    /// `text_bottom` of the line above.
+    ///
+    /// Returns the `text_bottom` of what was drawn.
+    #[allow(clippy::too_many_arguments)]
+    fn render_line_layer(
+        &mut self,
+        canvas: &Canvas,
+        doc: &KLyricDocumentV2,
+        time: f64,
+        audio: AudioLevels,
+        line: &Line,
+        line_idx: usize,
+        region: (Option<f32>, Option<f32>),
+        below: Option<f32>,
+    ) -> Result<f32> {
+        // Resolve style (cached)
+        let style_name = doc.line_style(line);
+        let style = if let Some(s) = self.style_cache.get(style_name) {
+            s
+        } else {
+            let s = StyleResolver::new(doc).resolve(style_name);
+            self.style_cache.insert(style_name.to_string(), s);
+            self.style_cache.get(style_name).unwrap()
+        };
+
+        // Layout (Cached via Content Hash)
+        // Optimization: Cache the hash calculation to avoid O(N) work every frame.
+        // Key by (line_ptr, style_ptr) to ensure correctness if line changes style association.
+        let line_ptr = line as *const _ as usize;
+        let style_ptr = style as *const _ as usize;
+        let hash_key = (line_ptr, style_ptr);
+
+        let mut layout_hash = if let Some(&hash) = self.line_hash_cache.get(&hash_key) {
+            hash
+        } else {
+            let hash = compute_layout_hash(line, style);
+            self.line_hash_cache.insert(hash_key, hash);
+            hash
+        };
+        // Stacked text moves with the line above
+        if let Some(bottom) = below {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            (layout_hash, bottom.to_bits()).hash(&mut hasher);
+            layout_hash = hasher.finish();
+        }
+
+        if !self.layout_cache.contains_key(&layout_hash) {
+            let mut g = LayoutEngine::layout_line(line, style, &mut self.text_renderer);
+            if let Some(bottom) = below {
+                LayoutEngine::stack_below(&mut g, bottom);
+            }
+            self.layout_cache.insert(layout_hash, g);
+        }
+        let glyphs = self.layout_cache.get(&layout_hash).unwrap();
+
+        // Effects (Cached via Line Ptr)
+        if !self.line_effect_cache.contains_key(&line_ptr) {
+            let presets = &self.particle_system.preset_factory;
+            let effects = Self::resolve_line_effects(doc, line, style, presets);
+            self.line_effect_cache.insert(line_ptr, effects);
+        }
+        let effects = self.line_effect_cache.get(&line_ptr).unwrap();
+
+        // [Bolt Optimization] Resolve colors (cached)
+        if !self.style_color_cache.contains_key(style_name) {
+            let colors = resolve_style_colors(style, |src| {
+                cached_image(&mut self.images, &self.text_renderer, doc, src)
+                    .and_then(|asset| asset.to_image())
+            });
+            self.style_color_cache
+                .insert(style_name.to_string(), colors);
+        }
+        let style_colors = self.style_color_cache.get(style_name).unwrap();
+
+        // A `background` mask fill paints the text with its image
+        let fill_src = line
+            .mask
+            .as_ref()
+            .or(style.mask.as_ref())
+            .filter(|mask| mask.fill == MaskFill::Background)
+            .and_then(|mask| {
+                mask.src
+                    .as_deref()
+                    .or_else(|| doc.background_at(time)?.image.as_deref())
+            });
+        let fill =
+            fill_src.and_then(|src| cached_image(&mut self.images, &self.text_renderer, doc, src));
+
+        let mut line_renderer = LineRenderer {
+            canvas,
+            doc,
+            time,
+            text_renderer: &mut self.text_renderer,
+            particle_system: &mut self.particle_system,
+            width: self.width,
+            height: self.height,
+            paints: &mut self.render_paints,
+            audio,
+            region,
+            fill,
+        };
+
+        line_renderer.render_line(
+            line,
+            glyphs,
+            line_idx,
+            style,
+            style_colors,
+            effects,
+            &mut self.line_render_scratch,
+        )?;
+        Ok(LayoutEngine::text_bottom(glyphs))
+    }
+
+    /// Draw the overlays on screen at `time`: text as lines, images and
+    /// shapes with `LineRenderer::render_shape`, through the same style and
+    /// effect resolution
+    fn render_overlays(
+        &mut self,
+        canvas: &Canvas,
+        doc: &KLyricDocumentV2,
+        time: f64,
+        audio: AudioLevels,
+    ) -> Result<()> {
+        if doc.overlays.is_empty() {
+            return Ok(());
+        }
+        let lines = self.overlay_lines(doc);
+        // Keep particle emitter keys and seeds apart from lines and
+        // translations, which take indices below `first_idx`
+        let tracks = doc
+            .lines
+            .iter()
+            .map(|l| l.translations.len())
+            .max()
+            .unwrap_or(0);
+        let first_idx = doc.lines.len() * (tracks + 1);
+
+        for (i, overlay) in doc.active_overlays(time) {
+            let line = &lines[i];
+            let idx = first_idx + i;
+            if overlay.kind == OverlayKind::Text {
+                self.render_line_layer(canvas, doc, time, audio, line, idx, (None, None), None)?;
+                continue;
+            }
+
+            let asset = match (overlay.kind, overlay.src.as_deref()) {
+                (OverlayKind::Image, Some(src)) => {
+                    match cached_image(&mut self.images, &self.text_renderer, doc, src) {
+                        Some(asset) => Some(asset),
+                        None => continue,
+                    }
+                }
+                (OverlayKind::Image, None) => continue,
+                _ => None,
+            };
+
+            let style_name = doc.line_style(line);
+            if !self.style_cache.contains_key(style_name) {
+                let style = StyleResolver::new(doc).resolve(style_name);
+                self.style_cache.insert(style_name.to_string(), style);
+            }
+            let style = self.style_cache.get(style_name).unwrap();
+
+            let line_ptr = line as *const _ as usize;
+            if !self.line_effect_cache.contains_key(&line_ptr) {
+                let presets = &self.particle_system.preset_factory;
+                let effects = Self::resolve_line_effects(doc, line, style, presets);
+                self.line_effect_cache.insert(line_ptr, effects);
+            }
+            let effects = self.line_effect_cache.get(&line_ptr).unwrap();
+
+            if !self.style_color_cache.contains_key(style_name) {
+                let colors = resolve_style_colors(style, |src| {
+                    cached_image(&mut self.images, &self.text_renderer, doc, src)
+                        .and_then(|asset| asset.to_image())
+                });
+                self.style_color_cache
+                    .insert(style_name.to_string(), colors);
+            }
+            let style_colors = self.style_color_cache.get(style_name).unwrap();
+
+            let mut line_renderer = LineRenderer {
+                canvas,
+                doc,
+                time,
+                text_renderer: &mut self.text_renderer,
+                particle_system: &mut self.particle_system,
+                width: self.width,
+                height: self.height,
+                paints: &mut self.render_paints,
+                audio,
+                region: (None, None),
+                fill: None,
+            };
+            line_renderer.render_shape(
+                line,
+                overlay,
+                asset.as_ref(),
+                style,
+                style_colors,
+                effects,
+                &mut self.line_render_scratch,
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether particles are drawn behind or masked by the text this frame.
+    /// Lines and overlays whose effects spawn them count from their first
+    /// frame, before their emitters exist.
+    fn needs_text_layer(
+        &mut self,
+        doc: &KLyricDocumentV2,
+        time: f64,
+        regions: &[(&Line, (Option<f32>, Option<f32>))],
+    ) -> bool {
+        if self.particle_system.has_layer(ParticleLayer::Behind)
+            || self.particle_system.has_layer(ParticleLayer::Masked)
+        {
+            return true;
+        }
+        if regions
+            .iter()
+            .any(|(line, _)| self.spawns_under_text(doc, line))
+        {
+            return true;
+        }
+        if doc.overlays.is_empty() {
+            return false;
+        }
+        let lines = self.overlay_lines(doc);
+        doc.active_overlays(time)
+            .any(|(i, _)| self.spawns_under_text(doc, &lines[i]))
+    }
+
+    /// Lines rendering the document's overlays, built on first use
+    fn overlay_lines(&mut self, doc: &KLyricDocumentV2) -> Arc<[Line]> {
+        self.overlay_lines
+            .get_or_insert_with(|| doc.overlays.iter().map(Overlay::to_line).collect())
+            .clone()
+    }
+
+    /// Whether the line's particle effects draw behind or masked by the
+    /// text. Resolves and caches the line's style and effects.
+    fn spawns_under_text(&mut self, doc: &KLyricDocumentV2, line: &Line) -> bool {
+        let style_name = doc.line_style(line);
+        if !self.style_cache.contains_key(style_name) {
+            let style = StyleResolver::new(doc).resolve(style_name);
+            self.style_cache.insert(style_name.to_string(), style);
+        }
+        let line_ptr = line as *const _ as usize;
+        if !self.line_effect_cache.contains_key(&line_ptr) {
+            let style = &self.style_cache[style_name];
+            let presets = &self.particle_system.preset_factory;
+            let effects = Self::resolve_line_effects(doc, line, style, presets);
+            self.line_effect_cache.insert(line_ptr, effects);
+        }
+        self.line_effect_cache[&line_ptr].under_text
+    }
+
     /// Resolve and categorize effects for a line.
     /// This resolves presets and creates owned Effect copies for caching.
     fn resolve_line_effects(
         doc: &KLyricDocumentV2,
         line: &Line,
         style: &Style,
+        presets: &PresetFactory,
     ) -> CategorizedLineEffects {
         let empty_vec = Vec::new();
         let style_effects = style.effects.as_ref().unwrap_or(&empty_vec);
         let line_effects = &line.effects;
-        let total_effects = style_effects.len() + line_effects.len();
+        let section_effects = doc
+            .section_theme(line.start)
+            .map(|theme| theme.effects.as_slice())
+            .unwrap_or_default();
+        let total_effects = style_effects.len() + line_effects.len() + section_effects.len();
 
         let mut transform_effects: Vec<ResolvedEffect> = Vec::with_capacity(total_effects);
         let mut particle_effects: Vec<(String, ResolvedEffect)> =
@@ -275,13 +737,16 @@ impl Renderer {
         let mut disintegrate_effects: Vec<(String, ResolvedEffect)> = Vec::with_capacity(1);
         let mut stroke_reveal_effects: Vec<Effect> = Vec::with_capacity(1);
 
-        // Collect all effect names: Style effects first (base), then Line effects (override/stack)
-        let all_effects_names = style_effects.iter().chain(line_effects.iter());
+        // Collect all effect names: Style effects first (base), then Line effects (override/stack),
+        // then those the line's section adds
+        let all_effects_names = style_effects
+            .iter()
+            .chain(line_effects.iter())
+            .chain(section_effects.iter());
 
         for effect_name in all_effects_names {
             // Resolve effect (handling presets and references)
-            let effect_resolved: Option<Effect> = if let Some(effect) = doc.effects.get(effect_name)
-            {
+            let effect_resolved: Option<Effect> = if let Some(effect) = doc.effect(effect_name) {
                 if let Some(preset_name) = &effect.preset {
                     if let Some(mut generated) =
                         crate::presets::transitions::get_transition(preset_name)
@@ -326,11 +791,29 @@ impl Renderer {
             }
         }
 
+        // Emitters take the layer of their config, else of their preset
+        let layer = |effect: &Effect| {
+            effect
+                .particle_config
+                .as_ref()
+                .map(|config| config.layer)
+                .or_else(|| {
+                    let definition = presets.definition(effect.preset.as_deref()?)?;
+                    Some(definition.config.layer)
+                })
+                .unwrap_or_default()
+        };
+        let under_text = particle_effects
+            .iter()
+            .chain(&disintegrate_effects)
+            .any(|(_, resolved)| layer(&resolved.effect) != ParticleLayer::Front);
+
         CategorizedLineEffects {
             transform_effects,
             particle_effects,
             disintegrate_effects,
             stroke_reveal_effects,
+            under_text,
         }
     }
 
@@ -465,21 +948,130 @@ impl Renderer {
         self.particle_system.clear();
     }
 
-    fn draw_background(&self, canvas: &Canvas, doc: &KLyricDocumentV2) {
-        if let Some(theme) = &doc.theme {
-            if let Some(bg) = &theme.background {
-                if let Some(hex) = &bg.color {
-                    if let Some(color) = parse_color(hex) {
-                        let sc = Color::from_argb(255, color.r(), color.g(), color.b());
-                        canvas.clear(sc);
-                        return;
-                    }
-                }
-            }
-        }
+    fn draw_background(&self, canvas: &Canvas, doc: &KLyricDocumentV2, time: f64) {
         // Default black
         canvas.clear(Color::BLACK);
+        let Some(bg) = doc.background_at(time) else {
+            return;
+        };
+        let color = bg.color.as_deref().and_then(parse_color);
+        let gradient = bg
+            .gradient
+            .as_ref()
+            .and_then(|gradient| TextFill::gradient(gradient, FillSpan::Line));
+        match (gradient, color) {
+            // A gradient background, or a gradient with no color to fall back on
+            (Some(gradient), color)
+                if matches!(bg.bg_type, BackgroundType::Gradient) || color.is_none() =>
+            {
+                let rect = Rect::from_wh(self.width as f32, self.height as f32);
+                let mut paint = Paint::default();
+                paint.set_shader(gradient.shader(rect));
+                canvas.draw_rect(rect, &paint);
+            }
+            (_, Some(color)) => {
+                canvas.clear(Color::from_argb(255, color.r(), color.g(), color.b()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Pair active lines with their singer's or track's screen region.
+///
+/// A singer's `placement` always applies, then the track's. When several
+/// lines of one track share the screen, the rest split it into columns,
+/// ordered by singer id so each singer keeps its side.
+fn line_regions<'a>(
+    doc: &KLyricDocumentV2,
+    active: &[&'a Line],
+) -> Vec<(&'a Line, (Option<f32>, Option<f32>))> {
+    active
+        .iter()
+        .map(|&line| {
+            let track: Vec<&Line> = active
+                .iter()
+                .copied()
+                .filter(|l| l.track == line.track)
+                .collect();
+            let region = match placement_of(doc, line) {
+                Some(placement) => placement.region(),
+                None if track.len() > 1 => {
+                    let mut auto: Vec<&Line> = track
+                        .into_iter()
+                        .filter(|l| placement_of(doc, l).is_none())
+                        .collect();
+                    auto.sort_by(|a, b| a.singer.cmp(&b.singer));
+                    let column = auto.iter().position(|l| std::ptr::eq(*l, line));
+                    let column = column.unwrap_or(0) as f32;
+                    (Some((column + 0.5) / auto.len() as f32), None)
+                }
+                None => (None, None),
+            };
+            (line, region)
+        })
+        .collect()
+}
+
+/// A decoded image and the modification time of its file when it was
+/// loaded; `asset` is `None` when loading failed
+struct CachedAsset {
+    modified: Option<SystemTime>,
+    asset: Option<OverlayAsset>,
+}
+
+/// Decoded image at `src`, relative to the document. Loaded on first use and
+/// again whenever the file's modification time changes, so a file that was
+/// missing or broken shows up once it's fixed.
+fn cached_image(
+    images: &mut HashMap<PathBuf, CachedAsset>,
+    text_renderer: &TextRenderer,
+    doc: &KLyricDocumentV2,
+    src: &str,
+) -> Option<OverlayAsset> {
+    let path = doc.asset_path(src);
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    match images.get(&path) {
+        Some(cached) if cached.modified == modified => cached.asset.clone(),
+        _ => {
+            let asset = load_asset(&path, text_renderer.font_mgr());
+            let cached = CachedAsset {
+                modified,
+                asset: asset.clone(),
+            };
+            images.insert(path, cached);
+            asset
+        }
+    }
+}
+
+/// Decode an image, SVG by its extension, logging when it can't
+/// be shown. SVG text finds its fonts through `font_mgr`.
+fn load_asset(path: &Path, font_mgr: &FontMgr) -> Option<OverlayAsset> {
+    let is_svg = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("svg"));
+    let asset = std::fs::read(path).ok().and_then(|bytes| {
+        if is_svg {
+            svg::Dom::from_bytes(&bytes, font_mgr.clone())
+                .ok()
+                .map(OverlayAsset::Vector)
+        } else {
+            Image::from_encoded(Data::new_copy(&bytes)).map(OverlayAsset::Raster)
+        }
+    });
+    if asset.is_none() {
+        log::warn!("Failed to load image {}", path.display());
     }
+    asset
+}
+
+fn placement_of(doc: &KLyricDocumentV2, line: &Line) -> Option<crate::model::Placement> {
+    line.singer
+        .as_deref()
+        .and_then(|id| doc.singers.get(id)?.placement)
+        .or_else(|| doc.track_of(line)?.placement)
 }
 
 fn compute_layout_hash(line: &Line, style: &Style) -> u64 {
@@ -515,9 +1107,16 @@ fn compute_layout_hash(line: &Line, style: &Style) -> u64 {
         }
     }
 
+    // Style Ruby
+    if let Some(r) = &style.ruby {
+        r.scale.map(f32::to_bits).hash(&mut hasher);
+        r.gap.map(f32::to_bits).hash(&mut hasher);
+    }
+
     // Chars
     for c in &line.chars {
         c.char.hash(&mut hasher);
+        c.ruby.hash(&mut hasher);
         if let Some(f) = &c.font {
             f.family.hash(&mut hasher);
             if let Some(s) = f.size {
@@ -557,7 +1156,15 @@ mod tests {
             theme: None,
             styles: HashMap::new(),
             effects: HashMap::new(),
+            particle_presets: HashMap::new(),
             lines: Vec::new(),
+            imports: Vec::new(),
+            sections: Vec::new(),
+            singers: HashMap::new(),
+            tracks: HashMap::new(),
+            overlays: Vec::new(),
+            imported: Default::default(),
+            base_dir: None,
         }
     }
 
@@ -574,6 +1181,7 @@ mod tests {
                 opacity: 1.0,
             }),
             default_style: None,
+            sections: HashMap::new(),
         });
         doc
     }
@@ -660,6 +1268,53 @@ mod tests {
         );
     }
 
+    // --- Singer Placement Tests ---
+
+    #[test]
+    fn test_line_regions() {
+        let mut doc = minimal_doc();
+        for id in ["b", "a", "c"] {
+            doc.singers.insert(id.to_string(), Default::default());
+            doc.lines.push(Line {
+                start: 0.0,
+                end: 2.0,
+                singer: Some(id.to_string()),
+                ..Default::default()
+            });
+        }
+        doc.singers.get_mut("c").unwrap().placement = Some(crate::model::Placement::Top);
+
+        let active = doc.get_active_lines(1.0);
+        let regions = line_regions(&doc, &active);
+        // `a` and `b` split the screen in id order; `c` keeps its placement
+        assert_eq!(regions[0].1, (Some(0.75), None));
+        assert_eq!(regions[1].1, (Some(0.25), None));
+        assert_eq!(regions[2].1, (None, Some(0.3)));
+
+        // A singer alone on screen keeps the line's position
+        let regions = line_regions(&doc, &active[..1]);
+        assert_eq!(regions[0].1, (None, None));
+
+        // Lines of another track don't take a column; its placement applies
+        doc.tracks.insert(
+            "credits".to_string(),
+            crate::model::Track {
+                placement: Some(crate::model::Placement::Bottom),
+                ..Default::default()
+            },
+        );
+        doc.lines.push(Line {
+            start: 0.0,
+            end: 2.0,
+            track: Some("credits".to_string()),
+            ..Default::default()
+        });
+        let active = doc.get_active_lines(1.0);
+        let regions = line_regions(&doc, &active);
+        assert_eq!(regions[1].1, (Some(0.25), None));
+        assert_eq!(regions[3].1, (None, Some(0.7)));
+    }
+
     // --- Particle Effect Tests ---
 
     #[test]
@@ -761,6 +1416,9 @@ mod tests {
                 shadow: None,
                 effects: Vec::new(),
                 transform: None,
+                pitch: None,
+                ruby: None,
+                singer: None,
             })
             .collect();
 
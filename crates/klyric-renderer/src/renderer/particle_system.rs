@@ -1,11 +1,17 @@
 use crate::expressions::FastEvaluationContext;
+use crate::particle::budget::cull_to_budget;
 use crate::particle::{
-    color_to_rgba, BlendMode, Particle, ParticleConfig, ParticleEmitter, ParticlePhysics,
-    ParticleShape, RangeValue, SpawnPattern,
+    color_to_rgba, BlendMode, Particle, ParticleConfig, ParticleEmitter, ParticleLayer,
+    ParticleLayerFilter, ParticlePhysics, ParticleShape, ParticleStats, RangeValue, SpawnPattern,
+    SpawnShape,
 };
 use crate::presets::{CharBounds, EffectPreset, PresetFactory};
 use evalexpr::Node;
-use skia_safe::{BlendMode as SkBlendMode, Canvas, Color, Image, Paint, Point, Rect};
+use skia_safe::canvas::SaveLayerRec;
+use skia_safe::image_filters::{self, CropRect};
+use skia_safe::{
+    path::Verb, BlendMode as SkBlendMode, Canvas, Color, Image, Paint, Path, Point, Rect,
+};
 use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
@@ -14,6 +20,12 @@ pub struct ParticleRenderSystem {
     /// Active particle emitters keyed by u64 hash
     pub particle_emitters: HashMap<u64, ParticleEmitter>,
     pub preset_factory: PresetFactory,
+    /// Level-of-detail scale applied to emission counts
+    count_scale: f32,
+    /// Global cap on live particles (None = unlimited)
+    max_particles: Option<usize>,
+    /// Particles culled by the budget during the last update
+    last_culled: usize,
 }
 
 impl Default for ParticleRenderSystem {
@@ -27,6 +39,9 @@ impl ParticleRenderSystem {
         Self {
             particle_emitters: HashMap::new(),
             preset_factory: PresetFactory::new(),
+            count_scale: 1.0,
+            max_particles: None,
+            last_culled: 0,
         }
     }
 
@@ -46,14 +61,165 @@ impl ParticleRenderSystem {
             // Keep if it has particles or it's still active
             !emitter.is_empty() || emitter.active
         });
+
+        self.last_culled = match self.max_particles {
+            Some(max) => cull_to_budget(&mut self.particle_emitters, max),
+            None => 0,
+        };
+    }
+
+    /// Set the level-of-detail scale for emission counts (applies to existing emitters too)
+    pub fn set_count_scale(&mut self, scale: f32) {
+        self.count_scale = scale.clamp(0.0, 1.0);
+        for emitter in self.particle_emitters.values_mut() {
+            emitter.count_scale = self.count_scale;
+        }
+    }
+
+    pub fn count_scale(&self) -> f32 {
+        self.count_scale
+    }
+
+    /// Set the global cap on live particles (None = unlimited)
+    pub fn set_max_particles(&mut self, max_particles: Option<usize>) {
+        self.max_particles = max_particles;
+    }
+
+    /// Live particle and emitter counts
+    pub fn stats(&self) -> ParticleStats {
+        ParticleStats::collect(&self.particle_emitters, self.last_culled)
+    }
+
+    fn insert_emitter(&mut self, key: u64, mut emitter: ParticleEmitter) {
+        emitter.count_scale = self.count_scale;
+        self.particle_emitters.insert(key, emitter);
     }
 
+    /// Render all particles regardless of their layer
     pub fn render(&self, canvas: &Canvas) {
-        for emitter in self.particle_emitters.values() {
-            for particle in &emitter.particles {
-                self.draw_particle(canvas, particle, &emitter.config.blend_mode);
+        self.render_emitters(canvas, self.particle_emitters.values());
+    }
+
+    /// Render the particles assigned to one compositing layer
+    pub fn render_layer(&self, canvas: &Canvas, layer: ParticleLayer) {
+        self.render_emitters(
+            canvas,
+            self.particle_emitters
+                .values()
+                .filter(|e| e.config.layer == layer),
+        );
+    }
+
+    /// Whether any live emitter draws into the given layer
+    pub fn has_layer(&self, layer: ParticleLayer) -> bool {
+        self.particle_emitters
+            .values()
+            .any(|e| e.config.layer == layer && (e.active || !e.particles.is_empty()))
+    }
+
+    /// Draw emitters, batching those that share a blur/bloom filter into one layer pass
+    fn render_emitters<'e>(
+        &self,
+        canvas: &Canvas,
+        emitters: impl Iterator<Item = &'e ParticleEmitter>,
+    ) {
+        // (blend, blur bits, bloom bits) -> emitters
+        let mut filtered: Vec<((BlendMode, u32, u32), Vec<&ParticleEmitter>)> = Vec::new();
+
+        for emitter in emitters {
+            if emitter.particles.is_empty() {
+                continue;
+            }
+            match &emitter.config.filter {
+                Some(f) if f.blur > 0.0 => {
+                    let key = (
+                        emitter.config.blend_mode,
+                        f.blur.to_bits(),
+                        f.bloom.to_bits(),
+                    );
+                    match filtered.iter_mut().find(|(k, _)| *k == key) {
+                        Some((_, group)) => group.push(emitter),
+                        None => filtered.push((key, vec![emitter])),
+                    }
+                }
+                _ => {
+                    for particle in &emitter.particles {
+                        self.draw_particle(canvas, particle, &emitter.config.blend_mode);
+                    }
+                }
             }
         }
+
+        for ((blend_mode, blur_bits, bloom_bits), group) in filtered {
+            let filter = ParticleLayerFilter {
+                blur: f32::from_bits(blur_bits),
+                bloom: f32::from_bits(bloom_bits),
+            };
+            self.draw_filtered_group(canvas, &group, blend_mode, &filter);
+        }
+    }
+
+    /// Blur or bloom a group of emitters as a single offscreen layer
+    fn draw_filtered_group(
+        &self,
+        canvas: &Canvas,
+        group: &[&ParticleEmitter],
+        blend_mode: BlendMode,
+        filter: &ParticleLayerFilter,
+    ) {
+        // Layer bounds: particle extents padded by the blur radius
+        let mut bounds: Option<Rect> = None;
+        for p in group.iter().flat_map(|e| e.particles.iter()) {
+            let r = Rect::from_xywh(p.x - p.size, p.y - p.size, p.size * 2.0, p.size * 2.0);
+            bounds = Some(match bounds {
+                Some(b) => Rect::join2(b, r),
+                None => r,
+            });
+        }
+        let Some(mut bounds) = bounds else {
+            return;
+        };
+        let pad = filter.blur * 3.0;
+        bounds.outset((pad, pad));
+
+        let draw_group = |canvas: &Canvas| {
+            for emitter in group {
+                for particle in &emitter.particles {
+                    self.draw_particle(canvas, particle, &BlendMode::Normal);
+                }
+            }
+        };
+
+        let blur = image_filters::blur(
+            (filter.blur, filter.blur),
+            None,
+            None,
+            CropRect::NO_CROP_RECT,
+        );
+
+        let mut layer_paint = Paint::default();
+        layer_paint.set_blend_mode(sk_blend_mode(blend_mode));
+
+        if filter.bloom > 0.0 {
+            // Sharp particles first
+            canvas.save_layer(&SaveLayerRec::default().bounds(&bounds).paint(&layer_paint));
+            draw_group(canvas);
+            canvas.restore();
+
+            // Additive blurred glow on top
+            let mut glow_paint = Paint::default();
+            glow_paint.set_image_filter(blur);
+            glow_paint.set_blend_mode(SkBlendMode::Plus);
+            glow_paint.set_alpha_f(filter.bloom.clamp(0.0, 1.0));
+            canvas.save_layer(&SaveLayerRec::default().bounds(&bounds).paint(&glow_paint));
+            draw_group(canvas);
+            canvas.restore();
+        } else {
+            layer_paint.set_image_filter(blur);
+            canvas.save_layer(&SaveLayerRec::default().bounds(&bounds).paint(&layer_paint));
+            draw_group(canvas);
+            canvas.restore();
+        }
     }
 
     /// Add a manual particle effect (e.g. for testing)
@@ -65,12 +231,13 @@ impl ParticleRenderSystem {
 
         let mut emitter = self.preset_factory.create_from_enum(preset, &bounds, seed);
         emitter.frame_driven = false; // Manual effects persist until empty/stopped
-        self.particle_emitters.insert(key, emitter);
+        self.insert_emitter(key, emitter);
     }
 
     /// Trigger a burst effect at given position
     pub fn burst_effect(&mut self, preset: EffectPreset, bounds: CharBounds, seed: u64) {
         let mut emitter = self.preset_factory.create_from_enum(preset, &bounds, seed);
+        emitter.count_scale = self.count_scale;
         emitter.burst();
         emitter.frame_driven = false; // Burst effects persist until empty
 
@@ -80,7 +247,7 @@ impl ParticleRenderSystem {
         bounds.x.to_bits().hash(&mut hasher);
         let key = hasher.finish();
 
-        self.particle_emitters.insert(key, emitter);
+        self.insert_emitter(key, emitter);
     }
 
     pub fn has_emitter(&self, key: u64) -> bool {
@@ -166,7 +333,7 @@ impl ParticleRenderSystem {
                     e.update_config(cfg.clone());
                 }
                 e.frame_driven = true; // Text effects are frame driven
-                self.particle_emitters.insert(key, e);
+                self.insert_emitter(key, e);
             }
         } else {
             // Update existing emitter bounds
@@ -181,6 +348,21 @@ impl ParticleRenderSystem {
         }
     }
 
+    /// Whether the emitter exists and is waiting for a glyph shape
+    pub fn emitter_needs_glyph_shape(&self, key: u64) -> bool {
+        self.particle_emitters
+            .get(&key)
+            .map(|e| e.needs_glyph_shape())
+            .unwrap_or(false)
+    }
+
+    /// Attach a glyph shape to an emitter using a glyph spawn pattern
+    pub fn set_emitter_glyph_shape(&mut self, key: u64, shape: Arc<SpawnShape>) {
+        if let Some(emitter) = self.particle_emitters.get_mut(&key) {
+            emitter.set_glyph_shape(shape);
+        }
+    }
+
     /// Create and register a one-shot disintegration emitter from an image
     pub fn ensure_disintegration_emitter(
         &mut self,
@@ -219,6 +401,9 @@ impl ParticleRenderSystem {
                 wind_y: 0.0,
             },
             blend_mode: BlendMode::Normal,
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
         });
 
         // Disable automatic spawning
@@ -234,13 +419,16 @@ impl ParticleRenderSystem {
         // Sampling step - don't spawn a particle for every single pixel, that's too heavy
         // Dynamically adjust step based on size to keep particle count reasonable
         let pixel_count = image.width() * image.height();
-        let step = if pixel_count > 5000 {
+        let base_step = if pixel_count > 5000 {
             3
         } else if pixel_count > 1000 {
             2
         } else {
             1
         };
+        // Lower quality samples a sparser grid (particle count scales with step^2)
+        let lod_step = (1.0 / self.count_scale.max(0.01).sqrt()).round() as usize;
+        let step = base_step * lod_step.max(1);
 
         let pm_w = image.width() as f32;
         let pm_h = image.height() as f32;
@@ -312,11 +500,12 @@ impl ParticleRenderSystem {
             }
         }
 
-        self.particle_emitters.insert(key, emitter);
+        self.insert_emitter(key, emitter);
     }
 
     pub fn clear(&mut self) {
         self.particle_emitters.clear();
+        self.last_culled = 0;
     }
 
     fn draw_particle(&self, canvas: &Canvas, particle: &Particle, blend_mode: &BlendMode) {
@@ -329,17 +518,7 @@ impl ParticleRenderSystem {
         paint.set_color(color);
         paint.set_anti_alias(true);
 
-        match blend_mode {
-            BlendMode::Additive => {
-                paint.set_blend_mode(SkBlendMode::Plus);
-            }
-            BlendMode::Multiply => {
-                paint.set_blend_mode(SkBlendMode::Multiply);
-            }
-            BlendMode::Normal => {
-                paint.set_blend_mode(SkBlendMode::SrcOver);
-            }
-        }
+        paint.set_blend_mode(sk_blend_mode(*blend_mode));
 
         canvas.save();
 
@@ -370,6 +549,59 @@ impl ParticleRenderSystem {
     }
 }
 
+/// Map a particle blend mode to the Skia blend mode
+fn sk_blend_mode(mode: BlendMode) -> SkBlendMode {
+    match mode {
+        BlendMode::Normal => SkBlendMode::SrcOver,
+        BlendMode::Additive => SkBlendMode::Plus,
+        BlendMode::Multiply => SkBlendMode::Multiply,
+        BlendMode::Screen => SkBlendMode::Screen,
+        BlendMode::Overlay => SkBlendMode::Overlay,
+        BlendMode::SoftLight => SkBlendMode::SoftLight,
+    }
+}
+
+/// Flatten a glyph path into a spawn shape in unit coordinates over `bounds`
+pub fn glyph_spawn_shape(path: &Path, bounds: &Rect) -> SpawnShape {
+    let w = bounds.width().max(f32::EPSILON);
+    let h = bounds.height().max(f32::EPSILON);
+    let norm = |p: &Point| ((p.x - bounds.left) / w, (p.y - bounds.top) / h);
+
+    let mut shape = SpawnShape::new();
+    let mut iter = skia_safe::path::Iter::new(path, true);
+    while let Some((verb, pts)) = iter.next() {
+        match verb {
+            Verb::Move => {
+                let (x, y) = norm(&pts[0]);
+                shape.move_to(x, y);
+            }
+            Verb::Line => {
+                let (x, y) = norm(&pts[1]);
+                shape.line_to(x, y);
+            }
+            Verb::Quad => {
+                let (cx, cy) = norm(&pts[1]);
+                let (x, y) = norm(&pts[2]);
+                shape.quad_to(cx, cy, x, y);
+            }
+            Verb::Conic => {
+                let (cx, cy) = norm(&pts[1]);
+                let (x, y) = norm(&pts[2]);
+                shape.conic_to(cx, cy, x, y, iter.conic_weight().unwrap_or(1.0));
+            }
+            Verb::Cubic => {
+                let (c1x, c1y) = norm(&pts[1]);
+                let (c2x, c2y) = norm(&pts[2]);
+                let (x, y) = norm(&pts[3]);
+                shape.cubic_to(c1x, c1y, c2x, c2y, x, y);
+            }
+            Verb::Close => shape.close(),
+            Verb::Done => break,
+        }
+    }
+    shape
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -638,6 +870,9 @@ mod tests {
                 wind_y: 0.0,
             },
             blend_mode: BlendMode::Normal,
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
         };
 
         system.ensure_emitter(key, None, Some(config), bounds, 42);
@@ -691,6 +926,9 @@ mod tests {
                 wind_y: 0.0,
             },
             blend_mode: BlendMode::Normal,
+            priority: 0,
+            layer: ParticleLayer::Front,
+            filter: None,
         };
 
         let emitter = ParticleEmitter::new(config, SpawnPattern::Point { x: 0.0, y: 0.0 }, 42);
@@ -743,4 +981,54 @@ mod tests {
             panic!("Expected Point pattern for Fire preset");
         }
     }
+
+    #[test]
+    fn test_glyph_spawn_shape_attached_to_sparkle() {
+        let mut system = ParticleRenderSystem::new();
+        let bounds = test_bounds();
+        let key = 77;
+
+        system.ensure_emitter(key, Some("sparkle".to_string()), None, bounds, 42);
+        assert!(system.emitter_needs_glyph_shape(key));
+
+        // A square "glyph" occupying the left half of its bounds
+        let glyph_bounds = Rect::from_xywh(0.0, -20.0, 20.0, 20.0);
+        let path = Path::rect(Rect::from_xywh(0.0, -20.0, 10.0, 20.0), None);
+        let shape = Arc::new(glyph_spawn_shape(&path, &glyph_bounds));
+        system.set_emitter_glyph_shape(key, shape);
+        assert!(!system.emitter_needs_glyph_shape(key));
+
+        let emitter = system.particle_emitters.get_mut(&key).unwrap();
+        emitter.burst();
+        for p in &emitter.particles {
+            // Outline of the left half of the char box
+            assert!(p.x >= bounds.x - 0.01 && p.x <= bounds.x + bounds.width / 2.0 + 0.01);
+            assert!(p.y >= bounds.y - 0.01 && p.y <= bounds.y + bounds.height + 0.01);
+        }
+    }
+
+    #[test]
+    fn test_budget_and_stats() {
+        let mut system = ParticleRenderSystem::new();
+        system.set_max_particles(Some(10));
+
+        // Sparkle bursts 12 particles each
+        system.burst_effect(EffectPreset::Sparkle, test_bounds(), 1);
+        system.burst_effect(EffectPreset::Sparkle, test_bounds(), 2);
+        assert_eq!(system.stats().particles, 24);
+
+        system.update(0.001);
+        let stats = system.stats();
+        assert_eq!(stats.particles, 10);
+        assert_eq!(stats.culled, 14);
+        assert_eq!(stats.emitters, 2);
+    }
+
+    #[test]
+    fn test_count_scale_applies_to_new_emitters() {
+        let mut system = ParticleRenderSystem::new();
+        system.set_count_scale(0.5);
+        system.burst_effect(EffectPreset::Sparkle, test_bounds(), 1);
+        assert_eq!(system.stats().particles, 6);
+    }
 }
@@ -1,4 +1,6 @@
-use super::model::{KLyricDocumentV2, Style};
+use super::model::{Import, Imported, KLyricDocumentV2, Style, StyleLibrary};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
 
 /// Resolver for KLyric styles handling inheritance
 pub struct StyleResolver<'a> {
@@ -10,61 +12,104 @@ impl<'a> StyleResolver<'a> {
         Self { doc }
     }
 
-    /// Resolve a style by name, handling inheritance
+    /// Resolve a style by name, handling inheritance.
+    ///
+    /// Each style in the `extends` chain (see `KLyricDocumentV2::style_chain`)
+    /// is applied on top of its parent with `Style::inherit`, so a child that
+    /// only sets `font.size` keeps the parent's family and weight. A chain
+    /// that loops is cut where it first revisits a style.
     pub fn resolve(&self, name: &str) -> Style {
-        let mut resolved = Style::default();
-
-        // If style exists in document
-        if let Some(style) = self.doc.styles.get(name) {
-            // Handle inheritance (recursive)
-            if let Some(ref extends) = style.extends {
-                // Prevent infinite recursion loops by simple depth check or cycle detection?
-                // For MVP, just recursion is okay if we assume valid doc, but let's be safe.
-                // We'll trust the recursion for now but maybe limit depth?
-                // Actually, simple recursion is fine for now.
-                let parent = self.resolve(extends);
-
-                // Merge parent into resolved (parent is base)
-                resolved = parent;
-            }
+        self.doc.resolve_style(name)
+    }
 
-            // Merge current style on top
-            self.merge_style(&mut resolved, style);
-        } else if name == "base" {
-            // Fallback for "base" if not defined? Or just return default.
-            // Usually "base" should be in doc or we assume defaults.
+    /// Load the document's `imports` into `doc.imported`, resolving paths
+    /// relative to `base_dir` (the document's directory).
+    ///
+    /// Imported names are available plain (`title`) and qualified by the
+    /// library namespace (`house.title`). The document's own definitions win
+    /// over imported ones, later imports over earlier ones, and a library
+    /// over the libraries it imports. Inside a library, `extends` and effect
+    /// names that the library defines itself refer to its own definitions.
+    pub fn load_imports(doc: &mut KLyricDocumentV2, base_dir: &Path) -> Result<()> {
+        let mut imported = Imported::default();
+        let mut stack = Vec::new();
+        for import in &doc.imports {
+            load_library(import, base_dir, &mut stack, &mut imported)?;
         }
+        doc.imported = imported;
+        Ok(())
+    }
+}
 
-        resolved
+/// Load one library (after its own imports) into `out`
+fn load_library(
+    import: &Import,
+    base_dir: &Path,
+    stack: &mut Vec<PathBuf>,
+    out: &mut Imported,
+) -> Result<()> {
+    let path = base_dir.join(import.path());
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to find style library {}", path.display()))?;
+
+    if let Some(start) = stack.iter().position(|p| *p == path) {
+        let chain: Vec<String> = stack[start..]
+            .iter()
+            .chain(std::iter::once(&path))
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("Style library import cycle: {}", chain.join(" -> "));
+    }
+    // Shared dependencies are loaded once
+    if out.sources.contains(&path) {
+        return Ok(());
     }
 
-    /// Merge style properties (source overrides target)
-    fn merge_style(&self, target: &mut Style, source: &Style) {
-        if source.font.is_some() {
-            target.font = source.font.clone();
-        }
-        if source.colors.is_some() {
-            target.colors = source.colors.clone();
-        }
-        if source.stroke.is_some() {
-            target.stroke = source.stroke.clone();
-        }
-        if source.shadow.is_some() {
-            target.shadow = source.shadow.clone();
-        }
-        if source.glow.is_some() {
-            target.glow = source.glow.clone();
-        }
-        if source.transform.is_some() {
-            target.transform = source.transform.clone();
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read style library {}", path.display()))?;
+    let library: StyleLibrary = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse style library {}", path.display()))?;
+
+    stack.push(path.clone());
+    let dir = path.parent().unwrap_or(base_dir).to_path_buf();
+    for nested in &library.imports {
+        load_library(nested, &dir, stack, out)?;
+    }
+    stack.pop();
+    out.sources.push(path);
+
+    let namespace = import.namespace();
+    let qualify = |name: &str, own: &dyn Fn(&str) -> bool| {
+        if own(name) {
+            format!("{}.{}", namespace, name)
+        } else {
+            name.to_string()
         }
-        if source.effects.is_some() {
-            target.effects = source.effects.clone();
+    };
+    let own_style = |name: &str| library.styles.contains_key(name);
+    let own_effect = |name: &str| library.effects.contains_key(name);
+
+    for (name, style) in &library.styles {
+        let mut style = style.clone();
+        if let Some(extends) = &style.extends {
+            style.extends = Some(qualify(extends, &own_style));
         }
-        if source.layers.is_some() {
-            target.layers = source.layers.clone();
+        if let Some(effects) = &mut style.effects {
+            for effect in effects.iter_mut() {
+                *effect = qualify(effect, &own_effect);
+            }
         }
+        out.styles
+            .insert(format!("{}.{}", namespace, name), style.clone());
+        out.styles.insert(name.clone(), style);
     }
+    for (name, effect) in &library.effects {
+        out.effects
+            .insert(format!("{}.{}", namespace, name), effect.clone());
+        out.effects.insert(name.clone(), effect.clone());
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -95,7 +140,15 @@ mod tests {
             theme: None,
             styles: HashMap::new(),
             effects: HashMap::new(),
+            particle_presets: HashMap::new(),
             lines: vec![],
+            imports: Vec::new(),
+            sections: Vec::new(),
+            singers: HashMap::new(),
+            tracks: HashMap::new(),
+            overlays: Vec::new(),
+            imported: Default::default(),
+            base_dir: None,
         }
     }
 
@@ -142,10 +195,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -182,16 +241,24 @@ mod tests {
                     inactive: Some(FillStroke {
                         fill: Some("#888888".to_string()),
                         stroke: None,
+                        gradient: None,
+                        pattern: None,
                     }),
                     active: None,
                     complete: None,
                 }),
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -207,10 +274,16 @@ mod tests {
                     color: Some("#000000".to_string()),
                 }),
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -258,11 +331,18 @@ mod tests {
                     x: Some(2.0),
                     y: Some(2.0),
                     blur: Some(4.0),
+                    inset: None,
                 }),
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -281,10 +361,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None, // Inherits from C
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -300,10 +386,16 @@ mod tests {
                     color: Some("#FF0000".to_string()),
                 }),
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -332,7 +424,7 @@ mod tests {
 
     #[test]
     fn test_merge_font() {
-        // Font override behavior: source font completely replaces target font
+        // Fonts merge field by field: unset child fields keep the parent's values
         let mut styles = HashMap::new();
 
         styles.insert(
@@ -349,10 +441,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -370,10 +468,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -383,12 +487,11 @@ mod tests {
         let resolved = resolver.resolve("override");
 
         let font = resolved.font.unwrap();
-        // Source font entirely replaces target font
         assert_eq!(font.family, Some("NewFont".to_string()));
         assert_eq!(font.size, Some(30.0));
-        // Note: weight and letter_spacing are None in source, so they become None
-        assert!(font.weight.is_none());
-        assert!(font.letter_spacing.is_none());
+        // Not specified by the child, so inherited
+        assert_eq!(font.weight, Some(400));
+        assert_eq!(font.letter_spacing, Some(1.0));
     }
 
     #[test]
@@ -405,19 +508,29 @@ mod tests {
                     inactive: Some(FillStroke {
                         fill: Some("#AAAAAA".to_string()),
                         stroke: None,
+                        gradient: None,
+                        pattern: None,
                     }),
                     active: Some(FillStroke {
                         fill: Some("#FFFFFF".to_string()),
                         stroke: None,
+                        gradient: None,
+                        pattern: None,
                     }),
                     complete: None,
                 }),
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -431,18 +544,28 @@ mod tests {
                     active: Some(FillStroke {
                         fill: Some("#00FF00".to_string()),
                         stroke: Some("#000000".to_string()),
+                        gradient: None,
+                        pattern: None,
                     }),
                     complete: Some(FillStroke {
                         fill: Some("#0000FF".to_string()),
                         stroke: None,
+                        gradient: None,
+                        pattern: None,
                     }),
                 }),
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -452,10 +575,11 @@ mod tests {
         let resolved = resolver.resolve("child");
 
         let colors = resolved.colors.unwrap();
-        // Child's colors entirely replace base colors
-        assert!(colors.inactive.is_none()); // Child didn't set inactive
-        assert!(colors.active.is_some());
-        assert_eq!(colors.active.unwrap().fill, Some("#00FF00".to_string()));
+        // Child didn't set inactive, so the base color is kept
+        assert_eq!(colors.inactive.unwrap().fill, Some("#AAAAAA".to_string()));
+        let active = colors.active.unwrap();
+        assert_eq!(active.fill, Some("#00FF00".to_string()));
+        assert_eq!(active.stroke, Some("#000000".to_string()));
         assert!(colors.complete.is_some());
     }
 
@@ -481,10 +605,16 @@ mod tests {
                     color: Some("#111111".to_string()),
                 }),
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -497,14 +627,20 @@ mod tests {
                 colors: None,
                 stroke: None, // Keep base stroke
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: Some(Glow {
                     color: Some("#FFFF00".to_string()),
                     blur: Some(10.0),
                     intensity: Some(0.8),
                 }),
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -558,10 +694,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: Some(vec!["base_effect".to_string()]),
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -574,10 +716,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: Some(vec!["child_effect".to_string()]),
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -590,10 +738,16 @@ mod tests {
                 colors: None,
                 stroke: None,
                 shadow: None,
+                strokes: None,
+                shadows: None,
                 glow: None,
+                mask: None,
                 transform: None,
                 effects: None,
                 layers: None,
+                ruby: None,
+                reset: Vec::new(),
+                append: Vec::new(),
             },
         );
 
@@ -614,4 +768,289 @@ mod tests {
         assert_eq!(effects_inh.len(), 1);
         assert_eq!(effects_inh[0], "base_effect".to_string());
     }
+
+    #[test]
+    fn test_merge_nested_fields() {
+        let doc: KLyricDocumentV2 = serde_json::from_str(
+            r##"{
+                "version": "2.0",
+                "project": { "title": "", "duration": 1, "resolution": { "width": 1, "height": 1 } },
+                "styles": {
+                    "base": {
+                        "colors": { "active": { "fill": "#FFFFFF", "stroke": "#000000" } },
+                        "shadow": { "color": "#000000", "x": 4, "blur": 8 },
+                        "transform": { "scale": 1.2, "rotation": 5 }
+                    },
+                    "child": {
+                        "extends": "base",
+                        "colors": { "active": { "fill": "#FF0000" } },
+                        "shadow": { "y": 6 },
+                        "transform": { "opacity": 0.5 }
+                    }
+                },
+                "lines": []
+            }"##,
+        )
+        .unwrap();
+
+        let resolved = StyleResolver::new(&doc).resolve("child");
+        let active = resolved.colors.unwrap().active.unwrap();
+        assert_eq!(active.fill.as_deref(), Some("#FF0000"));
+        assert_eq!(active.stroke.as_deref(), Some("#000000"));
+        let shadow = resolved.shadow.unwrap();
+        assert_eq!(
+            (shadow.x, shadow.y, shadow.blur),
+            (Some(4.0), Some(6.0), Some(8.0))
+        );
+        let transform = resolved.transform.unwrap();
+        assert_eq!(transform.scale, Some(1.2));
+        assert_eq!(transform.rotation, Some(5.0));
+        assert_eq!(transform.opacity, Some(0.5));
+        // Resolution is the same through the document
+        assert_eq!(doc.resolve_style("child").shadow.unwrap().y, Some(6.0));
+    }
+
+    #[test]
+    fn test_reset_and_append() {
+        let doc: KLyricDocumentV2 = serde_json::from_str(
+            r##"{
+                "version": "2.0",
+                "project": { "title": "", "duration": 1, "resolution": { "width": 1, "height": 1 } },
+                "styles": {
+                    "base": {
+                        "font": { "family": "Serif", "size": 40, "weight": 900 },
+                        "shadow": { "color": "#000000" },
+                        "colors": { "active": { "fill": "#FFFFFF", "stroke": "#000000" } },
+                        "effects": ["fadeIn"],
+                        "layers": [{ "selector": { "mode": "All" }, "modifiers": [] }]
+                    },
+                    "child": {
+                        "extends": "base",
+                        "reset": ["shadow", "font.weight", "colors.active.stroke", "nope.none"],
+                        "font": { "size": 50 },
+                        "effects": ["pop"],
+                        "layers": [{ "selector": { "mode": "All" }, "modifiers": [] }],
+                        "append": ["effects"]
+                    },
+                    "grandchild": {
+                        "extends": "child",
+                        "reset": ["effects"],
+                        "effects": ["wave"],
+                        "append": ["effects", "layers"]
+                    }
+                },
+                "lines": []
+            }"##,
+        )
+        .unwrap();
+        let resolver = StyleResolver::new(&doc);
+
+        let child = resolver.resolve("child");
+        assert!(child.shadow.is_none());
+        let font = child.font.unwrap();
+        assert_eq!(font.family.as_deref(), Some("Serif"));
+        assert_eq!(font.size, Some(50.0));
+        assert!(font.weight.is_none());
+        let active = child.colors.unwrap().active.unwrap();
+        assert_eq!(active.fill.as_deref(), Some("#FFFFFF"));
+        assert!(active.stroke.is_none());
+        assert_eq!(child.effects.unwrap(), vec!["fadeIn", "pop"]);
+        // Layers were not appended, so the child's replace the base's
+        assert_eq!(child.layers.unwrap().len(), 1);
+        // The child's own merge directives are not inherited
+        assert!(child.reset.is_empty() && child.append.is_empty());
+
+        // Reset runs first, so appending to a cleared list starts it over
+        let grandchild = resolver.resolve("grandchild");
+        assert_eq!(grandchild.effects.unwrap(), vec!["wave"]);
+        assert_eq!(grandchild.layers.unwrap().len(), 1);
+    }
+
+    // ========== Import Tests ==========
+
+    /// Fresh directory with a `songs/` subdirectory for documents
+    fn library_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("klyric-styles-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("songs")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_load_imports() {
+        let dir = library_dir("load");
+        std::fs::write(
+            dir.join("common.klyric-styles"),
+            r#"{
+                "styles": { "base": { "font": { "size": 40, "family": "Common" } } },
+                "effects": { "pop": { "type": "transition" } }
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("house.klyric-styles"),
+            r#"{
+                "imports": ["common.klyric-styles"],
+                "styles": {
+                    "base": { "font": { "size": 50 } },
+                    "title": { "extends": "base", "effects": ["pop", "glow"] },
+                    "accent": { "font": { "size": 10 } }
+                },
+                "effects": { "glow": { "type": "transition" } }
+            }"#,
+        )
+        .unwrap();
+
+        let mut doc = minimal_doc();
+        doc.imports = vec![Import::Path("../house.klyric-styles".to_string())];
+        doc.styles.insert(
+            "accent".to_string(),
+            Style {
+                font: Some(Font {
+                    size: Some(99.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+        StyleResolver::load_imports(&mut doc, &dir.join("songs")).unwrap();
+        assert_eq!(doc.imported.sources.len(), 2);
+
+        // Library-internal references point at the library's own definitions
+        let title = doc.style("title").unwrap();
+        assert_eq!(title.extends.as_deref(), Some("house.base"));
+        assert_eq!(
+            title.effects,
+            Some(vec!["pop".to_string(), "house.glow".to_string()])
+        );
+        let resolver = StyleResolver::new(&doc);
+        let font = resolver.resolve("title").font.unwrap();
+        assert_eq!(font.size, Some(50.0));
+
+        // Qualified names reach overridden definitions
+        let common = resolver.resolve("common.base").font.unwrap();
+        assert_eq!(common.family.as_deref(), Some("Common"));
+        assert!(doc.effect("pop").is_some());
+        assert!(doc.effect("house.glow").is_some());
+
+        // The document's own styles win, and imports are not inlined on save
+        assert_eq!(resolver.resolve("accent").font.unwrap().size, Some(99.0));
+        assert_eq!(doc.styles.len(), 1);
+        let json = doc.to_json(false).unwrap();
+        assert!(!json.contains("extends"));
+        assert!(json.contains("house.klyric-styles"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_extends_shadowed_import() {
+        let mut doc = minimal_doc();
+        doc.imported.styles.insert(
+            "title".to_string(),
+            Style {
+                font: Some(Font {
+                    family: Some("House".to_string()),
+                    size: Some(40.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+        // Overrides the imported `title` and builds on it
+        doc.styles.insert(
+            "title".to_string(),
+            Style {
+                extends: Some("title".to_string()),
+                font: Some(Font {
+                    size: Some(80.0),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        );
+
+        let chain = doc.style_chain("title");
+        assert_eq!(chain.links.len(), 2);
+        assert!(chain.cycle.is_none());
+        let font = StyleResolver::new(&doc).resolve("title").font.unwrap();
+        assert_eq!(font.family.as_deref(), Some("House"));
+        assert_eq!(font.size, Some(80.0));
+
+        // Without the import loaded, the chain ends at the override
+        doc.imported.styles.clear();
+        let chain = doc.style_chain("title");
+        assert_eq!(chain.links.len(), 1);
+        assert!(chain.cycle.is_none());
+        let font = StyleResolver::new(&doc).resolve("title").font.unwrap();
+        assert_eq!(font.size, Some(80.0));
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let styles = HashMap::from([
+            (
+                "a".to_string(),
+                Style {
+                    extends: Some("b".to_string()),
+                    stroke: Some(Stroke {
+                        width: Some(2.0),
+                        color: None,
+                    }),
+                    ..Default::default()
+                },
+            ),
+            (
+                "b".to_string(),
+                Style {
+                    extends: Some("a".to_string()),
+                    font: Some(Font {
+                        size: Some(30.0),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let doc = doc_with_styles(styles);
+
+        let chain = doc.style_chain("a");
+        let names: Vec<&str> = chain.links.iter().map(|link| link.name).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(chain.cycle, Some(0));
+
+        // Both styles still resolve, each loop member applied once
+        let resolved = StyleResolver::new(&doc).resolve("a");
+        assert_eq!(resolved.font.unwrap().size, Some(30.0));
+        assert_eq!(resolved.stroke.unwrap().width, Some(2.0));
+    }
+
+    #[test]
+    fn test_import_cycle() {
+        let dir = library_dir("cycle");
+        std::fs::write(
+            dir.join("a.klyric-styles"),
+            r#"{ "imports": ["b.klyric-styles"] }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("b.klyric-styles"),
+            r#"{ "imports": ["a.klyric-styles"] }"#,
+        )
+        .unwrap();
+
+        let mut doc = minimal_doc();
+        doc.imports = vec![Import::Aliased {
+            path: "a.klyric-styles".to_string(),
+            namespace: "a".to_string(),
+        }];
+        let err = StyleResolver::load_imports(&mut doc, &dir).unwrap_err();
+        assert!(err.to_string().contains("import cycle"), "{}", err);
+
+        doc.imports = vec![Import::Path("missing.klyric-styles".to_string())];
+        assert!(StyleResolver::load_imports(&mut doc, &dir).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -109,6 +109,11 @@ impl TextRenderer {
         self.default_typeface.clone()
     }
 
+    /// Font manager that system fonts are matched with
+    pub fn font_mgr(&self) -> &FontMgr {
+        &self.font_mgr
+    }
+
     /// Get a resolved font from cache or create it
     pub fn get_font(&mut self, typeface: &Typeface, size: f32) -> Font {
         self.get_resolved_font(typeface, size).font
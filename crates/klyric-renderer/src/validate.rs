@@ -0,0 +1,1078 @@
+//! Document validation with JSON-path diagnostics
+//!
+//! `validate_json` reports structural problems (wrong types, missing fields)
+//! per JSON path instead of stopping at the first serde error, then runs the
+//! semantic checks of `validate_document` on documents that deserialize.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::expressions::ExpressionEvaluator;
+use crate::model::{
+    AnimatedValue, Background, Effect, EffectLayer, KLyricDocumentV2, Line, Mask, MaskFill,
+    Modifier, Overlay, OverlayKind, Project, Section, Selector, Shadow, Singer, StateColors,
+    Stroke, Style, Theme, Track, APPENDABLE_FIELDS,
+};
+use crate::presets::PresetDefinition;
+
+/// Tolerance for timing comparisons (seconds)
+const TIME_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The document is broken or renders differently than written
+    Error,
+    /// Suspicious but renderable
+    Warning,
+}
+
+/// A single validation finding
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// JSON path of the offending value, e.g. `$.lines[2].chars[0].end`
+    pub path: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            severity: Severity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Append an object key to a JSON path, quoting keys that are not identifiers
+fn key_path(path: &str, key: &str) -> String {
+    let ident = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if ident {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}['{}']", path, key.replace('\'', "\\'"))
+    }
+}
+
+fn index_path(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
+
+// ============== Structural validation ==============
+
+enum Frame {
+    Object(Option<String>),
+    Array(usize),
+}
+
+/// Path of the value slot the scanner is currently in
+fn slot_path(stack: &[Frame]) -> String {
+    let mut path = String::new();
+    for frame in stack {
+        match frame {
+            Frame::Object(Some(key)) => path = key_path(&path, key),
+            Frame::Object(None) => {}
+            Frame::Array(index) => path = index_path(&path, *index),
+        }
+    }
+    path
+}
+
+/// Relative JSON path of the value a serde error at `line`/`column` points to.
+///
+/// serde_json reports the position of the last consumed character, so the
+/// error belongs to the value that ends there, or to the slot being read.
+fn path_at(text: &str, line: usize, column: usize) -> String {
+    let mut offset = 0;
+    for (i, l) in text.split('\n').enumerate() {
+        if i + 1 == line {
+            offset += column.min(l.len());
+            break;
+        }
+        offset += l.len() + 1;
+    }
+    let mut end = offset.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let prefix = &text[..end];
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut last = String::new();
+    let mut expecting_key = false;
+    let mut chars = prefix.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                stack.push(Frame::Object(None));
+                expecting_key = true;
+            }
+            '[' => stack.push(Frame::Array(0)),
+            '}' | ']' => {
+                stack.pop();
+                last = slot_path(&stack);
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Array(index)) => *index += 1,
+                Some(Frame::Object(key)) => {
+                    *key = None;
+                    expecting_key = true;
+                }
+                None => {}
+            },
+            '"' => {
+                let mut s = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                s.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        _ => s.push(c),
+                    }
+                }
+                if expecting_key {
+                    if let Some(Frame::Object(key)) = stack.last_mut() {
+                        *key = Some(s);
+                    }
+                    expecting_key = false;
+                }
+                last = slot_path(&stack);
+            }
+            c if c.is_whitespace() || c == ':' => {}
+            _ => {
+                // Number or literal
+                while chars
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | '}' | ']') && !c.is_whitespace())
+                {
+                    chars.next();
+                }
+                last = slot_path(&stack);
+            }
+        }
+    }
+
+    let ends_value = prefix
+        .trim_end()
+        .ends_with(|c: char| !matches!(c, ':' | ',' | '[' | '{'));
+    if ends_value {
+        last
+    } else {
+        slot_path(&stack)
+    }
+}
+
+/// serde_json error message without its " at line X column Y" suffix
+fn error_message(e: &serde_json::Error) -> String {
+    let message = e.to_string();
+    match message.rfind(" at line ") {
+        Some(i) => message[..i].to_string(),
+        None => message,
+    }
+}
+
+/// Deserialize one section of the document, reporting failures at their JSON path
+fn check_section<T: DeserializeOwned>(value: &Value, path: &str, out: &mut Vec<Diagnostic>) {
+    let text = value.to_string();
+    if let Err(e) = serde_json::from_str::<T>(&text) {
+        let sub = path_at(&text, e.line(), e.column());
+        out.push(Diagnostic::error(
+            format!("{}{}", path, sub),
+            error_message(&e),
+        ));
+    }
+}
+
+/// Check every entry of a named map (`styles`, `effects`, ...) separately
+fn check_map<T: DeserializeOwned>(value: &Value, path: &str, out: &mut Vec<Diagnostic>) {
+    match value.as_object() {
+        Some(entries) => {
+            let mut names: Vec<&String> = entries.keys().collect();
+            names.sort();
+            for name in names {
+                check_section::<T>(&entries[name], &key_path(path, name), out);
+            }
+        }
+        None => check_section::<HashMap<String, T>>(value, path, out),
+    }
+}
+
+/// Validate a KLyric JSON string: structure first, then document semantics.
+///
+/// Unlike `serde_json::from_str`, every malformed section is reported, each
+/// with the JSON path of the offending value.
+pub fn validate_json(json: &str) -> Vec<Diagnostic> {
+    let mut root: Value = match serde_json::from_str(json) {
+        Ok(root) => root,
+        Err(e) => {
+            return vec![Diagnostic::error(
+                "$",
+                format!(
+                    "{} (line {}, column {})",
+                    error_message(&e),
+                    e.line(),
+                    e.column()
+                ),
+            )]
+        }
+    };
+    // Older versions are checked in their upgraded form
+    if root.get("version").is_some_and(Value::is_string) {
+        match crate::migrate::migrate(root) {
+            Ok((migrated, _)) => root = migrated,
+            Err(e) => return vec![Diagnostic::error("$.version", e.to_string())],
+        }
+    }
+    let Some(object) = root.as_object() else {
+        return vec![Diagnostic::error("$", "expected a JSON object")];
+    };
+
+    let mut out = Vec::new();
+    for field in ["version", "project", "lines"] {
+        if !object.contains_key(field) {
+            out.push(Diagnostic::error("$", format!("missing field `{}`", field)));
+        }
+    }
+    if let Some(version) = object.get("version") {
+        check_section::<String>(version, "$.version", &mut out);
+    }
+    if let Some(project) = object.get("project") {
+        check_section::<Project>(project, "$.project", &mut out);
+    }
+    if let Some(theme) = object.get("theme") {
+        check_section::<Option<Theme>>(theme, "$.theme", &mut out);
+    }
+    if let Some(styles) = object.get("styles") {
+        check_map::<Style>(styles, "$.styles", &mut out);
+    }
+    if let Some(effects) = object.get("effects") {
+        check_map::<Effect>(effects, "$.effects", &mut out);
+    }
+    if let Some(singers) = object.get("singers") {
+        check_map::<Singer>(singers, "$.singers", &mut out);
+    }
+    if let Some(tracks) = object.get("tracks") {
+        check_map::<Track>(tracks, "$.tracks", &mut out);
+    }
+    if let Some(sections) = object.get("sections") {
+        check_section::<Vec<Section>>(sections, "$.sections", &mut out);
+    }
+    if let Some(overlays) = object.get("overlays") {
+        check_section::<Vec<Overlay>>(overlays, "$.overlays", &mut out);
+    }
+    if let Some(presets) = object.get("particlePresets") {
+        check_map::<PresetDefinition>(presets, "$.particlePresets", &mut out);
+    }
+    if let Some(lines) = object.get("lines") {
+        match lines.as_array() {
+            Some(lines) => {
+                for (i, line) in lines.iter().enumerate() {
+                    check_section::<Line>(line, &index_path("$.lines", i), &mut out);
+                }
+            }
+            None => check_section::<Vec<Line>>(lines, "$.lines", &mut out),
+        }
+    }
+
+    if !out.is_empty() {
+        return out;
+    }
+    match serde_json::from_value::<KLyricDocumentV2>(root) {
+        Ok(doc) => validate_document(&doc),
+        // Anything the per-section checks above did not catch
+        Err(e) => {
+            let sub = path_at(json, e.line(), e.column());
+            vec![Diagnostic::error(format!("${}", sub), error_message(&e))]
+        }
+    }
+}
+
+// ============== Semantic validation ==============
+
+struct Validator<'a> {
+    doc: &'a KLyricDocumentV2,
+    out: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn color(&mut self, path: String, color: Option<&String>) {
+        if let Some(color) = color {
+            if crate::utils::parse_hex_color(color).is_none() {
+                self.out.push(Diagnostic::error(
+                    path,
+                    format!("invalid color '{}' (expected #RRGGBB or #RRGGBBAA)", color),
+                ));
+            }
+        }
+    }
+
+    fn expression(&mut self, path: String, expression: &str) {
+        if !ExpressionEvaluator::validate(expression) {
+            self.out.push(Diagnostic::error(
+                path,
+                format!("invalid expression '{}'", expression),
+            ));
+        }
+    }
+
+    /// Report a dangling reference; it may still come from imports that
+    /// were not loaded (see `StyleResolver::load_imports`)
+    fn unknown(&mut self, path: String, message: String) {
+        let unresolved_imports =
+            !self.doc.imports.is_empty() && self.doc.imported.sources.is_empty();
+        self.out.push(if unresolved_imports {
+            Diagnostic::warning(path, format!("{} (imports not loaded)", message))
+        } else {
+            Diagnostic::error(path, message)
+        });
+    }
+
+    fn style_ref(&mut self, path: String, name: Option<&String>) {
+        if let Some(name) = name {
+            if self.doc.style(name).is_none() {
+                self.unknown(path, format!("unknown style '{}'", name));
+            }
+        }
+    }
+
+    fn singer_ref(&mut self, path: String, id: Option<&String>) {
+        if let Some(id) = id {
+            if !self.doc.singers.contains_key(id) {
+                self.out
+                    .push(Diagnostic::error(path, format!("unknown singer '{}'", id)));
+            }
+        }
+    }
+
+    fn singers(&mut self) {
+        let doc = self.doc;
+        let mut ids: Vec<&String> = doc.singers.keys().collect();
+        ids.sort();
+        for id in ids {
+            let singer = &doc.singers[id];
+            let path = key_path("$.singers", id);
+            self.style_ref(key_path(&path, "style"), singer.style.as_ref());
+            self.color(key_path(&path, "color"), singer.color.as_ref());
+        }
+    }
+
+    fn track_ref(&mut self, path: String, id: Option<&String>) {
+        if let Some(id) = id {
+            if !self.doc.tracks.contains_key(id) {
+                self.out
+                    .push(Diagnostic::error(path, format!("unknown track '{}'", id)));
+            }
+        }
+    }
+
+    fn tracks(&mut self) {
+        let doc = self.doc;
+        let mut ids: Vec<&String> = doc.tracks.keys().collect();
+        ids.sort();
+        for id in ids {
+            let path = key_path("$.tracks", id);
+            self.style_ref(key_path(&path, "style"), doc.tracks[id].style.as_ref());
+        }
+    }
+
+    fn effect_refs(&mut self, path: &str, names: &[String]) {
+        for (i, name) in names.iter().enumerate() {
+            let known = self.doc.effect(name).is_some()
+                || crate::presets::transitions::get_transition(name).is_some();
+            if !known {
+                self.unknown(index_path(path, i), format!("unknown effect '{}'", name));
+            }
+        }
+    }
+
+    fn stroke(&mut self, path: &str, stroke: Option<&Stroke>) {
+        if let Some(stroke) = stroke {
+            self.color(key_path(path, "color"), stroke.color.as_ref());
+        }
+    }
+
+    fn shadow(&mut self, path: &str, shadow: Option<&Shadow>) {
+        if let Some(shadow) = shadow {
+            self.color(key_path(path, "color"), shadow.color.as_ref());
+        }
+    }
+
+    fn mask(&mut self, path: &str, mask: Option<&Mask>) {
+        let Some(mask) = mask else {
+            return;
+        };
+        let any_background = |f: fn(&Background) -> bool| {
+            self.doc.theme.as_ref().is_some_and(|theme| {
+                theme.background.as_ref().is_some_and(f)
+                    || theme
+                        .sections
+                        .values()
+                        .any(|s| s.background.as_ref().is_some_and(f))
+            })
+        };
+        let background_image = any_background(|b| b.image.is_some());
+        if mask.fill == MaskFill::Background && mask.src.is_none() && !background_image {
+            let message = if any_background(|b| b.video.is_some()) {
+                "`background` fill can't show a video background; set `src` to an image"
+            } else {
+                "`background` fill without `src` or a background image shows the text's colors"
+            };
+            self.out
+                .push(Diagnostic::warning(key_path(path, "fill"), message));
+        }
+        if mask.invert && mask.shape.is_none() {
+            self.out.push(Diagnostic::warning(
+                key_path(path, "invert"),
+                "`invert` has no effect without a `shape`",
+            ));
+        }
+    }
+
+    fn state_colors(&mut self, path: &str, colors: &StateColors) {
+        for (state, fill_stroke) in [
+            ("inactive", &colors.inactive),
+            ("active", &colors.active),
+            ("complete", &colors.complete),
+        ] {
+            if let Some(fill_stroke) = fill_stroke {
+                let state_path = key_path(path, state);
+                self.color(key_path(&state_path, "fill"), fill_stroke.fill.as_ref());
+                self.color(key_path(&state_path, "stroke"), fill_stroke.stroke.as_ref());
+                if let Some(gradient) = fill_stroke.gradient.as_ref().map(|g| &g.gradient) {
+                    let gradient_path = key_path(&state_path, "gradient");
+                    let colors_path = key_path(&gradient_path, "colors");
+                    for (i, color) in gradient.colors.iter().enumerate() {
+                        self.color(index_path(&colors_path, i), Some(color));
+                    }
+                    if gradient.colors.len() < 2 {
+                        self.out.push(Diagnostic::warning(
+                            colors_path,
+                            "a gradient needs at least two colors; the solid `fill` is used",
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    fn layers(&mut self, path: &str, layers: &[EffectLayer]) {
+        for (i, layer) in layers.iter().enumerate() {
+            if let Selector::Section(name) = &layer.selector {
+                if !self
+                    .doc
+                    .sections
+                    .iter()
+                    .any(|section| &section.name == name)
+                {
+                    self.out.push(Diagnostic::warning(
+                        key_path(&index_path(path, i), "selector"),
+                        format!("no section is named '{}'", name),
+                    ));
+                }
+            }
+            let layer_path = key_path(&index_path(path, i), "modifiers");
+            for (j, modifier) in layer.modifiers.iter().enumerate() {
+                if let Modifier::Color(params) = modifier {
+                    let params_path = key_path(&index_path(&layer_path, j), "params");
+                    self.color(key_path(&params_path, "fill"), params.fill.as_ref());
+                    self.color(key_path(&params_path, "stroke"), params.stroke.as_ref());
+                }
+            }
+        }
+    }
+
+    /// `extends` chain from `name` when it loops back to `name`, the same
+    /// walk `resolve_style` takes
+    fn extends_cycle(&self, name: &str) -> Option<Vec<String>> {
+        let chain = self.doc.style_chain(name);
+        // A loop further up the chain is reported on its own members
+        if chain.cycle != Some(0) {
+            return None;
+        }
+        let mut names: Vec<String> = chain.links.iter().map(|l| l.name.to_string()).collect();
+        names.push(name.to_string());
+        Some(names)
+    }
+
+    fn styles(&mut self) {
+        let doc = self.doc;
+        let mut names: Vec<&String> = doc.styles.keys().collect();
+        names.sort();
+        for name in names {
+            let style = &doc.styles[name];
+            let path = key_path("$.styles", name);
+            let extends_path = key_path(&path, "extends");
+            self.style_ref(extends_path.clone(), style.extends.as_ref());
+            if let Some(chain) = self.extends_cycle(name) {
+                self.out.push(Diagnostic::error(
+                    extends_path,
+                    format!("extends cycle: {}", chain.join(" -> ")),
+                ));
+            }
+            if let Some(colors) = &style.colors {
+                self.state_colors(&key_path(&path, "colors"), colors);
+            }
+            self.stroke(&key_path(&path, "stroke"), style.stroke.as_ref());
+            self.shadow(&key_path(&path, "shadow"), style.shadow.as_ref());
+            for (i, stroke) in style.strokes.iter().flatten().enumerate() {
+                self.stroke(&index_path(&key_path(&path, "strokes"), i), Some(stroke));
+            }
+            for (i, shadow) in style.shadows.iter().flatten().enumerate() {
+                self.shadow(&index_path(&key_path(&path, "shadows"), i), Some(shadow));
+            }
+            self.mask(&key_path(&path, "mask"), style.mask.as_ref());
+            if let Some(glow) = &style.glow {
+                self.color(
+                    key_path(&key_path(&path, "glow"), "color"),
+                    glow.color.as_ref(),
+                );
+            }
+            if let Some(effects) = &style.effects {
+                self.effect_refs(&key_path(&path, "effects"), effects);
+            }
+            if let Some(layers) = &style.layers {
+                self.layers(&key_path(&path, "layers"), layers);
+            }
+            for (i, field) in style.append.iter().enumerate() {
+                if !APPENDABLE_FIELDS.contains(&field.as_str()) {
+                    self.out.push(Diagnostic::warning(
+                        index_path(&key_path(&path, "append"), i),
+                        format!("cannot append to '{}'", field),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn effects(&mut self) {
+        let doc = self.doc;
+        let mut names: Vec<&String> = doc.effects.keys().collect();
+        names.sort();
+        for name in names {
+            let effect = &doc.effects[name];
+            let path = key_path("$.effects", name);
+            if effect.duration.is_some_and(|d| d < 0.0) {
+                self.out.push(Diagnostic::error(
+                    key_path(&path, "duration"),
+                    "negative duration",
+                ));
+            }
+            if effect.delay < 0.0 {
+                self.out.push(Diagnostic::error(
+                    key_path(&path, "delay"),
+                    "negative delay",
+                ));
+            }
+
+            let properties_path = key_path(&path, "properties");
+            let mut properties: Vec<(&String, &AnimatedValue)> = effect.properties.iter().collect();
+            properties.sort_by_key(|(k, _)| *k);
+            for (property, value) in properties {
+                if let AnimatedValue::Expression(expression) = value {
+                    self.expression(key_path(&properties_path, property), expression);
+                }
+            }
+            if let Some(overrides) = &effect.particle_override {
+                let overrides_path = key_path(&path, "particleOverride");
+                let mut overrides: Vec<(&String, &String)> = overrides.iter().collect();
+                overrides.sort();
+                for (property, expression) in overrides {
+                    self.expression(key_path(&overrides_path, property), expression);
+                }
+            }
+
+            let keyframes_path = key_path(&path, "keyframes");
+            for (i, keyframe) in effect.keyframes.iter().enumerate() {
+                self.color(
+                    key_path(&index_path(&keyframes_path, i), "color"),
+                    keyframe.color.as_ref(),
+                );
+            }
+            if let Some(config) = &effect.particle_config {
+                self.color(
+                    key_path(&key_path(&path, "particleConfig"), "color"),
+                    Some(&config.color),
+                );
+            }
+        }
+
+        let mut presets: Vec<(&String, &PresetDefinition)> = doc.particle_presets.iter().collect();
+        presets.sort_by_key(|(k, _)| *k);
+        for (name, preset) in presets {
+            let config_path = key_path(&key_path("$.particlePresets", name), "config");
+            self.color(key_path(&config_path, "color"), Some(&preset.config.color));
+        }
+    }
+
+    fn lines(&mut self) {
+        let doc = self.doc;
+        for (i, line) in doc.lines.iter().enumerate() {
+            let path = index_path("$.lines", i);
+            if line.end < line.start {
+                self.out.push(Diagnostic::error(
+                    key_path(&path, "end"),
+                    format!(
+                        "negative duration: line ends at {} before it starts at {}",
+                        line.end, line.start
+                    ),
+                ));
+            }
+            self.style_ref(key_path(&path, "style"), line.style.as_ref());
+            self.singer_ref(key_path(&path, "singer"), line.singer.as_ref());
+            self.track_ref(key_path(&path, "track"), line.track.as_ref());
+            self.effect_refs(&key_path(&path, "effects"), &line.effects);
+            self.stroke(&key_path(&path, "stroke"), line.stroke.as_ref());
+            self.shadow(&key_path(&path, "shadow"), line.shadow.as_ref());
+            self.mask(&key_path(&path, "mask"), line.mask.as_ref());
+            let translations_path = key_path(&path, "translations");
+            for (k, translation) in line.translations.iter().enumerate() {
+                let style_path = key_path(&index_path(&translations_path, k), "style");
+                self.style_ref(style_path, translation.style.as_ref());
+            }
+
+            let chars_path = key_path(&path, "chars");
+            let mut previous_end: Option<f64> = None;
+            for (j, ch) in line.chars.iter().enumerate() {
+                let char_path = index_path(&chars_path, j);
+                if ch.end < ch.start {
+                    self.out.push(Diagnostic::error(
+                        key_path(&char_path, "end"),
+                        format!(
+                            "negative duration: char ends at {} before it starts at {}",
+                            ch.end, ch.start
+                        ),
+                    ));
+                }
+                if ch.start < line.start - TIME_EPSILON || ch.end > line.end + TIME_EPSILON {
+                    self.out.push(Diagnostic::warning(
+                        char_path.clone(),
+                        format!(
+                            "char timing {}-{} is outside the line range {}-{}",
+                            ch.start, ch.end, line.start, line.end
+                        ),
+                    ));
+                }
+                if let Some(previous_end) = previous_end {
+                    if ch.start < previous_end - TIME_EPSILON {
+                        self.out.push(Diagnostic::warning(
+                            key_path(&char_path, "start"),
+                            format!(
+                                "char starts at {} before the previous char ends at {}",
+                                ch.start, previous_end
+                            ),
+                        ));
+                    }
+                }
+                previous_end = Some(ch.end);
+
+                self.style_ref(key_path(&char_path, "style"), ch.style.as_ref());
+                self.singer_ref(key_path(&char_path, "singer"), ch.singer.as_ref());
+                self.effect_refs(&key_path(&char_path, "effects"), &ch.effects);
+                self.stroke(&key_path(&char_path, "stroke"), ch.stroke.as_ref());
+                self.shadow(&key_path(&char_path, "shadow"), ch.shadow.as_ref());
+            }
+        }
+    }
+
+    fn background(&mut self, path: &str, background: Option<&Background>) {
+        let Some(background) = background else {
+            return;
+        };
+        self.color(key_path(path, "color"), background.color.as_ref());
+        if let Some(gradient) = &background.gradient {
+            let colors_path = key_path(&key_path(path, "gradient"), "colors");
+            for (i, color) in gradient.colors.iter().enumerate() {
+                self.color(index_path(&colors_path, i), Some(color));
+            }
+        }
+    }
+
+    fn theme(&mut self) {
+        let doc = self.doc;
+        let Some(theme) = &doc.theme else {
+            return;
+        };
+        self.style_ref(
+            "$.theme.defaultStyle".to_string(),
+            theme.default_style.as_ref(),
+        );
+        self.background("$.theme.background", theme.background.as_ref());
+
+        let mut names: Vec<&String> = theme.sections.keys().collect();
+        names.sort();
+        for name in names {
+            let overrides = &theme.sections[name];
+            let path = key_path("$.theme.sections", name);
+            if !doc.sections.iter().any(|section| &section.name == name) {
+                self.out.push(Diagnostic::warning(
+                    path.clone(),
+                    format!("no section is named '{}'", name),
+                ));
+            }
+            self.style_ref(
+                key_path(&path, "defaultStyle"),
+                overrides.default_style.as_ref(),
+            );
+            self.background(
+                &key_path(&path, "background"),
+                overrides.background.as_ref(),
+            );
+            self.effect_refs(&key_path(&path, "effects"), &overrides.effects);
+        }
+    }
+
+    fn overlays(&mut self) {
+        let doc = self.doc;
+        for (i, overlay) in doc.overlays.iter().enumerate() {
+            let path = index_path("$.overlays", i);
+            if overlay.end < overlay.start {
+                self.out.push(Diagnostic::error(
+                    key_path(&path, "end"),
+                    format!(
+                        "negative duration: overlay ends at {} before it starts at {}",
+                        overlay.end, overlay.start
+                    ),
+                ));
+            }
+            let missing = match overlay.kind {
+                OverlayKind::Text => overlay.text.is_none().then_some("text"),
+                OverlayKind::Image => overlay.src.is_none().then_some("src"),
+                OverlayKind::Rect | OverlayKind::Ellipse => {
+                    if overlay.width.is_none() {
+                        Some("width")
+                    } else {
+                        overlay.height.is_none().then_some("height")
+                    }
+                }
+            };
+            if let Some(field) = missing {
+                self.out.push(Diagnostic::warning(
+                    path.clone(),
+                    format!("overlay without `{}` is not drawn", field),
+                ));
+            }
+            self.style_ref(key_path(&path, "style"), overlay.style.as_ref());
+            self.effect_refs(&key_path(&path, "effects"), &overlay.effects);
+        }
+    }
+
+    fn sections(&mut self) {
+        let doc = self.doc;
+        for (i, section) in doc.sections.iter().enumerate() {
+            if section.end < section.start {
+                self.out.push(Diagnostic::error(
+                    key_path(&index_path("$.sections", i), "end"),
+                    format!(
+                        "negative duration: section ends at {} before it starts at {}",
+                        section.end, section.start
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Check a parsed document for problems serde cannot see: dangling style,
+/// singer, track and effect references, `extends` cycles, bad timing, colors
+/// and expressions.
+pub fn validate_document(doc: &KLyricDocumentV2) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        doc,
+        out: Vec::new(),
+    };
+
+    if !crate::migrate::is_supported(&doc.version) {
+        validator.out.push(Diagnostic::error(
+            "$.version",
+            format!("unsupported KLyric version '{}'", doc.version),
+        ));
+    }
+    if doc.project.duration < 0.0 {
+        validator
+            .out
+            .push(Diagnostic::error("$.project.duration", "negative duration"));
+    }
+    validator.theme();
+    validator.sections();
+    validator.overlays();
+    validator.styles();
+    validator.singers();
+    validator.tracks();
+    validator.effects();
+    validator.lines();
+    validator.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r##"{
+        "version": "2.0",
+        "project": { "title": "t", "duration": 10, "resolution": { "width": 1920, "height": 1080 } },
+        "styles": {
+            "base": { "colors": { "inactive": "#FFFFFF" } },
+            "a": { "extends": "b" },
+            "b": { "extends": "a" },
+            "bad color": { "shadow": { "color": "rgba(0,0,0,0.5)" } },
+            "c": { "extends": "base", "append": ["effects", "font"] },
+            "d": { "layers": [{ "selector": { "mode": "Section", "args": "bridge" }, "modifiers": [] }] },
+            "e": { "mask": { "shape": "rect", "fill": "background" } },
+            "f": { "colors": { "active": { "gradient": { "colors": ["blue"] } } } },
+            "g": { "strokes": [{ "width": 10, "color": "#000000" }], "shadows": [{ "color": "black", "inset": true }], "append": ["strokes"] }
+        },
+        "effects": {
+            "pop": { "type": "transition", "duration": -1, "properties": { "scale": "1 +" } }
+        },
+        "singers": { "lead": { "style": "nope", "color": "red" } },
+        "tracks": { "credits": { "style": "nope" } },
+        "sections": [{ "name": "chorus", "start": 4, "end": 2 }],
+        "overlays": [
+            { "start": 0, "end": 2, "text": "Title", "style": "nope", "effects": ["fade"] },
+            { "type": "rect", "start": 0, "end": 2, "width": 100 }
+        ],
+        "theme": {
+            "sections": {
+                "chorus": { "defaultStyle": "nope", "effects": ["nope"] },
+                "outro": { "background": { "color": "blue" } }
+            }
+        },
+        "lines": [
+            {
+                "start": 1, "end": 3, "style": "missing", "effects": ["pop", "fade", "nope"],
+                "singer": "ghost", "track": "backing", "mask": { "invert": true },
+                "translations": [{ "text": "x", "style": "gone" }, { "text": "y" }],
+                "chars": [
+                    { "char": "a", "start": 1, "end": 2 },
+                    { "char": "b", "start": 1.5, "end": 1.2 },
+                    { "char": "c", "start": 2.5, "end": 3.5 }
+                ]
+            }
+        ]
+    }"##;
+
+    fn find<'a>(diagnostics: &'a [Diagnostic], path: &str) -> Vec<&'a Diagnostic> {
+        diagnostics.iter().filter(|d| d.path == path).collect()
+    }
+
+    #[test]
+    fn test_validate_document() {
+        let doc: KLyricDocumentV2 = serde_json::from_str(DOC).unwrap();
+        let diagnostics = validate_document(&doc);
+
+        let cycle = find(&diagnostics, "$.styles.a.extends");
+        assert_eq!(cycle.len(), 1);
+        assert_eq!(cycle[0].message, "extends cycle: a -> b -> a");
+        assert_eq!(find(&diagnostics, "$.styles.b.extends").len(), 1);
+        assert_eq!(
+            find(&diagnostics, "$.styles['bad color'].shadow.color").len(),
+            1
+        );
+        assert_eq!(find(&diagnostics, "$.effects.pop.duration").len(), 1);
+        assert!(find(&diagnostics, "$.styles.g.strokes[0].color").is_empty());
+        assert_eq!(find(&diagnostics, "$.styles.g.shadows[0].color").len(), 1);
+        assert!(find(&diagnostics, "$.styles.g.append[0]").is_empty());
+        assert_eq!(
+            find(&diagnostics, "$.styles.f.colors.active.gradient.colors[0]")[0].severity,
+            Severity::Error
+        );
+        assert_eq!(
+            find(&diagnostics, "$.styles.f.colors.active.gradient.colors")[0].severity,
+            Severity::Warning
+        );
+        assert!(find(&diagnostics, "$.styles.c.append[0]").is_empty());
+        assert_eq!(
+            find(&diagnostics, "$.styles.c.append[1]")[0].message,
+            "cannot append to 'font'"
+        );
+        assert_eq!(
+            find(&diagnostics, "$.effects.pop.properties.scale")[0].message,
+            "invalid expression '1 +'"
+        );
+
+        assert_eq!(
+            find(&diagnostics, "$.lines[0].style")[0].message,
+            "unknown style 'missing'"
+        );
+        assert_eq!(
+            find(&diagnostics, "$.lines[0].singer")[0].message,
+            "unknown singer 'ghost'"
+        );
+        assert_eq!(find(&diagnostics, "$.singers.lead.style").len(), 1);
+        assert_eq!(
+            find(&diagnostics, "$.lines[0].track")[0].message,
+            "unknown track 'backing'"
+        );
+        assert_eq!(find(&diagnostics, "$.tracks.credits.style").len(), 1);
+        assert_eq!(find(&diagnostics, "$.singers.lead.color").len(), 1);
+        assert_eq!(find(&diagnostics, "$.sections[0].end").len(), 1);
+        assert_eq!(find(&diagnostics, "$.overlays[0].style").len(), 1);
+        assert!(find(&diagnostics, "$.overlays[0].effects[0]").is_empty());
+        assert_eq!(
+            find(&diagnostics, "$.overlays[1]")[0].message,
+            "overlay without `height` is not drawn"
+        );
+        assert!(find(&diagnostics, "$.theme.sections.chorus").is_empty());
+        assert_eq!(
+            find(&diagnostics, "$.theme.sections.outro")[0].severity,
+            Severity::Warning
+        );
+        assert_eq!(
+            find(&diagnostics, "$.theme.sections.outro.background.color").len(),
+            1
+        );
+        assert_eq!(
+            find(&diagnostics, "$.theme.sections.chorus.defaultStyle").len(),
+            1
+        );
+        assert_eq!(
+            find(&diagnostics, "$.theme.sections.chorus.effects[0]").len(),
+            1
+        );
+        assert_eq!(
+            find(&diagnostics, "$.styles.d.layers[0].selector")[0].message,
+            "no section is named 'bridge'"
+        );
+        assert_eq!(find(&diagnostics, "$.styles.e.mask.fill").len(), 1);
+        assert_eq!(find(&diagnostics, "$.lines[0].mask.invert").len(), 1);
+        assert_eq!(
+            find(&diagnostics, "$.lines[0].translations[0].style")[0].message,
+            "unknown style 'gone'"
+        );
+        // Built-in transitions resolve by name
+        assert!(find(&diagnostics, "$.lines[0].effects[1]").is_empty());
+        assert_eq!(find(&diagnostics, "$.lines[0].effects[2]").len(), 1);
+
+        let negative = find(&diagnostics, "$.lines[0].chars[1].end");
+        assert_eq!(negative[0].severity, Severity::Error);
+        let overlap = find(&diagnostics, "$.lines[0].chars[1].start");
+        assert_eq!(overlap[0].severity, Severity::Warning);
+        let outside = find(&diagnostics, "$.lines[0].chars[2]");
+        assert_eq!(outside[0].severity, Severity::Warning);
+        assert!(find(&diagnostics, "$.lines[0].chars[0]").is_empty());
+    }
+
+    #[test]
+    fn test_mask_fill_with_video_background() {
+        let doc: KLyricDocumentV2 = serde_json::from_str(
+            r#"{
+                "version": "2.0",
+                "project": { "title": "t", "duration": 10, "resolution": { "width": 1920, "height": 1080 } },
+                "theme": { "background": { "type": "video", "video": "clip.mp4" } },
+                "styles": {
+                    "base": { "mask": { "fill": "background" } },
+                    "still": { "mask": { "fill": "background", "src": "still.png" } }
+                },
+                "lines": []
+            }"#,
+        )
+        .unwrap();
+        let diagnostics = validate_document(&doc);
+        assert_eq!(
+            find(&diagnostics, "$.styles.base.mask.fill")[0].message,
+            "`background` fill can't show a video background; set `src` to an image"
+        );
+        assert!(find(&diagnostics, "$.styles.still.mask.fill").is_empty());
+    }
+
+    #[test]
+    fn test_extends_shadowing_is_not_a_cycle() {
+        let mut doc: KLyricDocumentV2 = serde_json::from_str(
+            r#"{
+                "version": "2.0",
+                "project": { "title": "t", "duration": 10, "resolution": { "width": 1920, "height": 1080 } },
+                "styles": { "title": { "extends": "title" }, "x": { "extends": "a" } },
+                "lines": []
+            }"#,
+        )
+        .unwrap();
+        doc.imported
+            .styles
+            .insert("title".to_string(), Default::default());
+        // Imported, and looping among themselves
+        for (name, parent) in [("a", "b"), ("b", "a")] {
+            doc.imported.styles.insert(
+                name.to_string(),
+                crate::model::Style {
+                    extends: Some(parent.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        let diagnostics = validate_document(&doc);
+        assert!(find(&diagnostics, "$.styles.title.extends").is_empty());
+        // The loop is in the library, not in `x`
+        assert!(find(&diagnostics, "$.styles.x.extends").is_empty());
+    }
+
+    #[test]
+    fn test_validate_imported_document() {
+        let lrc = "[ti:Song]\n[00:01.00]Hello world\n[00:03.00]Again\n";
+        let ass = "[Script Info]\nPlayResX: 1920\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,{\\k50}Hel{\\k50}lo\n";
+        for (content, name) in [(lrc, "a.lrc"), (ass, "a.ass")] {
+            let doc = crate::importer::import_subtitle(content, Some(name)).unwrap();
+            assert_eq!(validate_document(&doc), vec![], "{}", name);
+            let json = doc.to_json(false).unwrap();
+            assert_eq!(validate_json(&json), vec![], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_validate_json_reports_paths() {
+        let json = r##"{
+            "version": "2.0",
+            "project": { "title": "t", "duration": "long", "resolution": { "width": 1, "height": 1 } },
+            "styles": { "base": { "font": { "size": "big" } } },
+            "lines": [
+                { "start": 0, "end": 1, "chars": [] },
+                { "start": 0, "end": 1, "chars": [{ "char": "a", "start": 0 }] },
+                { "start": 0, "end": 1, "chars": [{ "char": "a", "start": 0, "end": 1 }, { "char": 5, "start": 0, "end": 1 }] }
+            ]
+        }"##;
+        let diagnostics = validate_json(json);
+        let paths: Vec<&str> = diagnostics.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "$.project.duration",
+                "$.styles.base.font.size",
+                "$.lines[1].chars[0]",
+                "$.lines[2].chars[1].char",
+            ]
+        );
+        assert!(diagnostics[2].message.contains("missing field `end`"));
+
+        // Valid structure falls through to the semantic checks
+        let diagnostics = validate_json(DOC);
+        assert!(!find(&diagnostics, "$.styles.a.extends").is_empty());
+
+        let diagnostics = validate_json("{ \"version\": ");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "$");
+    }
+}
@@ -25,9 +25,13 @@ fn test_layout_color_parsing_optimization() {
                 x: Some(2.0),
                 y: Some(2.0),
                 blur: None,
+                inset: None,
             }),
             effects: Vec::new(),
             transform: None,
+            pitch: None,
+            ruby: None,
+            singer: None,
         })
         .collect();
 
@@ -37,14 +41,18 @@ fn test_layout_color_parsing_optimization() {
         end: 5.0,
         text: Some("AAAAA".to_string()),
         style: None,
+        singer: None,
         font: None,
         stroke: None,
         shadow: None,
+        mask: None,
         effects: Vec::new(),
         position: None,
         transform: None,
         layout: None,
         chars,
+        translations: Vec::new(),
+        track: None,
     };
 
     let style = Style::default();
@@ -30,7 +30,15 @@ fn test_render_shadow_optimization() {
         theme: None,
         styles: HashMap::new(),
         effects: HashMap::new(),
+        particle_presets: HashMap::new(),
         lines: Vec::new(),
+        imports: Vec::new(),
+        sections: Vec::new(),
+        singers: HashMap::new(),
+        tracks: HashMap::new(),
+        overlays: Vec::new(),
+        imported: Default::default(),
+        base_dir: None,
     };
 
     // Add style with shadow
@@ -40,6 +48,7 @@ fn test_render_shadow_optimization() {
         x: Some(2.0),
         y: Some(2.0),
         blur: Some(0.0),
+        inset: None,
     });
     doc.styles.insert("base".to_string(), style);
 
@@ -61,6 +70,9 @@ fn test_render_shadow_optimization() {
         shadow: None, // Uses style shadow
         effects: Vec::new(),
         transform: None,
+        pitch: None,
+        ruby: None,
+        singer: None,
     };
     line.chars.push(c);
     doc.lines.push(line);
@@ -20,6 +20,7 @@ fn test_stroke_reveal_with_repetitive_text() {
             opacity: 1.0,
         }),
         default_style: None,
+        sections: HashMap::new(),
     });
 
     // Define StrokeReveal effect
@@ -53,6 +54,9 @@ fn test_stroke_reveal_with_repetitive_text() {
             shadow: None,
             effects: Vec::new(),
             transform: None,
+            pitch: None,
+            ruby: None,
+            singer: None,
         })
         .collect();
 
@@ -21,6 +21,7 @@ fn test_render_buffer_integrity() {
             opacity: 1.0,
         }),
         default_style: None,
+        sections: Default::default(),
     });
 
     // Render
@@ -40,6 +40,8 @@ fn test_expression_transform() {
         active: true,
         char_index: Some(0),
         char_count: Some(5),
+        audio: Default::default(),
+        section: None,
     };
 
     // We need to match how EffectEngine constructs EvaluationContext.
@@ -86,6 +88,8 @@ fn test_typewriter_opacity() {
         active: true,
         char_index: Some(0),
         char_count: Some(10),
+        audio: Default::default(),
+        section: None,
     };
 
     let base = Transform::default();
@@ -107,6 +111,8 @@ fn test_typewriter_opacity() {
         active: true,
         char_index: Some(9), // Last char
         char_count: Some(10),
+        audio: Default::default(),
+        section: None,
     };
     let effects_vec = vec![effect.clone()];
     let result_mid = EffectEngine::compute_transform(0.5, base, &effects_vec, &ctx_mid);
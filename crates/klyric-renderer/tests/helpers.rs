@@ -110,7 +110,15 @@ pub fn minimal_doc() -> KLyricDocumentV2 {
         theme: None,
         styles: HashMap::new(),
         effects: HashMap::new(),
+        particle_presets: HashMap::new(),
         lines: Vec::new(),
+        imports: Vec::new(),
+        sections: Vec::new(),
+        singers: HashMap::new(),
+        tracks: HashMap::new(),
+        overlays: Vec::new(),
+        imported: Default::default(),
+        base_dir: None,
     }
 }
 
@@ -141,6 +149,9 @@ pub fn doc_with_line(text: &str, start: f64, end: f64) -> KLyricDocumentV2 {
                 shadow: None,
                 effects: Vec::new(),
                 transform: None,
+                pitch: None,
+                ruby: None,
+                singer: None,
             }
         })
         .collect();
@@ -151,14 +162,18 @@ pub fn doc_with_line(text: &str, start: f64, end: f64) -> KLyricDocumentV2 {
         end,
         text: Some(text.to_string()),
         style: Some("base".to_string()),
+        singer: None,
         font: None,
         stroke: None,
         shadow: None,
+        mask: None,
         effects: Vec::new(),
         position: None,
         transform: None,
         layout: None,
         chars,
+        translations: Vec::new(),
+        track: None,
     };
 
     let mut styles = HashMap::new();
@@ -177,22 +192,34 @@ pub fn doc_with_line(text: &str, start: f64, end: f64) -> KLyricDocumentV2 {
                 active: Some(FillStroke {
                     fill: Some("#FFFFFF".to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 }),
                 inactive: Some(FillStroke {
                     fill: Some("#888888".to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 }),
                 complete: Some(FillStroke {
                     fill: Some("#FFFFFF".to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 }),
             }),
             stroke: None,
             shadow: None,
+            strokes: None,
+            shadows: None,
             glow: None,
+            mask: None,
             transform: None,
             effects: None,
             layers: None,
+            ruby: None,
+            reset: Vec::new(),
+            append: Vec::new(),
         },
     );
 
@@ -216,7 +243,15 @@ pub fn doc_with_line(text: &str, start: f64, end: f64) -> KLyricDocumentV2 {
         theme: None,
         styles,
         effects: HashMap::new(),
+        particle_presets: HashMap::new(),
         lines: vec![line],
+        imports: Vec::new(),
+        sections: Vec::new(),
+        singers: HashMap::new(),
+        tracks: HashMap::new(),
+        overlays: Vec::new(),
+        imported: Default::default(),
+        base_dir: None,
     }
 }
 
@@ -242,6 +277,9 @@ pub fn doc_with_multiple_lines(lines_data: &[(&str, f64, f64)]) -> KLyricDocumen
                         shadow: None,
                         effects: Vec::new(),
                         transform: None,
+                        pitch: None,
+                        ruby: None,
+                        singer: None,
                     }
                 })
                 .collect();
@@ -252,14 +290,18 @@ pub fn doc_with_multiple_lines(lines_data: &[(&str, f64, f64)]) -> KLyricDocumen
                 end: *end,
                 text: Some(text.to_string()),
                 style: Some("base".to_string()),
+                singer: None,
                 font: None,
                 stroke: None,
                 shadow: None,
+                mask: None,
                 effects: Vec::new(),
                 position: None,
                 transform: None,
                 layout: None,
                 chars,
+                translations: Vec::new(),
+                track: None,
             }
         })
         .collect();
@@ -285,22 +327,34 @@ pub fn doc_with_multiple_lines(lines_data: &[(&str, f64, f64)]) -> KLyricDocumen
                 active: Some(FillStroke {
                     fill: Some("#FFFFFF".to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 }),
                 inactive: Some(FillStroke {
                     fill: Some("#888888".to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 }),
                 complete: Some(FillStroke {
                     fill: Some("#FFFFFF".to_string()),
                     stroke: None,
+                    gradient: None,
+                    pattern: None,
                 }),
             }),
             stroke: None,
             shadow: None,
+            strokes: None,
+            shadows: None,
             glow: None,
+            mask: None,
             transform: None,
             effects: None,
             layers: None,
+            ruby: None,
+            reset: Vec::new(),
+            append: Vec::new(),
         },
     );
 
@@ -324,6 +378,14 @@ pub fn doc_with_multiple_lines(lines_data: &[(&str, f64, f64)]) -> KLyricDocumen
         theme: None,
         styles,
         effects: HashMap::new(),
+        particle_presets: HashMap::new(),
+        imports: Vec::new(),
+        sections: Vec::new(),
+        singers: HashMap::new(),
+        tracks: HashMap::new(),
+        overlays: Vec::new(),
+        imported: Default::default(),
+        base_dir: None,
         lines,
     }
 }
@@ -350,7 +412,15 @@ pub fn doc_with_styles(styles: HashMap<String, Style>) -> KLyricDocumentV2 {
         theme: None,
         styles,
         effects: HashMap::new(),
+        particle_presets: HashMap::new(),
         lines: Vec::new(),
+        imports: Vec::new(),
+        sections: Vec::new(),
+        singers: HashMap::new(),
+        tracks: HashMap::new(),
+        overlays: Vec::new(),
+        imported: Default::default(),
+        base_dir: None,
     }
 }
 
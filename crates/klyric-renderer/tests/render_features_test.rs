@@ -0,0 +1,730 @@
+//! Pixel tests for styled and animated text
+//!
+//! Tests that need glyphs are skipped when no known system font is found.
+
+use klyric_renderer::renderer::RenderMode;
+use klyric_renderer::{parse_document, KLyricDocumentV2, Renderer};
+use std::path::Path;
+
+const WIDTH: u32 = 400;
+const HEIGHT: u32 = 200;
+
+/// A renderer with a system font loaded, and the font's family name
+fn setup_renderer() -> Option<(Renderer, String)> {
+    let font_paths = [
+        (
+            "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+            "DejaVu Sans",
+        ),
+        ("/usr/share/fonts/TTF/DejaVuSans.ttf", "DejaVu Sans"),
+        (
+            "/usr/share/fonts/truetype/liberation/LiberationSans-Regular.ttf",
+            "Liberation Sans",
+        ),
+        ("C:\\Windows\\Fonts\\arial.ttf", "Arial"),
+        ("/Library/Fonts/Arial.ttf", "Arial"),
+    ];
+
+    let mut renderer = Renderer::new(WIDTH, HEIGHT);
+    for (path, name) in font_paths {
+        if Path::new(path).exists() && renderer.text_renderer_mut().load_font(name, path).is_ok() {
+            return Some((renderer, name.to_string()));
+        }
+    }
+    println!("SKIPPING: no font available");
+    None
+}
+
+/// A line object with evenly timed chars; `extra` is spliced into it
+fn line_json(text: &str, start: f64, end: f64, extra: &str) -> String {
+    let step = (end - start) / text.chars().count() as f64;
+    let chars: Vec<String> = text
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let s = start + i as f64 * step;
+            format!(
+                r#"{{ "char": "{}", "start": {}, "end": {} }}"#,
+                c,
+                s,
+                s + step
+            )
+        })
+        .collect();
+    format!(
+        r#"{{ "text": "{}", "start": {}, "end": {}, "style": "base", "chars": [{}]{}{} }}"#,
+        text,
+        start,
+        end,
+        chars.join(", "),
+        if extra.is_empty() { "" } else { ", " },
+        extra
+    )
+}
+
+/// A document with a `base` style in `font`; `style` and `extra` are spliced
+/// into the style and document objects
+fn doc(font: &str, style: &str, lines: &[String], extra: &str) -> KLyricDocumentV2 {
+    let json = format!(
+        r#"{{
+            "version": "2.0",
+            "project": {{
+                "title": "Test",
+                "duration": 10.0,
+                "resolution": {{ "width": {}, "height": {} }}
+            }},
+            "styles": {{
+                "base": {{ "font": {{ "family": "{}", "size": 120.0 }}{}{} }}
+            }},
+            "lines": [{}]{}{}
+        }}"#,
+        WIDTH,
+        HEIGHT,
+        font,
+        if style.is_empty() { "" } else { ", " },
+        style,
+        lines.join(", "),
+        if extra.is_empty() { "" } else { ", " },
+        extra
+    );
+    parse_document(&json).expect("test document should parse")
+}
+
+/// Solid white text in every karaoke state
+const WHITE_TEXT: &str = r##""colors": {
+    "inactive": { "fill": "#FFFFFF" },
+    "active": { "fill": "#FFFFFF" },
+    "complete": { "fill": "#FFFFFF" }
+}"##;
+
+/// Solid black text, invisible against the default background
+const BLACK_TEXT: &str = r##""colors": {
+    "inactive": { "fill": "#000000" },
+    "active": { "fill": "#000000" },
+    "complete": { "fill": "#000000" }
+}"##;
+
+/// Count pixels matching a predicate on (r, g, b)
+fn count(pixels: &[u8], f: impl Fn(u8, u8, u8) -> bool) -> usize {
+    pixels
+        .chunks_exact(4)
+        .filter(|p| f(p[0], p[1], p[2]))
+        .count()
+}
+
+fn is_white(r: u8, g: u8, b: u8) -> bool {
+    r > 220 && g > 220 && b > 220
+}
+
+fn is_red(r: u8, g: u8, b: u8) -> bool {
+    r > 200 && g < 60 && b < 60
+}
+
+fn is_lit(r: u8, g: u8, b: u8) -> bool {
+    r > 10 || g > 10 || b > 10
+}
+
+/// A particle effect emitting one static particle per char every frame
+fn particle_effect(config: &str) -> String {
+    format!(
+        r#""effects": {{
+            "emit": {{
+                "type": "particle",
+                "trigger": "active",
+                "duration": 10.0,
+                "particleConfig": {{
+                    "count": 1,
+                    "spawnRate": 30.0,
+                    "lifetime": 10.0,
+                    "speed": 0.0,
+                    "shape": "square",
+                    "physics": {{ "gravity": 0.0 }},
+                    {}
+                }}
+            }}
+        }}"#,
+        config
+    )
+}
+
+#[test]
+fn test_behind_particles_draw_under_text_on_first_frame() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let effect = particle_effect(
+        r##""startSize": 1000.0, "endSize": 1000.0, "color": "#FF0000", "layer": "behind""##,
+    );
+    let doc = doc(
+        &font,
+        WHITE_TEXT,
+        &[line_json("I", 1.0, 10.0, r#""effects": ["emit"]"#)],
+        &effect,
+    );
+
+    // Nothing on screen yet, then the emitter is created and spawns in the
+    // same frame
+    renderer.render_frame(&doc, 0.5).unwrap();
+    let pixels = renderer.render_frame(&doc, 1.5).unwrap();
+
+    // The particle covers the frame but the text stays on top of it
+    assert!(count(&pixels, is_red) > 0, "behind particle was not drawn");
+    assert!(count(&pixels, is_white) > 0, "particle covered the text");
+}
+
+#[test]
+fn test_particle_bloom_adds_glow() {
+    let Some((mut sharp, font)) = setup_renderer() else {
+        return;
+    };
+    let Some((mut bloom, _)) = setup_renderer() else {
+        return;
+    };
+    let size = r##""startSize": 6.0, "endSize": 6.0, "color": "#FFFFFF""##;
+    let line = [line_json("I", 1.0, 10.0, r#""effects": ["emit"]"#)];
+    let sharp_doc = doc(&font, BLACK_TEXT, &line, &particle_effect(size));
+    let bloom_doc = doc(
+        &font,
+        BLACK_TEXT,
+        &line,
+        &particle_effect(&format!(
+            r#"{}, "filter": {{ "blur": 8.0, "bloom": 1.0 }}"#,
+            size
+        )),
+    );
+
+    sharp.render_frame(&sharp_doc, 0.5).unwrap();
+    bloom.render_frame(&bloom_doc, 0.5).unwrap();
+    let sharp_pixels = sharp.render_frame(&sharp_doc, 1.5).unwrap();
+    let bloom_pixels = bloom.render_frame(&bloom_doc, 1.5).unwrap();
+
+    let sharp_lit = count(&sharp_pixels, is_lit);
+    let bloom_lit = count(&bloom_pixels, is_lit);
+    assert!(sharp_lit > 0, "particle was not drawn");
+    assert!(
+        bloom_lit > sharp_lit * 2,
+        "bloom should spread a glow around the particle ({} vs {})",
+        bloom_lit,
+        sharp_lit
+    );
+}
+
+#[test]
+fn test_translation_does_not_copy_emitters() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let effect = particle_effect(r##""startSize": 4.0, "endSize": 4.0, "color": "#FF0000""##);
+    let doc = doc(
+        &font,
+        WHITE_TEXT,
+        &[line_json(
+            "I",
+            1.0,
+            10.0,
+            r#""effects": ["emit"], "translations": [{ "text": "A" }]"#,
+        )],
+        &effect,
+    );
+
+    renderer.render_frame(&doc, 0.5).unwrap();
+    renderer.render_frame(&doc, 1.5).unwrap();
+
+    // One emitter for the one char of the original line
+    assert_eq!(renderer.particle_stats().emitters, 1);
+}
+
+/// (r, g, b) of the pixel at `x`, `y`
+fn pixel(pixels: &[u8], x: u32, y: u32) -> (u8, u8, u8) {
+    let i = ((y * WIDTH + x) * 4) as usize;
+    (pixels[i], pixels[i + 1], pixels[i + 2])
+}
+
+#[test]
+fn test_section_gradient_background() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let doc = doc(
+        &font,
+        "",
+        &[],
+        r##""sections": [{ "name": "chorus", "start": 2.0, "end": 4.0 }],
+        "theme": {
+            "sections": {
+                "chorus": {
+                    "background": {
+                        "type": "gradient",
+                        "gradient": { "colors": ["#FF0000", "#0000FF"], "angle": 90.0 }
+                    }
+                }
+            }
+        }"##,
+    );
+
+    // Left to right, red to blue while the chorus plays
+    let pixels = renderer.render_frame(&doc, 3.0).unwrap();
+    let (r, _, b) = pixel(&pixels, 2, HEIGHT / 2);
+    assert!(
+        r > 200 && b < 60,
+        "left edge should be red, got {:?}",
+        (r, b)
+    );
+    let (r, _, b) = pixel(&pixels, WIDTH - 3, HEIGHT / 2);
+    assert!(
+        b > 200 && r < 60,
+        "right edge should be blue, got {:?}",
+        (r, b)
+    );
+
+    // Default black outside it
+    let pixels = renderer.render_frame(&doc, 1.0).unwrap();
+    assert_eq!(count(&pixels, is_lit), 0);
+}
+
+#[test]
+fn test_muted_track_only_hidden_in_preview() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let mut doc = doc(
+        &font,
+        WHITE_TEXT,
+        &[line_json("I", 1.0, 10.0, r#""track": "vocals""#)],
+        r#""tracks": { "vocals": {} }"#,
+    );
+    doc.tracks.get_mut("vocals").unwrap().muted = true;
+
+    // Export is the default mode and draws every track
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    assert!(count(&pixels, is_white) > 0, "export dropped a muted track");
+
+    renderer.set_render_mode(RenderMode::Preview);
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    assert_eq!(count(&pixels, is_white), 0, "preview drew a muted track");
+}
+
+/// Write a solid red PNG of `size` pixels to `path`
+fn write_red_png(path: &Path, size: i32) {
+    let mut surface = skia_safe::surfaces::raster_n32_premul((size, size)).unwrap();
+    surface.canvas().clear(skia_safe::Color::RED);
+    let png = surface
+        .image_snapshot()
+        .encode(None, skia_safe::EncodedImageFormat::PNG, None)
+        .unwrap();
+    std::fs::write(path, png.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_image_overlay_loads_once_file_appears() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("klyric-overlay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("logo.png");
+    let _ = std::fs::remove_file(&path);
+    let doc = doc(
+        &font,
+        "",
+        &[],
+        &format!(
+            r#""overlays": [{{
+                "type": "image", "start": 0.0, "end": 10.0, "src": {:?},
+                "width": 100.0, "height": 100.0
+            }}]"#,
+            path.to_str().unwrap()
+        ),
+    );
+
+    // Missing at first, then drawn once it's written
+    let pixels = renderer.render_frame(&doc, 1.0).unwrap();
+    assert_eq!(count(&pixels, is_red), 0);
+    write_red_png(&path, 8);
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // Stretched to 100x100
+    let red = count(&pixels, is_red);
+    assert!((9000..=11000).contains(&red), "{} red pixels", red);
+}
+
+#[test]
+fn test_svg_overlay() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("klyric-svg-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("badge.svg");
+    std::fs::write(
+        &path,
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <rect width="20" height="10" fill="#FF0000"/>
+                <text x="1" y="9" font-family="{}" font-size="9" fill="#FFFFFF">IIII</text>
+            </svg>"##,
+            font
+        ),
+    )
+    .unwrap();
+    let doc = doc(
+        &font,
+        "",
+        &[],
+        &format!(
+            r#""overlays": [{{
+                "type": "image", "start": 0.0, "end": 10.0, "src": {:?}, "width": 200.0
+            }}]"#,
+            path.to_str().unwrap()
+        ),
+    );
+
+    let pixels = renderer.render_frame(&doc, 1.0).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // Scaled to 200x100 keeping the aspect ratio, with its text on top
+    let red = count(&pixels, is_red);
+    assert!(red > 10000 && red < 20000, "{} red pixels", red);
+    assert!(count(&pixels, is_white) > 0, "SVG text was not drawn");
+}
+
+#[test]
+fn test_text_overlay_emits_particles() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let effect = particle_effect(
+        r##""startSize": 1000.0, "endSize": 1000.0, "color": "#FF0000", "layer": "behind""##,
+    );
+    let doc = doc(
+        &font,
+        WHITE_TEXT,
+        &[line_json("I", 0.0, 10.0, "")],
+        &format!(
+            r#"{}, "overlays": [{{
+                "start": 0.0, "end": 10.0, "text": "I", "style": "base", "effects": ["emit"]
+            }}]"#,
+            effect
+        ),
+    );
+
+    renderer.render_frame(&doc, 0.5).unwrap();
+    let pixels = renderer.render_frame(&doc, 1.0).unwrap();
+    assert!(count(&pixels, is_red) > 0, "overlay particle was not drawn");
+}
+
+/// White pixels left and right of `x`
+fn white_split(pixels: &[u8], x: u32) -> (usize, usize) {
+    let mut split = (0, 0);
+    for (i, p) in pixels.chunks_exact(4).enumerate() {
+        if is_white(p[0], p[1], p[2]) {
+            if (i as u32 % WIDTH) < x {
+                split.0 += 1;
+            } else {
+                split.1 += 1;
+            }
+        }
+    }
+    split
+}
+
+#[test]
+fn test_rect_mask_clips_text() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let plain = doc(&font, WHITE_TEXT, &[line_json("IIII", 1.0, 10.0, "")], "");
+    let masked = doc(
+        &font,
+        WHITE_TEXT,
+        &[line_json(
+            "IIII",
+            1.0,
+            10.0,
+            r#""mask": { "shape": "rect", "width": { "mode": "Fixed", "val": 0.5 } }"#,
+        )],
+        "",
+    );
+
+    // Centered text spans both halves of the frame
+    let (left, right) = white_split(&renderer.render_frame(&plain, 2.0).unwrap(), WIDTH / 2);
+    assert!(left > 0 && right > 0);
+
+    // The mask keeps only the left half of the line's bounds
+    let pixels = renderer.render_frame(&masked, 2.0).unwrap();
+    let (left, _) = white_split(&pixels, WIDTH / 2);
+    let (_, right) = white_split(&pixels, WIDTH / 2 + 4);
+    assert!(left > 0, "mask hid the whole line");
+    assert_eq!(right, 0, "text outside the mask was drawn");
+}
+
+fn is_blue(r: u8, g: u8, b: u8) -> bool {
+    b > 200 && r < 60 && g < 60
+}
+
+/// Text colors with `fill` as the fill of every karaoke state
+fn fill_colors(fill: &str) -> String {
+    format!(
+        r#""colors": {{
+            "inactive": {0},
+            "active": {0},
+            "complete": {0}
+        }}"#,
+        fill
+    )
+}
+
+const RED_TO_BLUE: &str =
+    r##"{ "gradient": { "colors": ["#FF0000", "#0000FF"], "angle": 90.0 } }"##;
+
+#[test]
+fn test_gradient_fill_paints_glyphs() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let line = [line_json("IIII", 1.0, 10.0, "")];
+    let across = doc(&font, &fill_colors(RED_TO_BLUE), &line, "");
+    let per_glyph = doc(
+        &font,
+        &fill_colors(
+            r##"{ "gradient": { "colors": ["#FF0000", "#0000FF"], "angle": 90.0, "span": "glyph" } }"##,
+        ),
+        &line,
+        "",
+    );
+
+    // Across the line: red on the left, blue on the right
+    let pixels = renderer.render_frame(&across, 2.0).unwrap();
+    assert_eq!(count(&pixels, is_white), 0, "solid fill drawn");
+    assert!(any_in_columns(&pixels, 0..WIDTH / 2, is_red));
+    assert!(any_in_columns(&pixels, WIDTH / 2..WIDTH, is_blue));
+    assert!(!any_in_columns(&pixels, WIDTH / 2..WIDTH, is_red));
+
+    // Per glyph: every glyph runs red to blue, on both halves
+    let pixels = renderer.render_frame(&per_glyph, 2.0).unwrap();
+    assert!(any_in_columns(&pixels, WIDTH / 2..WIDTH, is_red));
+    assert!(any_in_columns(&pixels, 0..WIDTH / 2, is_blue));
+}
+
+/// Whether any pixel in the columns `xs` matches a predicate on (r, g, b)
+fn any_in_columns(pixels: &[u8], mut xs: std::ops::Range<u32>, f: fn(u8, u8, u8) -> bool) -> bool {
+    xs.any(|x| {
+        (0..HEIGHT).any(|y| {
+            let (r, g, b) = pixel(pixels, x, y);
+            f(r, g, b)
+        })
+    })
+}
+
+#[test]
+fn test_pattern_fill_paints_glyphs() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let dir = std::env::temp_dir().join(format!("klyric-pattern-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tile.png");
+    write_red_png(&path, 8);
+    let fill = format!(
+        r#"{{ "pattern": {{ "src": {:?} }} }}"#,
+        path.to_str().unwrap()
+    );
+    let doc = doc(
+        &font,
+        &fill_colors(&fill),
+        &[line_json("IIII", 1.0, 10.0, "")],
+        "",
+    );
+
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(count(&pixels, is_red) > 0, "pattern was not drawn");
+    assert_eq!(count(&pixels, is_white), 0, "solid fill drawn");
+}
+
+#[test]
+fn test_karaoke_wipe_uses_gradient_fill() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let style = format!(
+        r##""colors": {{
+            "inactive": {{ "fill": "#FFFFFF" }},
+            "active": {}
+        }}"##,
+        RED_TO_BLUE
+    );
+    let doc = doc(
+        &font,
+        &style,
+        &[line_json("I", 1.0, 3.0, r#""effects": ["sweep"]"#)],
+        r#""effects": { "sweep": { "type": "karaoke", "mode": "wipe", "trigger": "active" } }"#,
+    );
+
+    // Halfway through the char: the sung part is the gradient, the rest
+    // is still white
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    let tinted = count(&pixels, |r, g, b| g < 60 && (r > 100 || b > 100));
+    assert!(tinted > 0, "wipe did not draw the gradient");
+    assert!(count(&pixels, is_white) > 0, "wipe covered the unsung part");
+}
+
+#[test]
+fn test_layered_strokes_draw_under_single_stroke() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let style = format!(
+        r##"{}, "stroke": {{ "width": 8.0, "color": "#0000FF" }},
+        "strokes": [{{ "width": 30.0, "color": "#FF0000" }}]"##,
+        BLACK_TEXT
+    );
+    let doc = doc(&font, &style, &[line_json("I", 1.0, 10.0, "")], "");
+
+    // The wide red outline shows around the blue one instead of covering it
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    assert!(count(&pixels, is_red) > 0, "layered stroke was not drawn");
+    assert!(
+        count(&pixels, is_blue) > 0,
+        "layered stroke covered the single one"
+    );
+}
+
+#[test]
+fn test_inset_shadow_draws_inside_glyphs() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let line = [line_json("I", 1.0, 10.0, "")];
+    let plain = doc(&font, WHITE_TEXT, &line, "");
+    let inset = doc(
+        &font,
+        &format!(
+            r##"{}, "shadow": {{ "color": "#FF0000", "x": 8.0, "y": 8.0, "blur": 0.0, "inset": true }}"##,
+            WHITE_TEXT
+        ),
+        &line,
+        "",
+    );
+
+    let glyph = renderer.render_frame(&plain, 2.0).unwrap();
+    let pixels = renderer.render_frame(&inset, 2.0).unwrap();
+    assert!(count(&pixels, is_red) > 0, "inner shadow was not drawn");
+    assert!(
+        count(&pixels, is_white) > 0,
+        "inner shadow covered the fill"
+    );
+    // Only where the glyph is
+    for (shaded, plain) in pixels.chunks_exact(4).zip(glyph.chunks_exact(4)) {
+        if is_red(shaded[0], shaded[1], shaded[2]) {
+            assert!(
+                is_lit(plain[0], plain[1], plain[2]),
+                "shadow outside the glyph"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_single_shadow_keeps_alpha_and_blur() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let shadow = |blur: f32| {
+        doc(
+            &font,
+            &format!(
+                r##"{}, "shadow": {{ "color": "#FF000080", "x": 30.0, "y": 0.0, "blur": {:?} }}"##,
+                BLACK_TEXT, blur
+            ),
+            &[line_json("I", 1.0, 10.0, "")],
+            "",
+        )
+    };
+
+    // Half-transparent red over black, never full red
+    let sharp = renderer.render_frame(&shadow(0.0), 2.0).unwrap();
+    let half_red = |r: u8, g: u8, b: u8| (100..160).contains(&r) && g < 20 && b < 20;
+    assert!(count(&sharp, half_red) > 0, "shadow lost its color");
+    assert_eq!(count(&sharp, is_red), 0, "shadow ignored its color's alpha");
+
+    // Its own blur spreads it
+    let blurred = renderer.render_frame(&shadow(8.0), 2.0).unwrap();
+    assert!(
+        count(&blurred, is_lit) > count(&sharp, is_lit) * 3 / 2,
+        "shadow ignored its blur"
+    );
+}
+
+/// A keyframe effect named `adjust` holding `keyframe` for the whole line
+fn adjust_effect(keyframe: &str) -> String {
+    format!(
+        r#""effects": {{
+            "adjust": {{
+                "type": "keyframe",
+                "duration": 10.0,
+                "keyframes": [
+                    {{ "time": 0.0, {0} }},
+                    {{ "time": 1.0, {0} }}
+                ]
+            }}
+        }}"#,
+        keyframe
+    )
+}
+
+const RED_TEXT: &str = r##""colors": {
+    "inactive": { "fill": "#FF0000" },
+    "active": { "fill": "#FF0000" },
+    "complete": { "fill": "#FF0000" }
+}"##;
+
+#[test]
+fn test_hue_shift_turns_red_text_green() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let doc = doc(
+        &font,
+        RED_TEXT,
+        &[line_json("I", 1.0, 10.0, r#""effects": ["adjust"]"#)],
+        &adjust_effect(r#""hueShift": 120.0"#),
+    );
+
+    let pixels = renderer.render_frame(&doc, 2.0).unwrap();
+    assert_eq!(count(&pixels, is_red), 0, "hue shift was not applied");
+    let green = count(&pixels, |r, g, b| g > 80 && r < 40 && b < 40);
+    assert!(green > 0, "text should turn green");
+}
+
+#[test]
+fn test_color_adjust_applies_to_glitch_channels() {
+    let Some((mut renderer, font)) = setup_renderer() else {
+        return;
+    };
+    let glitch = |keyframe: &str| {
+        doc(
+            &font,
+            WHITE_TEXT,
+            &[line_json("I", 1.0, 10.0, r#""effects": ["adjust"]"#)],
+            &adjust_effect(keyframe),
+        )
+    };
+
+    // The split channels are drawn instead of the fill
+    let pixels = renderer
+        .render_frame(&glitch(r#""glitchOffset": 6.0"#), 2.0)
+        .unwrap();
+    assert!(count(&pixels, is_lit) > 0, "glitch text was not drawn");
+
+    // And darkened like it
+    let pixels = renderer
+        .render_frame(&glitch(r#""glitchOffset": 6.0, "brightness": 0.0"#), 2.0)
+        .unwrap();
+    assert_eq!(
+        count(&pixels, is_lit),
+        0,
+        "glitch channels ignored brightness"
+    );
+}
@@ -0,0 +1,73 @@
+//! The JSON Schema against real documents, and the checked-in copy of it
+//!
+//! Regenerate `schema/klyric.schema.json` after a model change with
+//! `KLYRIC_UPDATE_SCHEMA=1 cargo test --test schema_test`.
+
+use klyric_renderer::importer::import_subtitle;
+use klyric_renderer::KLyricDocumentV2;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+fn repo_path(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+/// Schema errors for a document, one `path: message` per entry
+fn schema_errors(document: &Value) -> Vec<String> {
+    let schema = KLyricDocumentV2::json_schema();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    validator
+        .iter_errors(document)
+        .map(|e| format!("{}: {}", e.instance_path, e))
+        .collect()
+}
+
+#[test]
+fn test_schema_accepts_sample_document() {
+    let json = std::fs::read_to_string(repo_path("../../samples/sample.klyric")).unwrap();
+    let document: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(schema_errors(&document), Vec::<String>::new());
+
+    // And its re-serialized form
+    let doc = KLyricDocumentV2::from_json(&json).unwrap();
+    let written: Value = serde_json::from_str(&doc.to_json(false).unwrap()).unwrap();
+    assert_eq!(schema_errors(&written), Vec::<String>::new());
+}
+
+#[test]
+fn test_schema_accepts_imported_samples() {
+    for sample in ["sample.lrc", "sample.srt", "sample_karaoke.ass"] {
+        let content = std::fs::read_to_string(repo_path(&format!("../../samples/{}", sample)))
+            .unwrap_or_else(|e| panic!("{}: {}", sample, e));
+        let doc = import_subtitle(&content, Some(sample)).unwrap();
+        let written: Value = serde_json::from_str(&doc.to_json(false).unwrap()).unwrap();
+        assert_eq!(schema_errors(&written), Vec::<String>::new(), "{}", sample);
+    }
+}
+
+#[test]
+fn test_schema_rejects_malformed_document() {
+    let document = serde_json::json!({
+        "version": "2.0",
+        "project": { "title": "t", "duration": "long" },
+        "lines": [{ "start": 0 }]
+    });
+    assert!(!schema_errors(&document).is_empty());
+}
+
+#[test]
+fn test_checked_in_schema_is_current() {
+    let path = repo_path("schema/klyric.schema.json");
+    let generated = serde_json::to_string_pretty(&KLyricDocumentV2::json_schema()).unwrap() + "\n";
+    if std::env::var_os("KLYRIC_UPDATE_SCHEMA").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &generated).unwrap();
+        return;
+    }
+    let checked_in = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "schema/klyric.schema.json is out of date; regenerate it with \
+         KLYRIC_UPDATE_SCHEMA=1 cargo test --test schema_test"
+    );
+}